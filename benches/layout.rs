@@ -0,0 +1,76 @@
+//! compares rebuilding the whole stretch tree against the incremental layout tree
+//! on a view with several thousand widgets
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use sauron_native::{
+    stretch::{geometry::Size, number::Number},
+    widget::{
+        attribute::*,
+        layout::{compute_node_layout, LayoutTree},
+        *,
+    },
+    Node,
+};
+
+const ROWS: usize = 100;
+const COLUMNS: usize = 50;
+
+/// a view with ROWS * COLUMNS buttons, the row at `grown` takes twice the height of the others
+fn view(grown: usize) -> Node<()> {
+    column(
+        vec![],
+        (0..ROWS)
+            .map(|r| {
+                let flex = if r == grown { 2.0 } else { 1.0 };
+                row(
+                    vec![flex_grow(flex)],
+                    (0..COLUMNS)
+                        .map(|c| {
+                            button(vec![label(format!(
+                                "button {}",
+                                r * COLUMNS + c
+                            ))])
+                        })
+                        .collect(),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn window_size() -> Size<Number> {
+    Size {
+        width: Number::Defined(800.0),
+        height: Number::Defined(600.0),
+    }
+}
+
+fn layout_benchmark(c: &mut Criterion) {
+    c.bench_function("full rebuild", |b| {
+        b.iter_batched(
+            || view(1),
+            |mut new_view| compute_node_layout(&mut new_view, window_size()),
+            BatchSize::LargeInput,
+        )
+    });
+
+    c.bench_function("incremental update", |b| {
+        let mut old_view = view(0);
+        let mut layout_tree = LayoutTree::new();
+        layout_tree.rebuild(&old_view);
+        layout_tree.compute(&mut old_view, window_size());
+        // the views alternate, so each update changes the style of two rows
+        // from the view the layout tree was last updated with
+        let mut grown = 0;
+        b.iter_batched(
+            || {
+                grown = (grown + 1) % 2;
+                view(grown)
+            },
+            |mut new_view| layout_tree.update(&mut new_view, window_size()),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, layout_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,19 @@
+use sauron_native::markup::Markup;
+
+#[derive(Debug)]
+enum Msg {
+    Increment,
+    Decrement,
+}
+
+fn main() {
+    let markup = Markup::new()
+        .on("increment", |_| Msg::Increment)
+        .on("decrement", |_| Msg::Decrement);
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/markup.xml");
+    match markup.load_file(path) {
+        Ok(node) => println!("node: {:#?}", node),
+        Err(e) => eprintln!("error: {}", e),
+    }
+}
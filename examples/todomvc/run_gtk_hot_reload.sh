@@ -0,0 +1,6 @@
+#!/bin/bash
+
+set -v
+cargo build --features "with-hot-reload"
+SAURON_NATIVE_HOT_RELOAD=1 ../../target/debug/todomvc &
+cargo watch -x 'build --features with-hot-reload'
@@ -17,9 +17,12 @@ fn main() {
     #[cfg(feature = "with-titik")]
     TitikBackend::init(app::Model::new());
 
-    #[cfg(feature = "with-gtk")]
+    #[cfg(all(feature = "with-gtk", not(feature = "with-hot-reload")))]
     GtkBackend::init(app::Model::new());
 
+    #[cfg(feature = "with-hot-reload")]
+    GtkBackend::init_with_hot_reload(app::Model::new());
+
     #[cfg(feature = "with-nwg")]
     NwgBackend::init(app::Model::new());
 }
@@ -0,0 +1,46 @@
+//! Provides the `node!` macro which allows the widget tree of a sauron native
+//! view to be declared in an xml like syntax
+//!
+#![deny(clippy::all)]
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+mod node;
+
+/// Declare a widget tree in an xml like syntax, which is expanded into
+/// the builder functions of `sauron_native::widget`
+///
+/// - the tag is the name of the builder function, ie: `column`, `row`, `button`
+/// - attributes and events are the functions in `sauron_native::widget::attribute`
+///   and `sauron_native::widget::event`
+/// - attribute values are literals or rust expressions enclosed in braces
+/// - the charts `line_chart`, `bar_chart` and `scatter_plot` take their
+///   `series`, `x_axis` and `y_axis` as attributes
+/// - children are elements, a braced expression of a single node
+///   or a braced iterator of nodes prefixed with `..`
+///
+/// ```rust,ignore
+/// use sauron_native::node;
+///
+/// node! {
+///     <column>
+///         <button label="+" on_click={|_| Msg::Increment} />
+///         <text_label value={format!("count: {}", self.count)} />
+///         <row>
+///             {..self.items.iter().map(|item| item.view())}
+///         </row>
+///     </column>
+/// }
+/// ```
+///
+/// Unknown widgets, unknown attributes and children on a widget that is not
+/// a container are reported as compile errors.
+#[proc_macro]
+pub fn node(input: TokenStream) -> TokenStream {
+    let element = syn::parse_macro_input!(input as node::Element);
+    match element.to_node() {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,468 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    token, Error, Expr, Ident, Lit, Result, Token,
+};
+
+/// the widgets that can be used as tag, mapped to the builder function
+/// and whether it can have children.
+/// This must be kept in sync with `Widget::is_container`
+const WIDGETS: &[(&str, bool)] = &[
+    ("column", true),
+    ("row", true),
+    ("vpane", true),
+    ("hpane", true),
+    ("overlay", true),
+    ("groupbox", true),
+    ("grid", true),
+    ("header_bar", true),
+    ("menu_bar", true),
+    ("menu", true),
+    ("menu_item", true),
+    ("context_menu", true),
+    ("menu_separator", false),
+    ("button", false),
+    ("paragraph", false),
+    ("text_input", false),
+    ("checkbox", false),
+    ("radio", false),
+    ("image", false),
+    ("svg", false),
+    ("textarea", false),
+    ("text_label", false),
+    ("search_input", false),
+    ("link", false),
+    ("canvas", false),
+    ("line_chart", false),
+    ("bar_chart", false),
+    ("scatter_plot", false),
+];
+
+/// the chart widgets, which take their series and axes as arguments of the builder function,
+/// these are specified with the `series`, `x_axis` and `y_axis` attributes
+const CHARTS: &[&str] = &["line_chart", "bar_chart", "scatter_plot"];
+
+/// the arguments of the chart builder functions
+const CHART_ARGUMENTS: &[&str] = &["series", "x_axis", "y_axis"];
+
+/// the functions in `sauron_native::widget::attribute`
+const ATTRIBUTES: &[&str] = &[
+    "value",
+    "data",
+    "source",
+    "label",
+    "svg_image",
+    "editable",
+    "scrollable",
+    "width",
+    "height",
+    "position_type",
+    "flex_grow",
+    "flex_shrink",
+    "flex_basis",
+    "justify_content",
+    "align_items",
+    "align_self",
+    "flex_wrap",
+    "min_width",
+    "min_height",
+    "max_width",
+    "max_height",
+    "resizable",
+    "monospace",
+    "selectable",
+    "preformatted",
+    "name",
+    "uri",
+    "checked",
+    "placeholder",
+    "key",
+    "grid_rows",
+    "grid_columns",
+    "grid_row",
+    "grid_column",
+    "row_span",
+    "column_span",
+    "image_scaling",
+    "paused",
+    "looping",
+    "drawing",
+    "drag_data",
+    "drop_effect",
+    "disabled",
+    "accelerator",
+    "style",
+];
+
+/// the functions in `sauron_native::widget::event`
+const EVENTS: &[&str] = &[
+    "on_click",
+    "on_mousedown",
+    "on_mouseup",
+    "on_mousemove",
+    "on_doubleclick",
+    "on_blur",
+    "on_input",
+    "on_keypress",
+    "on_animation_end",
+    "on_chart_hover",
+    "on_drop",
+    "on_drag_end",
+];
+
+/// an element with its attributes and children
+/// `<name attr=value ...> children </name>` or `<name attr=value .../>`
+pub(crate) struct Element {
+    name: Ident,
+    attributes: Vec<Attribute>,
+    children: Vec<Child>,
+}
+
+/// `name=value`, where value is a literal or a braced expression
+struct Attribute {
+    name: Ident,
+    value: Expr,
+}
+
+enum Child {
+    /// a nested element
+    Element(Element),
+    /// `{expr}`, an expression which evaluates to a node
+    Node(Expr),
+    /// `{..expr}`, an expression which evaluates to an iterator of nodes
+    Nodes(Expr),
+}
+
+impl Child {
+    fn is_context_menu(&self) -> bool {
+        match self {
+            Child::Element(element) => element.name == "context_menu",
+            _ => false,
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            Child::Element(element) => element.name.span(),
+            Child::Node(expr) => expr.span(),
+            Child::Nodes(expr) => expr.span(),
+        }
+    }
+}
+
+impl Parse for Element {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<Token![<]>()?;
+        let name: Ident = input.parse()?;
+
+        let mut attributes = vec![];
+        while !(input.peek(Token![/]) || input.peek(Token![>])) {
+            attributes.push(input.parse()?);
+        }
+
+        if input.peek(Token![/]) {
+            input.parse::<Token![/]>()?;
+            input.parse::<Token![>]>()?;
+            return Ok(Element {
+                name,
+                attributes,
+                children: vec![],
+            });
+        }
+        input.parse::<Token![>]>()?;
+
+        let mut children = vec![];
+        while !(input.peek(Token![<]) && input.peek2(Token![/])) {
+            if input.is_empty() {
+                return Err(Error::new(
+                    name.span(),
+                    format!("unclosed element `{}`", name),
+                ));
+            }
+            children.push(input.parse()?);
+        }
+
+        input.parse::<Token![<]>()?;
+        input.parse::<Token![/]>()?;
+        let closing: Ident = input.parse()?;
+        if closing != name {
+            return Err(Error::new(
+                closing.span(),
+                format!(
+                    "closing tag `{}` does not match the opening tag `{}`",
+                    closing, name
+                ),
+            ));
+        }
+        input.parse::<Token![>]>()?;
+
+        Ok(Element {
+            name,
+            attributes,
+            children,
+        })
+    }
+}
+
+impl Parse for Attribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            content.parse()?
+        } else if input.peek(Lit) {
+            let lit: Lit = input.parse()?;
+            syn::parse_quote!(#lit)
+        } else if input.peek(Token![-]) && input.peek2(Lit) {
+            input.parse::<Token![-]>()?;
+            let lit: Lit = input.parse()?;
+            syn::parse_quote!(-#lit)
+        } else {
+            return Err(input.error(format!(
+                "expecting a literal or an expression enclosed in braces as the value of `{}`",
+                name
+            )));
+        };
+        Ok(Attribute { name, value })
+    }
+}
+
+impl Parse for Child {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![<]) {
+            Ok(Child::Element(input.parse()?))
+        } else if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            if content.peek(Token![..]) {
+                content.parse::<Token![..]>()?;
+                Ok(Child::Nodes(content.parse()?))
+            } else {
+                Ok(Child::Node(content.parse()?))
+            }
+        } else if input.peek(Lit) {
+            Err(input.error(
+                "text can not be used as a child, use the `value` or `label` attribute instead",
+            ))
+        } else {
+            Err(input.error(
+                "expecting an element, a `{node}` or a `{..nodes}` expression",
+            ))
+        }
+    }
+}
+
+impl Element {
+    /// expand this element into a call to its builder function
+    pub(crate) fn to_node(&self) -> Result<TokenStream> {
+        let name = &self.name;
+        let tag = name.to_string();
+        let is_container = WIDGETS
+            .iter()
+            .find(|(widget, _)| *widget == tag)
+            .map(|(_, is_container)| *is_container)
+            .ok_or_else(|| {
+                let widgets: Vec<&str> =
+                    WIDGETS.iter().map(|(widget, _)| *widget).collect();
+                Error::new(
+                    name.span(),
+                    format!(
+                        "unknown widget `{}`, expecting one of: {}",
+                        tag,
+                        widgets.join(", ")
+                    ),
+                )
+            })?;
+
+        if !is_container {
+            if let Some(child) =
+                self.children.iter().find(|child| !child.is_context_menu())
+            {
+                return Err(Error::new(
+                    child.span(),
+                    format!(
+                        "`{}` is not a container widget and can only have a `context_menu` child",
+                        tag
+                    ),
+                ));
+            }
+            let node = if tag == "paragraph" {
+                self.to_paragraph()?
+            } else if CHARTS.contains(&tag.as_str()) {
+                self.to_chart()?
+            } else {
+                self.to_leaf()?
+            };
+            // the widgets which are not containers get their context menu attached
+            return self.children.iter().try_fold(node, |node, child| {
+                let context_menu = child.to_node()?;
+                Ok(quote! {
+                    ::sauron_native::widget::with_context_menu(#node, #context_menu)
+                })
+            });
+        }
+
+        let attributes = self
+            .attributes
+            .iter()
+            .map(Attribute::to_attribute)
+            .collect::<Result<Vec<_>>>()?;
+
+        let builder =
+            quote_spanned!(name.span()=> ::sauron_native::widget::#name);
+        let children = self.children_to_vec()?;
+        Ok(quote! {
+            #builder(vec![#(#attributes),*], #children)
+        })
+    }
+
+    /// a widget which is not a container is built from its attributes only
+    fn to_leaf(&self) -> Result<TokenStream> {
+        let attributes = self
+            .attributes
+            .iter()
+            .map(Attribute::to_attribute)
+            .collect::<Result<Vec<_>>>()?;
+        let name = &self.name;
+        let builder =
+            quote_spanned!(name.span()=> ::sauron_native::widget::#name);
+        Ok(quote! {
+            #builder(vec![#(#attributes),*])
+        })
+    }
+
+    /// paragraph is built from a text instead of a list of attributes,
+    /// which is specified with the `value` attribute
+    fn to_paragraph(&self) -> Result<TokenStream> {
+        let mut value = None;
+        for attribute in self.attributes.iter() {
+            if attribute.name == "value" {
+                value = Some(&attribute.value);
+            } else {
+                return Err(Error::new(
+                    attribute.name.span(),
+                    format!(
+                        "`paragraph` only accepts the `value` attribute, found `{}`",
+                        attribute.name
+                    ),
+                ));
+            }
+        }
+        let name = &self.name;
+        let builder =
+            quote_spanned!(name.span()=> ::sauron_native::widget::#name);
+        match value {
+            Some(value) => Ok(quote! {
+                #builder(&::std::string::ToString::to_string(&(#value)))
+            }),
+            None => Ok(quote! { #builder("") }),
+        }
+    }
+
+    /// the chart is built from its series and axes, the axes default to
+    /// `Axis::default()` and the series to an empty list
+    fn to_chart(&self) -> Result<TokenStream> {
+        let argument = |name: &str| {
+            self.attributes
+                .iter()
+                .find(|attribute| attribute.name == name)
+                .map(|attribute| &attribute.value)
+        };
+        let series = match argument("series") {
+            Some(series) => quote! { #series },
+            None => quote! { ::std::vec::Vec::new() },
+        };
+        let axis = |name| match argument(name) {
+            Some(axis) => quote! { #axis },
+            None => quote! { ::std::default::Default::default() },
+        };
+        let (x_axis, y_axis) = (axis("x_axis"), axis("y_axis"));
+        let attributes = self
+            .attributes
+            .iter()
+            .filter(|attribute| {
+                !CHART_ARGUMENTS.iter().any(|arg| attribute.name == arg)
+            })
+            .map(Attribute::to_attribute)
+            .collect::<Result<Vec<_>>>()?;
+        let name = &self.name;
+        let builder =
+            quote_spanned!(name.span()=> ::sauron_native::widget::#name);
+        Ok(quote! {
+            #builder(vec![#(#attributes),*], #series, #x_axis, #y_axis)
+        })
+    }
+
+    fn children_to_vec(&self) -> Result<TokenStream> {
+        let has_iterator = self
+            .children
+            .iter()
+            .any(|child| matches!(child, Child::Nodes(_)));
+
+        if !has_iterator {
+            let children = self
+                .children
+                .iter()
+                .map(Child::to_node)
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(quote! { vec![#(#children),*] });
+        }
+
+        let mut statements = vec![];
+        for child in self.children.iter() {
+            match child {
+                Child::Nodes(expr) => {
+                    statements.push(quote! { children.extend(#expr); })
+                }
+                _ => {
+                    let node = child.to_node()?;
+                    statements.push(quote! { children.push(#node); })
+                }
+            }
+        }
+        Ok(quote! {
+            {
+                let mut children = ::std::vec::Vec::new();
+                #(#statements)*
+                children
+            }
+        })
+    }
+}
+
+impl Child {
+    fn to_node(&self) -> Result<TokenStream> {
+        match self {
+            Child::Element(element) => element.to_node(),
+            Child::Node(expr) => Ok(quote! { #expr }),
+            Child::Nodes(_) => unreachable!("iterators are extended"),
+        }
+    }
+}
+
+impl Attribute {
+    fn to_attribute(&self) -> Result<TokenStream> {
+        let name = &self.name;
+        let value = &self.value;
+        let key = name.to_string();
+        if EVENTS.contains(&key.as_str()) {
+            Ok(quote_spanned! {name.span()=>
+                ::sauron_native::widget::event::#name(#value)
+            })
+        } else if ATTRIBUTES.contains(&key.as_str()) {
+            Ok(quote_spanned! {name.span()=>
+                ::sauron_native::widget::attribute::#name(#value)
+            })
+        } else {
+            Err(Error::new(
+                name.span(),
+                format!("unknown attribute `{}`", key),
+            ))
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use sauron_native_macro::node;
+
+fn main() {
+    let _ = node! {
+        <column>
+            <button label="+" />
+        </row>
+    };
+}
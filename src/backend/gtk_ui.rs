@@ -1,23 +1,40 @@
 //! gtk backend
 use super::Dispatch;
-use crate::widget::layout::compute_node_layout;
-use crate::{AttribKey, Backend, Component, Node};
+#[cfg(feature = "with-hot-reload")]
+use crate::hot_reload::{self, WindowGeometry};
+use crate::inspector::Inspector;
+use crate::instrument::{self, Phase, Span};
+use crate::widget::layout::LayoutTree;
+use crate::{AttribKey, Backend, Component, Error, Node};
 use gio::{prelude::*, ApplicationFlags};
 pub use gtk;
 use gtk::{
     prelude::*, Application, ApplicationWindow, Button, CheckButton, Container,
-    Entry, EventBox, Frame, HeaderBar, Image, LinkButton, Menu, MenuBar,
-    MenuItem, Overlay, Paned, RadioButton, ScrolledWindow, SearchEntry,
-    TextView, WidgetExt,
+    DrawingArea, Entry, EventBox, Frame, Grid, HeaderBar, Image, Inhibit,
+    LinkButton, Menu, MenuBar, MenuItem, Overlay, Paned, RadioButton,
+    ScrolledWindow, SearchEntry, TextView, WidgetExt,
 };
+use inspector::InspectorWindow;
 use log::*;
+#[cfg(feature = "with-hot-reload")]
+use serde::{de::DeserializeOwned, Serialize};
 use std::{cell::RefCell, fmt::Debug, marker::PhantomData, rc::Rc};
 use stretch::geometry::Size;
 use stretch::number::Number;
 
+mod animation;
 mod apply_patches;
+mod canvas;
+mod clipboard;
+mod context_menu;
 mod convert_widget;
+mod drag_drop;
 mod images;
+mod inspector;
+mod menu;
+
+const DEFAULT_WINDOW_POSITION: (i32, i32) = (1000, 500);
+const DEFAULT_WINDOW_SIZE: (i32, i32) = (800, 600);
 
 /// backend using gtk
 pub struct GtkBackend<APP, MSG>
@@ -26,10 +43,13 @@ where
 {
     app: Rc<RefCell<APP>>,
     current_vdom: Rc<RefCell<Node<MSG>>>,
+    layout_tree: Rc<RefCell<LayoutTree>>,
     root_node: Rc<RefCell<Option<GtkWidget>>>,
     application: Application,
     application_window: Rc<RefCell<Option<ApplicationWindow>>>,
     window_size: Rc<RefCell<(i32, i32)>>,
+    inspector: Rc<RefCell<Inspector>>,
+    inspector_window: Rc<RefCell<Option<InspectorWindow>>>,
     _phantom_msg: PhantomData<MSG>,
 }
 
@@ -38,6 +58,7 @@ pub(crate) enum GtkWidget {
     GBox(gtk::Box),
     GBoxScrollable(ScrolledWindow),
     GroupBox(Frame),
+    Grid(Grid),
     Paned(Paned),
     Button(Button),
     LinkButton(LinkButton),
@@ -55,7 +76,11 @@ pub(crate) enum GtkWidget {
     MenuBar(MenuBar),
     Menu(Menu),
     MenuItem(MenuItem),
+    /// the context menu is not added to its parent,
+    /// but attached to it
+    ContextMenu(Menu),
     SearchInput(SearchEntry),
+    Canvas(DrawingArea),
 }
 
 impl<APP, MSG> Clone for GtkBackend<APP, MSG> {
@@ -63,10 +88,13 @@ impl<APP, MSG> Clone for GtkBackend<APP, MSG> {
         GtkBackend {
             app: Rc::clone(&self.app),
             current_vdom: Rc::clone(&self.current_vdom),
+            layout_tree: Rc::clone(&self.layout_tree),
             root_node: Rc::clone(&self.root_node),
             application: self.application.clone(),
             application_window: self.application_window.clone(),
             window_size: self.window_size.clone(),
+            inspector: Rc::clone(&self.inspector),
+            inspector_window: Rc::clone(&self.inspector_window),
             _phantom_msg: PhantomData,
         }
     }
@@ -77,16 +105,25 @@ where
     MSG: Debug + Clone + 'static,
     APP: Component<MSG> + 'static,
 {
-    fn new(app: APP) {
+    fn new(
+        app: APP,
+        window_position: (i32, i32),
+        window_size: (i32, i32),
+    ) -> Self {
         let app_title = app.title();
 
-        let (initial_width, initial_height) = (800, 600);
-        let current_vdom =
-            Self::calculate_view_layout(&app, (initial_width, initial_height));
+        let (initial_width, initial_height) = window_size;
+        let (initial_x, initial_y) = window_position;
+        let mut layout_tree = LayoutTree::new();
+        let current_vdom = Self::calculate_view_layout(
+            &app,
+            &mut layout_tree,
+            (initial_width, initial_height),
+        );
         let root_vdom = current_vdom.clone();
 
         if gtk::init().is_err() {
-            println!("failed to initialize GTK Application");
+            error!("failed to initialize GTK Application");
         }
         Self::setup_css();
         let application = Application::new(
@@ -99,10 +136,13 @@ where
         let backend = GtkBackend {
             app: Rc::new(RefCell::new(app)),
             current_vdom: Rc::new(RefCell::new(current_vdom)),
+            layout_tree: Rc::new(RefCell::new(layout_tree)),
             root_node: Rc::new(RefCell::new(root_widget)),
             application_window: Rc::new(RefCell::new(None)),
             application,
             window_size: Rc::new(RefCell::new((initial_width, initial_height))),
+            inspector: Rc::new(RefCell::new(Inspector::new())),
+            inspector_window: Rc::new(RefCell::new(None)),
             _phantom_msg: PhantomData,
         };
 
@@ -114,7 +154,7 @@ where
         backend.application.connect_activate(move |application| {
             let application_window = ApplicationWindow::new(application);
             application_window.set_default_size(initial_width, initial_height);
-            application_window.move_(1000, 500);
+            application_window.move_(initial_x, initial_y);
             application_window.set_icon_name(Some("applications-graphics"));
             application_window.set_title(&app_title);
             application_window.add(
@@ -128,30 +168,59 @@ where
             );
             let backend_clone2 = backend_clone.clone();
             application_window.connect_size_allocate(move |_win, rect| {
-                println!(
+                trace!(
                     "moved in ({},{}) resized to ({},{})",
-                    rect.x, rect.y, rect.width, rect.height
+                    rect.x,
+                    rect.y,
+                    rect.width,
+                    rect.height
                 );
                 *backend_clone2.window_size.borrow_mut() =
                     (rect.width, rect.height);
                 //backend_clone2.redraw();
             });
 
+            let backend_clone3 = backend_clone.clone();
+            application_window.connect_key_press_event(move |_win, event| {
+                let key_event = inspector::from_gdk_key_event(event);
+                if Inspector::is_toggle_key(&key_event) {
+                    backend_clone3.toggle_inspector();
+                    Inhibit(true)
+                } else {
+                    Inhibit(false)
+                }
+            });
+
             application_window.show_all();
+            *backend_clone.inspector_window.borrow_mut() =
+                Some(InspectorWindow::new(
+                    &application_window,
+                    Rc::clone(&backend_clone.inspector),
+                ));
             *backend_clone.application_window.borrow_mut() =
                 Some(application_window);
         });
 
-        backend.application.run(&[]);
+        backend
+    }
+
+    fn run(&self) {
+        self.application.run(&[]);
     }
 
-    fn calculate_view_layout(app: &APP, window_size: (i32, i32)) -> Node<MSG> {
+    fn calculate_view_layout(
+        app: &APP,
+        layout_tree: &mut LayoutTree,
+        window_size: (i32, i32),
+    ) -> Node<MSG> {
         let mut new_view = app.view();
+        crate::widget::evict_unused_lazy();
 
         let (w, h) = window_size;
         let (adjusted_w, adjusted_h) = (w as f32 - 100.0, h as f32 - 20.0);
 
-        compute_node_layout(
+        layout_tree.rebuild(&new_view);
+        layout_tree.compute(
             &mut new_view,
             Size {
                 width: Number::Defined(adjusted_w),
@@ -161,58 +230,139 @@ where
         new_view
     }
 
-    /// redraw the UI due to layout changes caused by resize on the main window
+    /// redraw the UI due to layout changes caused by resize on the main window,
+    /// the inspector shows the timings of this redraw alone
+    #[allow(unused)]
     fn redraw(&self)
     where
         MSG: Debug,
     {
-        let mut new_view = Self::calculate_view_layout(
-            &self.app.borrow(),
-            *self.window_size.borrow(),
-        );
+        self.inspector.borrow_mut().start_dispatch();
+        self.render_view();
+    }
+
+    /// render the view of the app, patching the widgets with the changes from the current view
+    fn render_view(&self)
+    where
+        MSG: Debug,
+    {
+        let span = Span::enter(Phase::View);
+        let mut new_view = self.app.borrow().view();
+        crate::widget::evict_unused_lazy();
+        let view_duration = span.finish();
         let (w, h) = *self.window_size.borrow();
         let (adjusted_w, adjusted_h) = (w as f32 - 0.0, h as f32 - 0.0);
 
-        compute_node_layout(
+        // only the subtrees affected by the changes in the view are recomputed
+        let span = Span::enter(Phase::ComputeLayout);
+        self.layout_tree.borrow_mut().update(
             &mut new_view,
             Size {
                 width: Number::Defined(adjusted_w),
                 height: Number::Defined(adjusted_h),
             },
         );
+        let layout_duration = span.finish();
         {
             let current_vdom = self.current_vdom.borrow();
+            let span = Span::enter(Phase::Diff);
             let diff = mt_dom::diff_with_key(
                 &current_vdom,
                 &new_view,
                 &AttribKey::Key,
             );
-            apply_patches::apply_patches(
-                self,
-                &current_vdom,
-                &self.root_container(),
-                &diff,
-            );
+            let diff_duration = span.finish();
+            instrument::record_patches(&diff);
+            let span = Span::enter(Phase::ApplyPatches);
+            let patched = self
+                .root_container()
+                .ok_or(Error::MissingNode(0))
+                .and_then(|root_container| {
+                    apply_patches::apply_patches(
+                        self,
+                        &current_vdom,
+                        &root_container,
+                        &diff,
+                    )
+                });
+            // the widgets are out of sync with the view,
+            // so the whole view is rendered again
+            if let Err(e) = patched {
+                error!("unable to apply the patches, re-rendering: {}", e);
+                self.rerender(&new_view);
+            }
+            let patch_duration = span.finish();
+
+            let mut inspector = self.inspector.borrow_mut();
+            inspector.record_timing("view", view_duration);
+            inspector.record_timing("layout", layout_duration);
+            inspector.record_timing("diff", diff_duration);
+            inspector.record_timing("apply_patches", patch_duration);
+            if inspector.is_enabled() {
+                inspector.record_patches(&diff);
+            }
         }
         *self.current_vdom.borrow_mut() = new_view;
+        self.update_inspector();
+    }
+
+    /// show or hide the inspector window
+    fn toggle_inspector(&self) {
+        self.inspector.borrow_mut().toggle();
+        self.update_inspector();
+    }
+
+    /// refresh the content of the inspector window when it is shown
+    fn update_inspector(&self) {
+        let inspector = self.inspector.borrow();
+        if let Some(inspector_window) = self.inspector_window.borrow().as_ref()
+        {
+            if inspector.is_enabled() {
+                inspector_window
+                    .set_text(&inspector.report(&self.current_vdom.borrow()));
+            }
+            inspector_window.set_visible(inspector.is_enabled());
+        }
+    }
+
+    /// replace the root widget with the widgets converted from the view
+    fn rerender(&self, view: &Node<MSG>) {
+        let root_widget = convert_widget::from_node_tree(self, view);
+        if let Some(application_window) =
+            self.application_window.borrow().as_ref()
+        {
+            if let Some(old_widget) = self
+                .root_node
+                .borrow()
+                .as_ref()
+                .and_then(|root_widget| root_widget.as_widget())
+            {
+                application_window.remove(old_widget);
+            }
+            if let Some(new_widget) = root_widget.as_widget() {
+                application_window.add(new_widget);
+            }
+            application_window.show_all();
+        }
+        *self.root_node.borrow_mut() = Some(root_widget);
     }
 
-    fn root_container(&self) -> Rc<Container> {
+    fn root_container(&self) -> Option<Rc<Container>> {
         let root_widget = self.root_node.borrow();
-        if let Some(root_widget) = &*root_widget {
-            match &root_widget {
-                GtkWidget::GBox(gbox) => {
-                    let container: &Container = gbox.upcast_ref();
-                    Rc::new(container.clone())
-                }
-                GtkWidget::Paned(paned) => {
-                    let container: &Container = paned.upcast_ref();
-                    Rc::new(container.clone())
-                }
-                _ => panic!("expecting it to be a container"),
+        match root_widget.as_ref()? {
+            GtkWidget::GBox(gbox) => {
+                let container: &Container = gbox.upcast_ref();
+                Some(Rc::new(container.clone()))
             }
-        } else {
-            panic!("must have a root widget");
+            GtkWidget::Paned(paned) => {
+                let container: &Container = paned.upcast_ref();
+                Some(Rc::new(container.clone()))
+            }
+            GtkWidget::Grid(grid) => {
+                let container: &Container = grid.upcast_ref();
+                Some(Rc::new(container.clone()))
+            }
+            _ => None,
         }
     }
 
@@ -243,7 +393,75 @@ where
     MSG: Clone + Debug + 'static,
 {
     fn init(app: APP) {
-        GtkBackend::new(app);
+        GtkBackend::new(app, DEFAULT_WINDOW_POSITION, DEFAULT_WINDOW_SIZE)
+            .run();
+    }
+}
+
+#[cfg(feature = "with-hot-reload")]
+impl<APP, MSG> GtkBackend<APP, MSG>
+where
+    MSG: Debug + Clone + 'static,
+    APP: Component<MSG> + Serialize + DeserializeOwned + 'static,
+{
+    /// initialize the backend, and when the hot reload is enabled with the
+    /// `SAURON_NATIVE_HOT_RELOAD` environment variable, restore the app state and
+    /// window geometry from the previous run and restart when the app is rebuilt
+    pub fn init_with_hot_reload(app: APP) {
+        if !hot_reload::is_enabled() {
+            return Self::init(app);
+        }
+        let (app, window) = match hot_reload::restore::<APP>() {
+            Some(snapshot) => (snapshot.app, snapshot.window),
+            None => (app, None),
+        };
+        let (window_position, window_size) = window
+            .map(|w| ((w.x, w.y), (w.width, w.height)))
+            .unwrap_or((DEFAULT_WINDOW_POSITION, DEFAULT_WINDOW_SIZE));
+
+        let backend = GtkBackend::new(app, window_position, window_size);
+        backend.watch_for_rebuild();
+        backend.run();
+    }
+
+    /// periodically check if the app is rebuilt, then save the state and restart
+    fn watch_for_rebuild(&self) {
+        let path = match hot_reload::watched_path() {
+            Ok(path) => path,
+            Err(e) => {
+                error!("unable to watch for rebuilds: {}", e);
+                return;
+            }
+        };
+        info!("hot reload is watching: {}", path.display());
+        let mut watcher = hot_reload::Watcher::new(path);
+        let backend = self.clone();
+        glib::timeout_add_local(500, move || {
+            if !watcher.poll() {
+                return glib::Continue(true);
+            }
+            info!("rebuild detected, restarting..");
+            let window = backend.window_geometry();
+            if let Err(e) = hot_reload::save(&*backend.app.borrow(), window) {
+                error!("unable to save the hot reload snapshot: {}", e);
+            } else if let Err(e) = hot_reload::restart() {
+                error!("unable to restart the app: {}", e);
+            }
+            glib::Continue(false)
+        });
+    }
+
+    fn window_geometry(&self) -> Option<WindowGeometry> {
+        let application_window = self.application_window.borrow();
+        let application_window = application_window.as_ref()?;
+        let (x, y) = application_window.get_position();
+        let (width, height) = application_window.get_size();
+        Some(WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+        })
     }
 }
 
@@ -256,8 +474,15 @@ where
     where
         MSG: Debug,
     {
+        self.inspector.borrow_mut().start_dispatch();
+        let span = Span::enter(Phase::Update);
         self.app.borrow_mut().update(msg);
-        self.redraw();
+        let update_duration = span.finish();
+        self.inspector
+            .borrow_mut()
+            .record_timing("update", update_duration);
+        self.render_view();
+        clipboard::run_requests(self);
     }
 }
 
@@ -288,6 +513,10 @@ impl GtkWidget {
                 let widget: &gtk::Widget = group_box.upcast_ref();
                 Some(widget)
             }
+            GtkWidget::Grid(grid) => {
+                let widget: &gtk::Widget = grid.upcast_ref();
+                Some(widget)
+            }
             GtkWidget::Paned(paned) => {
                 let widget: &gtk::Widget = paned.upcast_ref();
                 Some(widget)
@@ -344,10 +573,15 @@ impl GtkWidget {
                 let widget: &gtk::Widget = menu_item.upcast_ref();
                 Some(widget)
             }
+            GtkWidget::ContextMenu(_) => None,
             GtkWidget::SearchInput(entry) => {
                 let widget: &gtk::Widget = entry.upcast_ref();
                 Some(widget)
             }
+            GtkWidget::Canvas(drawing_area) => {
+                let widget: &gtk::Widget = drawing_area.upcast_ref();
+                Some(widget)
+            }
         }
     }
 }
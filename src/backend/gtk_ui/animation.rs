@@ -0,0 +1,169 @@
+//! plays the frames of an animated image on a glib timer
+//!
+//! The frames are decoded and scaled with the image crate instead of using a `PixbufAnimation`,
+//! since it can not be paused, played once, or notify when it has finished.
+use super::images;
+use crate::{image_util::AnimationFrame, widget::attribute::ImageScaling};
+use gdk_pixbuf::Pixbuf;
+use gtk::{Image, ImageExt, WidgetExt};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    time::Duration,
+};
+
+thread_local! {
+    /// the animations played in the image widgets, removed when the widget is destroyed
+    static ANIMATIONS: RefCell<HashMap<Image, Rc<Animation>>> =
+        RefCell::new(HashMap::new());
+}
+
+struct Animation {
+    image: Image,
+    frames: Vec<(Pixbuf, Duration)>,
+    current: Cell<usize>,
+    paused: Cell<bool>,
+    looping: Cell<bool>,
+    finished: Cell<bool>,
+    timer: Cell<Option<glib::SourceId>>,
+    on_end: RefCell<Rc<dyn Fn()>>,
+}
+
+/// play the frames in the image widget, scaled into the size of the widget
+pub(crate) fn play(
+    image: &Image,
+    frames: &[AnimationFrame],
+    (width, height): (f32, f32),
+    scaling: ImageScaling,
+    paused: bool,
+    looping: bool,
+    on_end: Box<dyn Fn()>,
+) {
+    let frames: Vec<(Pixbuf, Duration)> = frames
+        .iter()
+        .map(|frame| {
+            (
+                images::scaled_pixbuf(&frame.image, width, height, scaling),
+                frame.delay,
+            )
+        })
+        .collect();
+    image.set_from_pixbuf(Some(&frames[0].0));
+
+    let animation = Rc::new(Animation {
+        image: image.clone(),
+        frames,
+        current: Cell::new(0),
+        paused: Cell::new(paused),
+        looping: Cell::new(looping),
+        finished: Cell::new(false),
+        timer: Cell::new(None),
+        on_end: RefCell::new(Rc::from(on_end)),
+    });
+    if !paused {
+        animation.schedule();
+    }
+    let previous = ANIMATIONS.with(|animations| {
+        animations.borrow_mut().insert(image.clone(), animation)
+    });
+    match previous {
+        // the image is already stopped when it is destroyed
+        Some(previous) => previous.stop(),
+        None => {
+            image.connect_destroy(stop);
+        }
+    }
+}
+
+/// stop and remove the animation played in the image widget, if there is any
+pub(crate) fn stop(image: &Image) {
+    if let Some(animation) =
+        ANIMATIONS.with(|animations| animations.borrow_mut().remove(image))
+    {
+        animation.stop();
+    }
+}
+
+/// pause or resume the animation played in the image widget,
+/// a finished animation is played again from the start when resumed
+pub(crate) fn set_paused(image: &Image, paused: bool) {
+    if let Some(animation) = find(image) {
+        if animation.paused.replace(paused) == paused {
+            return;
+        }
+        if paused {
+            animation.stop();
+        } else {
+            if animation.finished.get() {
+                animation.show_frame(0);
+            }
+            animation.schedule();
+        }
+    }
+}
+
+/// replace what is called when the animation played in the image widget finishes
+pub(crate) fn set_on_end(image: &Image, on_end: Box<dyn Fn()>) {
+    if let Some(animation) = find(image) {
+        animation.on_end.replace(Rc::from(on_end));
+    }
+}
+
+/// set whether the animation played in the image widget starts over when it ends
+pub(crate) fn set_looping(image: &Image, looping: bool) {
+    if let Some(animation) = find(image) {
+        animation.looping.set(looping);
+    }
+}
+
+fn find(image: &Image) -> Option<Rc<Animation>> {
+    ANIMATIONS.with(|animations| animations.borrow().get(image).cloned())
+}
+
+impl Animation {
+    /// show the next frame after the delay of the current frame
+    fn schedule(self: &Rc<Self>) {
+        let delay = self.frames[self.current.get()].1;
+        let animation = Rc::clone(self);
+        let timer =
+            glib::timeout_add_local(delay.as_millis() as u32, move || {
+                animation.timer.set(None);
+                animation.advance();
+                glib::Continue(false)
+            });
+        if let Some(previous) = self.timer.replace(Some(timer)) {
+            glib::source_remove(previous);
+        }
+    }
+
+    fn advance(self: &Rc<Self>) {
+        let next = self.current.get() + 1;
+        if next < self.frames.len() {
+            self.show_frame(next);
+            self.schedule();
+        } else if self.looping.get() {
+            self.show_frame(0);
+            self.schedule();
+        } else {
+            // the last frame is kept displayed
+            self.finished.set(true);
+            // the callback is not borrowed while it is called,
+            // since the dispatched msg can patch the callback
+            let on_end = Rc::clone(&self.on_end.borrow());
+            on_end();
+        }
+    }
+
+    fn show_frame(&self, index: usize) {
+        self.current.set(index);
+        self.finished.set(false);
+        self.image.set_from_pixbuf(Some(&self.frames[index].0));
+    }
+
+    fn stop(&self) {
+        if let Some(timer) = self.timer.take() {
+            glib::source_remove(timer);
+        }
+    }
+}
@@ -1,48 +1,91 @@
-use super::convert_widget;
-use super::Dispatch;
+use super::{
+    animation, canvas, convert_widget, drag_drop, images, menu, Dispatch,
+    GtkWidget,
+};
 use crate::{
-    widget::attribute::util::is_scrollable, AttribKey, Attribute, Node, Patch,
+    widget::attribute::{
+        find_value,
+        util::{get_grid_cell, is_scrollable},
+    },
+    AttribKey, Attribute, Error, Node, Patch, Value,
 };
-use gdk_pixbuf::{PixbufLoader, PixbufLoaderExt};
 use gtk::{
-    prelude::*, Button, Container, ContainerExt, EventBox, Image, Label,
-    MenuItem, Overlay, TextView, Widget,
+    prelude::*, Button, Container, ContainerExt, DrawingArea, EventBox, Grid,
+    Image, Label, MenuItem, Overlay, TextView, Widget,
 };
-use std::{collections::HashMap, fmt::Debug};
+use std::{any::type_name, collections::HashMap, fmt::Debug};
 
 pub fn apply_patches<MSG, DSP>(
     program: &DSP,
     node: &Node<MSG>,
     root_container: &Container,
     patches: &Vec<Patch<MSG>>,
-) where
+) -> Result<(), Error>
+where
     MSG: Debug + 'static,
     DSP: Clone + Dispatch<MSG> + 'static,
 {
-    let nodes_to_patch = find_nodes(node, root_container, patches);
+    let nodes_to_patch = find_nodes(node, root_container, patches)?;
 
     for patch in patches {
         let patch_node_idx = patch.node_idx();
         let widget = nodes_to_patch
             .get(&patch_node_idx)
-            .expect("must have a node to patch");
+            .ok_or(Error::MissingNode(patch_node_idx))?;
         match patch {
-            Patch::AddAttributes(tag, _node_idx, attrs) => {
-                set_widget_attributes(tag, widget, attrs);
+            Patch::AddAttributes(tag, node_idx, attrs) => {
+                for att in attrs {
+                    if let AttribKey::DragData = att.name() {
+                        for value in att.get_plain() {
+                            drag_drop::set_drag_data(widget, value);
+                        }
+                    }
+                }
+                if let crate::Widget::Image = tag {
+                    let image: &Image = downcast(tag, widget)?;
+                    let old_attrs = find_node(node, *node_idx)
+                        .and_then(|old_node| old_node.get_attributes())
+                        .unwrap_or(&[]);
+                    let new_attrs = patched_attributes(old_attrs, attrs);
+                    // the image is decoded and scaled again from all of its attributes
+                    if attrs.iter().any(|att| is_image_content(att.name())) {
+                        let (width, height) =
+                            find_value(AttribKey::Layout, &new_attrs)
+                                .and_then(|v| v.as_layout())
+                                .map(|layout| {
+                                    (layout.size.width, layout.size.height)
+                                })
+                                .unwrap_or((-1.0, -1.0));
+                        convert_widget::set_image_content(
+                            program,
+                            image,
+                            &new_attrs,
+                            (width, height),
+                        );
+                        image.set_size_request(width as i32, height as i32);
+                    } else if attrs
+                        .iter()
+                        .any(|att| *att.name() == AttribKey::AnimationEndEvent)
+                    {
+                        animation::set_on_end(
+                            image,
+                            convert_widget::on_animation_end(
+                                program, &new_attrs,
+                            ),
+                        );
+                    }
+                }
+                set_widget_attributes(tag, widget, attrs)?;
             }
             Patch::AppendChildren(tag, _node_idx, nodes) => {
                 match tag {
                     crate::Widget::Overlay => {
-                        let overlay = widget
-                            .downcast_ref::<Overlay>()
-                            .expect("must be an overlay");
+                        let overlay: &Overlay = downcast(tag, widget)?;
                         for node in nodes {
                             if let Some(element) = node.as_element_ref() {
                                 let child =
                                     convert_widget::from_node(program, element);
-                                let widget = child
-                                    .as_widget()
-                                    .expect("must be a widget");
+                                let widget = as_widget(&element.tag, &child)?;
                                 //Note: overlay have different behavior when adding child widget
                                 overlay.add_overlay(widget);
                                 overlay.set_child_index(widget, -1);
@@ -51,19 +94,34 @@ pub fn apply_patches<MSG, DSP>(
                             }
                         }
                     }
-
+                    crate::Widget::Grid => {
+                        let grid: &Grid = downcast(tag, widget)?;
+                        for node in nodes {
+                            if let Some(element) = node.as_element_ref() {
+                                let child =
+                                    convert_widget::from_node(program, element);
+                                let widget = as_widget(&element.tag, &child)?;
+                                let cell =
+                                    get_grid_cell(element.get_attributes());
+                                grid.attach(
+                                    widget,
+                                    cell.column as i32,
+                                    cell.row as i32,
+                                    cell.column_span as i32,
+                                    cell.row_span as i32,
+                                );
+                                widget.show();
+                            }
+                        }
+                    }
                     _ => {
-                        let container = widget
-                            .downcast_ref::<Container>()
-                            .expect("must be a container");
+                        let container: &Container = downcast(tag, widget)?;
                         for node in nodes {
                             if let Some(element) = node.as_element_ref() {
                                 let child = convert_widget::from_node(
                                     program, &element,
                                 );
-                                let widget = child
-                                    .as_widget()
-                                    .expect("must be a widget");
+                                let widget = as_widget(&element.tag, &child)?;
                                 //Note: overlay have different behavior when adding child widget
                                 container.add(widget);
                                 widget.show();
@@ -72,9 +130,13 @@ pub fn apply_patches<MSG, DSP>(
                     }
                 }
             }
-            Patch::RemoveChildren(_tag, _node_idx, children_index) => {
+            Patch::RemoveChildren(tag, _node_idx, children_index) => {
                 if let Some(container) = widget.downcast_ref::<Container>() {
-                    let children = container.get_children();
+                    let mut children = container.get_children();
+                    // grid returns the children in reverse order of insertion
+                    if let crate::Widget::Grid = tag {
+                        children.reverse();
+                    }
                     for (i, _child) in children.iter().enumerate() {
                         if children_index.contains(&i) {
                             container.remove(&children[i]);
@@ -87,29 +149,54 @@ pub fn apply_patches<MSG, DSP>(
                 if let Some(new_element) = new_node.as_element_ref() {
                     let new_widget =
                         convert_widget::from_node(program, new_element);
-                    let new_widget =
-                        new_widget.as_widget().expect("must be a widget");
+                    let new_widget = as_widget(&new_element.tag, &new_widget)?;
                     root_container.add(new_widget);
                     new_widget.show();
                 }
             }
             _ => {
-                println!("container: {:?}", root_container);
-                println!("todo for: {:?}", patch);
+                log::trace!("container: {:?}", root_container);
+                log::warn!("todo for: {:?}", patch);
             }
         }
     }
+    Ok(())
+}
+
+/// cast the gtk widget into the type expected for the widget
+fn downcast<'a, T>(
+    tag: &crate::Widget,
+    widget: &'a Widget,
+) -> Result<&'a T, Error>
+where
+    T: IsA<Widget>,
+{
+    widget
+        .downcast_ref::<T>()
+        .ok_or_else(|| Error::WidgetMismatch {
+            widget: tag.clone(),
+            expected: type_name::<T>(),
+        })
+}
+
+fn as_widget<'a>(
+    tag: &crate::Widget,
+    gtk_widget: &'a GtkWidget,
+) -> Result<&'a Widget, Error> {
+    gtk_widget.as_widget().ok_or_else(|| Error::WidgetMismatch {
+        widget: tag.clone(),
+        expected: type_name::<Widget>(),
+    })
 }
 
 fn set_widget_attributes<MSG: 'static>(
     tag: &crate::Widget,
     widget: &Widget,
     attrs: &[&Attribute<MSG>],
-) {
+) -> Result<(), Error> {
     match tag {
         crate::Widget::Button => {
-            let button =
-                widget.downcast_ref::<Button>().expect("must be a button");
+            let button: &Button = downcast(tag, widget)?;
             for att in attrs {
                 for value in att.get_plain() {
                     match att.name() {
@@ -121,11 +208,27 @@ fn set_widget_attributes<MSG: 'static>(
                 }
             }
         }
+        crate::Widget::MenuItem => {
+            let menu_item: &MenuItem = downcast(tag, widget)?;
+            for att in attrs {
+                for value in att.get_plain() {
+                    match att.name() {
+                        AttribKey::Label => {
+                            menu::set_label(menu_item, &value.to_string())
+                        }
+                        AttribKey::Checked => {
+                            menu::set_checked(menu_item, value.as_bool())
+                        }
+                        AttribKey::Disabled => {
+                            menu_item.set_sensitive(!value.as_bool())
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
         crate::Widget::TextArea => {
-            let text_view =
-                widget.downcast_ref::<TextView>().unwrap_or_else(|| {
-                    panic!("must be a text_view, found: {:?}", widget)
-                });
+            let text_view: &TextView = downcast(tag, widget)?;
             for att in attrs {
                 for value in att.get_plain() {
                     match att.name() {
@@ -143,30 +246,58 @@ fn set_widget_attributes<MSG: 'static>(
                 }
             }
         }
+        crate::Widget::Image => {
+            let image: &Image = downcast(tag, widget)?;
+            for att in attrs {
+                for value in att.get_plain() {
+                    match att.name() {
+                        AttribKey::Paused => {
+                            animation::set_paused(image, value.as_bool())
+                        }
+                        AttribKey::Looping => {
+                            animation::set_looping(image, value.as_bool())
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+        crate::Widget::Canvas => {
+            let drawing_area: &DrawingArea = downcast(tag, widget)?;
+            for att in attrs {
+                for value in att.get_plain() {
+                    match (att.name(), value) {
+                        (AttribKey::Drawing, Value::Drawing(commands)) => {
+                            canvas::set_content(
+                                drawing_area,
+                                canvas::Content::Commands(commands.to_vec()),
+                            )
+                        }
+                        (AttribKey::Chart, Value::Chart(chart)) => {
+                            canvas::set_content(
+                                drawing_area,
+                                canvas::Content::Chart((**chart).clone()),
+                            )
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
         crate::Widget::Svg => {
-            let image = widget
-                .downcast_ref::<Image>()
-                .unwrap_or_else(|| panic!("must be an image {:?}", widget));
+            let image: &Image = downcast(tag, widget)?;
             for att in attrs {
                 for value in att.get_plain() {
                     match att.name() {
                         AttribKey::Data => {
                             if let Some(bytes) = value.as_bytes() {
-                                let pixbuf_loader =
-                                    PixbufLoader::new_with_mime_type(
-                                        "image/svg+xml",
-                                    )
-                                    .expect("error loader");
-                                pixbuf_loader
-                                    .write(bytes)
-                                    .expect("Unable to write svg data into pixbuf_loader");
-                                pixbuf_loader
-                                    .close()
-                                    .expect("error creating pixbuf");
-                                let pixbuf = pixbuf_loader.get_pixbuf();
-                                image.set_from_pixbuf(Some(
-                                    &pixbuf.expect("error in pixbuf_loader"),
-                                ));
+                                images::set_image(
+                                    image,
+                                    images::load_pixbuf(
+                                        bytes,
+                                        images::SVG_MIME_TYPE,
+                                    ),
+                                );
                             }
                         }
                         _ => (),
@@ -175,16 +306,12 @@ fn set_widget_attributes<MSG: 'static>(
             }
         }
         crate::Widget::Label => {
-            let event_box =
-                widget.downcast_ref::<EventBox>().unwrap_or_else(|| {
-                    panic!("must be an eventbox, found: {:?}", widget)
-                });
+            let event_box: &EventBox = downcast(tag, widget)?;
             let event_box_children = event_box.get_children();
-            let child1 =
-                event_box_children.get(0).expect("must have one child");
-            let label = child1.downcast_ref::<Label>().unwrap_or_else(|| {
-                panic!("must be a label, found: {:?}", widget)
-            });
+            let child1 = event_box_children
+                .get(0)
+                .ok_or_else(|| children_mismatch(tag, 1, 0))?;
+            let label: &Label = downcast(tag, child1)?;
             for att in attrs {
                 for value in att.get_plain() {
                     match att.name() {
@@ -195,8 +322,77 @@ fn set_widget_attributes<MSG: 'static>(
             }
         }
         _ => {
-            println!("todo for other widgets");
+            log::warn!("todo for other widgets");
+        }
+    }
+    Ok(())
+}
+
+/// whether the image has to be loaded again when the attribute changes
+fn is_image_content(key: &AttribKey) -> bool {
+    match key {
+        AttribKey::Source
+        | AttribKey::Data
+        | AttribKey::ImageScaling
+        | AttribKey::Layout => true,
+        _ => false,
+    }
+}
+
+/// the attributes of the node once the patched attributes are added,
+/// the patched attributes replace the old attributes of the same name
+fn patched_attributes<MSG>(
+    old_attrs: &[Attribute<MSG>],
+    patched: &[&Attribute<MSG>],
+) -> Vec<Attribute<MSG>>
+where
+    MSG: 'static,
+{
+    let kept = old_attrs
+        .iter()
+        .filter(|att| !patched.iter().any(|p| p.name() == att.name()));
+    let mut attrs = vec![];
+    for att in kept.chain(patched.iter().copied()) {
+        for value in att.get_plain() {
+            attrs.push(mt_dom::attr(att.name().clone(), value.clone()));
         }
+        for cb in att.get_callback() {
+            attrs.push(mt_dom::on(att.name().clone(), cb.clone()));
+        }
+    }
+    attrs
+}
+
+/// the node at `node_idx` in a depth first traversal of the tree
+fn find_node<MSG>(node: &Node<MSG>, node_idx: usize) -> Option<&Node<MSG>> {
+    fn find<'a, MSG>(
+        node: &'a Node<MSG>,
+        node_idx: usize,
+        cur_node_idx: &mut usize,
+    ) -> Option<&'a Node<MSG>> {
+        if *cur_node_idx == node_idx {
+            return Some(node);
+        }
+        for child in node.get_children().unwrap_or(&[]) {
+            *cur_node_idx += 1;
+            if let Some(found) = find(child, node_idx, cur_node_idx) {
+                return Some(found);
+            }
+        }
+        None
+    }
+    find(node, node_idx, &mut 0)
+}
+
+fn children_mismatch(
+    tag: &crate::Widget,
+    expected: usize,
+    found: usize,
+) -> Error {
+    Error::ChildrenMismatch {
+        widget: tag.clone(),
+        expected,
+        found,
     }
 }
 
@@ -204,7 +400,7 @@ fn find_nodes<MSG>(
     node: &Node<MSG>,
     container: &Container,
     patches: &[Patch<MSG>],
-) -> HashMap<usize, Widget>
+) -> Result<HashMap<usize, Widget>, Error>
 where
     MSG: 'static,
 {
@@ -212,8 +408,9 @@ where
     let mut cur_node_idx = 0;
 
     for patch in patches {
-        let tag = patch.tag().expect("must have a tag");
-        nodes_to_find.insert(patch.node_idx(), tag);
+        if let Some(tag) = patch.tag() {
+            nodes_to_find.insert(patch.node_idx(), tag);
+        }
     }
     find_nodes_recursive(node, container, &mut cur_node_idx, &nodes_to_find)
 }
@@ -223,61 +420,94 @@ fn find_nodes_recursive<MSG>(
     container: &Container,
     cur_node_idx: &mut usize,
     nodes_to_find: &HashMap<usize, &crate::Widget>,
-) -> HashMap<usize, Widget>
+) -> Result<HashMap<usize, Widget>, Error>
 where
     MSG: 'static,
 {
-    let tag = node.tag().expect("must have a tag");
     let mut nodes_to_patch: HashMap<usize, Widget> = HashMap::new();
+    let tag = match node.tag() {
+        Some(tag) => tag,
+        None => return Ok(nodes_to_patch),
+    };
 
     if let Some(_) = nodes_to_find.get(cur_node_idx) {
         let container_widget: Widget = container.clone().upcast();
         nodes_to_patch.insert(*cur_node_idx, container_widget);
     }
 
-    if tag.is_container() {
-        let node_children = node.get_children().expect("must have children");
+    let node_children = node.get_children().unwrap_or(&[]);
+    if !tag.is_container() {
+        // the context menus of the widget are not patched,
+        // the missing node re-renders the whole view
+        *cur_node_idx += descendant_count(node_children);
+        return Ok(nodes_to_patch);
+    }
 
-        let attrs = node.get_attributes().expect("must have have attributes");
-        let widget_children = get_widget_children(tag, container, &attrs);
+    // the context menus are attached to their parent and are not its children
+    let attrs = node.get_attributes().unwrap_or(&[]);
+    let widget_children = get_widget_children(tag, container, &attrs)?;
+    let shown_children = node_children
+        .iter()
+        .filter(|child| child.tag() != Some(&crate::Widget::ContextMenu))
+        .count();
 
-        assert_eq!(
-            node_children.len(),
+    if shown_children != widget_children.len() {
+        return Err(children_mismatch(
+            tag,
+            shown_children,
             widget_children.len(),
-            "must have the same children len, in widget: {:?}",
-            tag
-        );
-        for (child_node, widget_child) in
-            node_children.iter().zip(widget_children.iter())
-        {
-            *cur_node_idx += 1;
-            let child_tag = child_node.tag().expect("must have a child tag");
-            if let Some(_patch_tag) = nodes_to_find.get(&cur_node_idx) {
-                let child_attrs =
-                    child_node.get_attributes().expect("must have attributes");
-                let widget: Widget = get_actual_node_to_patch(
-                    child_tag,
-                    widget_child,
-                    &child_attrs,
-                );
-                nodes_to_patch.insert(*cur_node_idx, widget);
+        ));
+    }
+    let mut widget_children = widget_children.iter();
+    for child_node in node_children.iter() {
+        *cur_node_idx += 1;
+        if child_node.tag() == Some(&crate::Widget::ContextMenu) {
+            *cur_node_idx +=
+                descendant_count(child_node.get_children().unwrap_or(&[]));
+            continue;
+        }
+        let widget_child = match widget_children.next() {
+            Some(widget_child) => widget_child,
+            None => break,
+        };
+        let child_tag = match child_node.tag() {
+            Some(child_tag) => child_tag,
+            None => continue,
+        };
+        if let Some(_patch_tag) = nodes_to_find.get(&cur_node_idx) {
+            let child_attrs = child_node.get_attributes().unwrap_or(&[]);
+            let widget: Widget = get_actual_node_to_patch(
+                child_tag,
+                widget_child,
+                &child_attrs,
+            )?;
+            nodes_to_patch.insert(*cur_node_idx, widget);
+        }
+        match widget_child.downcast_ref::<Container>() {
+            Some(container) if child_tag.is_container() => {
+                let child_nodes_to_patch = find_nodes_recursive(
+                    child_node,
+                    container,
+                    cur_node_idx,
+                    nodes_to_find,
+                )?;
+                nodes_to_patch.extend(child_nodes_to_patch);
             }
-            if child_tag.is_container() {
-                if let Some(container) =
-                    widget_child.downcast_ref::<Container>()
-                {
-                    let child_nodes_to_patch = find_nodes_recursive(
-                        child_node,
-                        container,
-                        cur_node_idx,
-                        nodes_to_find,
-                    );
-                    nodes_to_patch.extend(child_nodes_to_patch);
-                }
+            _ => {
+                *cur_node_idx +=
+                    descendant_count(child_node.get_children().unwrap_or(&[]));
             }
         }
     }
-    nodes_to_patch
+    Ok(nodes_to_patch)
+}
+
+/// the number of the nodes in the tree of these nodes
+fn descendant_count<MSG>(nodes: &[Node<MSG>]) -> usize {
+    nodes
+        .iter()
+        .map(|node| 1 + descendant_count(node.get_children().unwrap_or(&[])))
+        .sum()
 }
 
 /// return the actual node to be patched
@@ -286,20 +516,20 @@ fn get_actual_node_to_patch<MSG>(
     child_tag: &crate::Widget,
     widget_child: &Widget,
     attrs: &[crate::Attribute<MSG>],
-) -> Widget
+) -> Result<Widget, Error>
 where
     MSG: 'static,
 {
-    match child_tag {
+    let widget = match child_tag {
         crate::Widget::TextArea => {
             if is_scrollable(&attrs) {
                 // ScrolledWindow -> TextArea
-                let scrolled_window = widget_child
-                    .downcast_ref::<gtk::ScrolledWindow>()
-                    .expect("must be a scrolled window container");
+                let scrolled_window: &gtk::ScrolledWindow =
+                    downcast(child_tag, widget_child)?;
                 let scrolled_window_children = scrolled_window.get_children();
-                let text_area =
-                    scrolled_window_children.get(0).expect("must have a child");
+                let text_area = scrolled_window_children
+                    .get(0)
+                    .ok_or_else(|| children_mismatch(child_tag, 1, 0))?;
                 let text_area: Widget = text_area.clone().upcast();
                 text_area
             } else {
@@ -310,22 +540,19 @@ where
         crate::Widget::Svg => {
             if is_scrollable(&attrs) {
                 // ScrolledWindow -> ViewPort -> Image
-                let scrolled_window = widget_child
-                    .downcast_ref::<gtk::ScrolledWindow>()
-                    .expect("must be a scrolled window container");
+                let scrolled_window: &gtk::ScrolledWindow =
+                    downcast(child_tag, widget_child)?;
 
                 let scrolled_window_children = scrolled_window.get_children();
                 let view_port = scrolled_window_children
                     .get(0)
-                    .expect("scrolled window must have a child");
-                let view_port = view_port
-                    .downcast_ref::<gtk::Viewport>()
-                    .expect("must be a viewport container");
+                    .ok_or_else(|| children_mismatch(child_tag, 1, 0))?;
+                let view_port: &gtk::Viewport = downcast(child_tag, view_port)?;
 
                 let view_port_children = view_port.get_children();
                 let svg_image = view_port_children
                     .get(0)
-                    .expect("view port must have svg image as child");
+                    .ok_or_else(|| children_mismatch(child_tag, 1, 0))?;
                 let svg_image: Widget = svg_image.clone().upcast();
                 svg_image
             } else {
@@ -337,7 +564,8 @@ where
             let widget: Widget = widget_child.clone().upcast();
             widget
         }
-    }
+    };
+    Ok(widget)
 }
 
 /// return the children of this widget
@@ -346,67 +574,58 @@ fn get_widget_children<MSG>(
     tag: &crate::Widget,
     container: &Container,
     attrs: &[crate::Attribute<MSG>],
-) -> Vec<Widget>
+) -> Result<Vec<Widget>, Error>
 where
     MSG: 'static,
 {
-    match *tag {
+    let children = match *tag {
         // special case for GroupBox since GroupBox have a frame wrapper
         // GroupBox(Frame(Box))
         crate::Widget::GroupBox => {
             let frame_children = container.get_children();
-            let gbox_widget =
-                frame_children.get(0).expect("must have one child");
-            let gbox = gbox_widget
-                .downcast_ref::<gtk::Box>()
-                .expect("must be a container");
+            let gbox_widget = frame_children
+                .get(0)
+                .ok_or_else(|| children_mismatch(tag, 1, 0))?;
+            let gbox: &gtk::Box = downcast(tag, gbox_widget)?;
             gbox.get_children()
         }
         crate::Widget::Vbox | crate::Widget::Hbox => {
             if is_scrollable(attrs) {
-                println!("VBOX is SCROLLABLE..");
-                container.downcast_ref::<gtk::ScrolledWindow>()
-                            .unwrap_or_else(|| {
-                                panic!(
-                                    "container must be a ScrolledWindow, but found: {:?}",
-                                    container
-                                )
-                            });
+                log::trace!("VBOX is SCROLLABLE..");
+                if !container.is::<gtk::ScrolledWindow>() {
+                    return Err(Error::WidgetMismatch {
+                        widget: tag.clone(),
+                        expected: type_name::<gtk::ScrolledWindow>(),
+                    });
+                }
 
-                // ScrolledWindow -> VBox
+                // ScrolledWindow -> ViewPort -> VBox
                 let scrolled_children = container.get_children();
-                assert_eq!(
-                    scrolled_children.len(),
-                    1,
-                    "There should only be one children.. that is the real vbox"
-                );
-                let scrolled_widget =
-                    scrolled_children.get(0).expect("must have one child");
-                let view_port = scrolled_widget
-                    .downcast_ref::<gtk::Viewport>()
-                    .unwrap_or_else(|| {
-                        panic!(
-                            "must be a view port, but found: {:?}",
-                            scrolled_widget
-                        )
-                    });
+                if scrolled_children.len() != 1 {
+                    return Err(children_mismatch(
+                        tag,
+                        1,
+                        scrolled_children.len(),
+                    ));
+                }
+                let view_port: &gtk::Viewport =
+                    downcast(tag, &scrolled_children[0])?;
                 let viewport_children = view_port.get_children();
-                let box_widget =
-                    viewport_children.get(0).expect("must have 1 child");
-
-                let box_container = box_widget
-                    .downcast_ref::<gtk::Box>()
-                    .unwrap_or_else(|| {
-                        panic!(
-                            "must be a gtk::Box.. but found: {:?}",
-                            box_widget,
-                        )
-                    });
+                let box_widget = viewport_children
+                    .get(0)
+                    .ok_or_else(|| children_mismatch(tag, 1, 0))?;
+                let box_container: &gtk::Box = downcast(tag, box_widget)?;
                 box_container.get_children()
             } else {
                 container.get_children()
             }
         }
+        // gtk::Grid returns the children with the last attached child first
+        crate::Widget::Grid => {
+            let mut children = container.get_children();
+            children.reverse();
+            children
+        }
         // special case for SubMenu in MenuItem since
         // sub_menu is not returned as children for the menu_item
         // instead, we get the sub menu as part of it's children
@@ -415,14 +634,25 @@ where
         // so as to match the NodeIdx arrangement when applying patches
         crate::Widget::MenuItem => {
             let mut widgets = container.get_children();
-            let menu_item = container
-                .downcast_ref::<MenuItem>()
-                .expect("must be castable to menu item");
+            let menu_item =
+                container.downcast_ref::<MenuItem>().ok_or_else(|| {
+                    Error::WidgetMismatch {
+                        widget: tag.clone(),
+                        expected: type_name::<MenuItem>(),
+                    }
+                })?;
+            // the label of the menu item is not one of its children
+            if find_value(AttribKey::Label, attrs).is_some()
+                && !widgets.is_empty()
+            {
+                widgets.remove(0);
+            }
             if let Some(sub_menu) = menu_item.get_submenu() {
                 widgets.push(sub_menu);
             }
             widgets
         }
         _ => container.get_children(),
-    }
+    };
+    Ok(children)
 }
@@ -0,0 +1,222 @@
+//! draws the commands of a canvas into a `DrawingArea` with cairo
+use super::images;
+use crate::{
+    image_util,
+    widget::{
+        attribute::{find_value, ImageScaling},
+        canvas::{self, Color, DrawCommand},
+        chart::{Chart, ChartPoint},
+    },
+    AttribKey, Attribute,
+};
+use gdk::prelude::GdkContextExt;
+use gtk::{DrawingArea, Inhibit, WidgetExt};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// what is drawn in a canvas
+pub(crate) enum Content {
+    Commands(Vec<DrawCommand>),
+    /// the chart is drawn in the allocated size of the canvas,
+    /// so it follows the canvas as it is resized
+    Chart(Chart),
+}
+
+impl Content {
+    /// the chart of the canvas if it has one, otherwise its drawing commands
+    pub(crate) fn from_attrs<MSG>(attrs: &[Attribute<MSG>]) -> Self
+    where
+        MSG: 'static,
+    {
+        if let Some(chart) =
+            find_value(AttribKey::Chart, attrs).and_then(|v| v.as_chart())
+        {
+            Content::Chart(chart.clone())
+        } else {
+            Content::Commands(
+                find_value(AttribKey::Drawing, attrs)
+                    .and_then(|v| v.as_drawing())
+                    .map(|commands| commands.to_vec())
+                    .unwrap_or_default(),
+            )
+        }
+    }
+}
+
+type Drawing = Rc<RefCell<Content>>;
+
+thread_local! {
+    /// the content of the canvases, removed when the canvas is destroyed
+    static DRAWINGS: RefCell<HashMap<DrawingArea, Drawing>> =
+        RefCell::new(HashMap::new());
+}
+
+/// create a drawing area which draws the content
+pub(crate) fn drawing_area(content: Content) -> DrawingArea {
+    let drawing_area = DrawingArea::new();
+    let content = Rc::new(RefCell::new(content));
+    let content_clone = Rc::clone(&content);
+    drawing_area.connect_draw(move |drawing_area, cr| {
+        match &*content_clone.borrow() {
+            Content::Commands(commands) => draw(cr, commands),
+            Content::Chart(chart) => {
+                let (width, height) = allocated_size(drawing_area);
+                draw(cr, &chart.draw(width, height))
+            }
+        }
+        Inhibit(false)
+    });
+    drawing_area.connect_destroy(|drawing_area| {
+        DRAWINGS.with(|drawings| drawings.borrow_mut().remove(drawing_area));
+    });
+    DRAWINGS.with(|drawings| {
+        drawings.borrow_mut().insert(drawing_area.clone(), content)
+    });
+    drawing_area
+}
+
+/// replace the content of the canvas and draw it again
+pub(crate) fn set_content(drawing_area: &DrawingArea, content: Content) {
+    if let Some(drawing) = find(drawing_area) {
+        *drawing.borrow_mut() = content;
+        drawing_area.queue_draw();
+    }
+}
+
+/// the data point of the chart in the canvas nearest to the position
+pub(crate) fn nearest_point(
+    drawing_area: &DrawingArea,
+    position: (f64, f64),
+) -> Option<ChartPoint> {
+    let drawing = find(drawing_area)?;
+    let content = drawing.borrow();
+    match &*content {
+        Content::Chart(chart) => {
+            chart.nearest(position, allocated_size(drawing_area))
+        }
+        Content::Commands(_) => None,
+    }
+}
+
+fn find(drawing_area: &DrawingArea) -> Option<Drawing> {
+    DRAWINGS.with(|drawings| drawings.borrow().get(drawing_area).cloned())
+}
+
+fn allocated_size(drawing_area: &DrawingArea) -> (f32, f32) {
+    (
+        drawing_area.get_allocated_width() as f32,
+        drawing_area.get_allocated_height() as f32,
+    )
+}
+
+/// the styles of cairo which are not part of its saved state
+#[derive(Clone, Default)]
+struct Styles {
+    fill: Color,
+    stroke: Color,
+}
+
+fn draw(cr: &cairo::Context, commands: &[DrawCommand]) {
+    let mut styles = Styles::default();
+    let mut saved = vec![];
+    for command in commands {
+        match command {
+            DrawCommand::BeginPath => cr.new_path(),
+            DrawCommand::MoveTo(x, y) => cr.move_to(*x, *y),
+            DrawCommand::LineTo(x, y) => cr.line_to(*x, *y),
+            DrawCommand::BezierCurveTo { cp1, cp2, x, y } => {
+                cr.curve_to(cp1.0, cp1.1, cp2.0, cp2.1, *x, *y)
+            }
+            DrawCommand::Arc {
+                x,
+                y,
+                radius,
+                start_angle,
+                end_angle,
+            } => cr.arc(
+                *x,
+                *y,
+                *radius,
+                *start_angle,
+                canvas::clockwise_end_angle(*start_angle, *end_angle),
+            ),
+            DrawCommand::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => cr.rectangle(*x, *y, *width, *height),
+            DrawCommand::ClosePath => cr.close_path(),
+            DrawCommand::Fill => {
+                set_source_color(cr, &styles.fill);
+                cr.fill_preserve();
+            }
+            DrawCommand::Stroke => {
+                set_source_color(cr, &styles.stroke);
+                cr.stroke_preserve();
+            }
+            DrawCommand::FillStyle(color) => styles.fill = *color,
+            DrawCommand::StrokeStyle(color) => styles.stroke = *color,
+            DrawCommand::LineWidth(line_width) => {
+                cr.set_line_width(*line_width)
+            }
+            DrawCommand::Text { x, y, text, size } => {
+                // the path is not part of the saved state of cairo
+                let path = cr.copy_path();
+                cr.new_path();
+                cr.save();
+                set_source_color(cr, &styles.fill);
+                cr.set_font_size(*size);
+                cr.move_to(*x, *y);
+                cr.show_text(text);
+                cr.restore();
+                cr.new_path();
+                cr.append_path(&path);
+            }
+            DrawCommand::Image {
+                x,
+                y,
+                width,
+                height,
+                source,
+            } => match image_util::source_frames(source) {
+                Ok(frames) => {
+                    let pixbuf = images::scaled_pixbuf(
+                        &frames[0].image,
+                        *width as f32,
+                        *height as f32,
+                        ImageScaling::Stretch,
+                    );
+                    let path = cr.copy_path();
+                    cr.new_path();
+                    cr.save();
+                    cr.set_source_pixbuf(&pixbuf, *x, *y);
+                    cr.rectangle(*x, *y, *width, *height);
+                    cr.fill();
+                    cr.restore();
+                    cr.append_path(&path);
+                }
+                Err(e) => {
+                    log::warn!("unable to draw the image {:?}: {}", source, e)
+                }
+            },
+            DrawCommand::Save => {
+                saved.push(styles.clone());
+                cr.save();
+            }
+            DrawCommand::Restore => {
+                if let Some(restored) = saved.pop() {
+                    styles = restored;
+                    cr.restore();
+                }
+            }
+            DrawCommand::Translate(tx, ty) => cr.translate(*tx, *ty),
+            DrawCommand::Scale(sx, sy) => cr.scale(*sx, *sy),
+            DrawCommand::Rotate(angle) => cr.rotate(*angle),
+        }
+    }
+}
+
+fn set_source_color(cr: &cairo::Context, color: &Color) {
+    let (red, green, blue, alpha) = color.to_f64();
+    cr.set_source_rgba(red, green, blue, alpha);
+}
@@ -0,0 +1,79 @@
+//! carries out the clipboard requests with the gtk clipboard
+use super::{images, Dispatch};
+use crate::{
+    clipboard::{self, Reader, Request},
+    image_util, Value,
+};
+use gtk::Clipboard;
+
+/// carry out the clipboard requests queued in the update,
+/// the content which is read is dispatched as a msg later on
+pub(crate) fn run_requests<MSG, DSP>(program: &DSP)
+where
+    MSG: 'static,
+    DSP: Clone + Dispatch<MSG> + 'static,
+{
+    let requests = clipboard::take_requests::<MSG>();
+    if requests.is_empty() {
+        return;
+    }
+    let gtk_clipboard = Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+    for request in requests {
+        match request {
+            Request::Write(content) => write(&gtk_clipboard, content),
+            Request::Read(reader) => read(&gtk_clipboard, program, reader),
+        }
+    }
+}
+
+fn write(gtk_clipboard: &Clipboard, content: Value) {
+    match content {
+        Value::String(text) => gtk_clipboard.set_text(&text),
+        Value::Str(text) => gtk_clipboard.set_text(text),
+        Value::Bytes(bytes) => {
+            match image_util::decode(&bytes) {
+                Ok(image) => {
+                    gtk_clipboard.set_image(&images::pixbuf_from_rgba(image))
+                }
+                Err(e) => {
+                    log::warn!("unable to put the image on the clipboard: {}", e)
+                }
+            }
+        }
+        _ => log::warn!(
+            "only text and images can be put on the clipboard, not {}",
+            content
+        ),
+    }
+}
+
+/// the targets are requested first, so only the image or the text is requested,
+/// without blocking on the application which owns the clipboard
+fn read<MSG, DSP>(
+    gtk_clipboard: &Clipboard,
+    program: &DSP,
+    reader: Reader<MSG>,
+)
+where
+    MSG: 'static,
+    DSP: Clone + Dispatch<MSG> + 'static,
+{
+    let program = program.clone();
+    let emit = move |content: Option<Value>| {
+        program.dispatch(reader.msg(content));
+    };
+    gtk_clipboard.request_targets(move |gtk_clipboard, targets| {
+        if gtk::targets_include_image(targets, false) {
+            gtk_clipboard.request_image(move |_, pixbuf| {
+                let png = pixbuf.save_to_bufferv("png", &[]).map_err(|e| {
+                    log::warn!("unable to encode the clipboard image: {}", e)
+                });
+                emit(png.ok().map(Value::Bytes))
+            });
+        } else {
+            gtk_clipboard.request_text(move |_, text| {
+                emit(text.map(|text| Value::String(text.to_string())))
+            });
+        }
+    });
+}
@@ -0,0 +1,45 @@
+//! pops up the context menu of a widget on a right click, the menu key or a long press
+//!
+//! Only the widgets with their own window, such as the buttons, the labels and the inputs,
+//! receive the clicks, so the context menu of a box pops up on its children.
+use gtk::{prelude::*, GestureLongPress, Inhibit, Menu};
+
+/// attach the context menu to the widget
+pub(crate) fn attach(widget: &gtk::Widget, menu: &Menu) {
+    menu.set_attach_widget(Some(widget));
+    menu.show_all();
+
+    widget.add_events(gdk::EventMask::BUTTON_PRESS_MASK);
+    let menu_clone = menu.clone();
+    widget.connect_button_press_event(move |_, event| {
+        if event.triggers_context_menu() {
+            menu_clone.popup_at_pointer(Some(&**event));
+            Inhibit(true)
+        } else {
+            Inhibit(false)
+        }
+    });
+
+    let menu_clone = menu.clone();
+    widget.connect_popup_menu(move |widget| {
+        menu_clone.popup_at_widget(
+            widget,
+            gdk::Gravity::Center,
+            gdk::Gravity::NorthWest,
+            None,
+        );
+        true
+    });
+
+    // the long press is for the touch screens, the mouse is still able to drag
+    let long_press = GestureLongPress::new(widget);
+    long_press.set_touch_only(true);
+    let menu_clone = menu.clone();
+    long_press.connect_pressed(move |_, _, _| {
+        menu_clone.popup_at_pointer(None);
+    });
+    // the gesture is kept until its widget is destroyed
+    widget.connect_destroy(move |_| {
+        long_press.reset();
+    });
+}
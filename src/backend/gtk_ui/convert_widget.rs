@@ -1,21 +1,25 @@
+use super::animation;
+use super::canvas;
+use super::context_menu;
+use super::drag_drop;
 use super::images;
+use super::menu;
 use super::Dispatch;
 use super::GtkWidget;
-use crate::image_util;
-use crate::widget::attribute::util::get_layout;
-use crate::widget::event::{InputEvent, MouseEvent};
+use crate::widget::attribute::util::{get_grid_cell, get_layout};
+use crate::widget::event::{Event, InputEvent, MouseEvent};
 use crate::{
+    image_util,
     widget::attribute::{find_callback, find_value, util::is_scrollable},
-    AttribKey, Attribute, Widget,
+    AttribKey, Attribute, Error, Widget,
 };
-use gdk_pixbuf::{PixbufLoader, PixbufLoaderExt};
 use gio::prelude::*;
 use gtk::{
     prelude::*, Adjustment, Button, CheckButton, Entry, EntryBuffer, EventBox,
-    Frame, HeaderBar, Image, Label, LabelBuilder, LinkButton, Menu, MenuBar,
-    MenuItem, Orientation, Overlay, Paned, RadioButton, ScrolledWindow,
-    SearchEntry, TextBuffer, TextBufferExt, TextTagTable, TextView,
-    TextViewExt, WidgetExt,
+    Frame, Grid, HeaderBar, Image, Label, LabelBuilder, LinkButton, Menu,
+    MenuBar, MenuItem, Orientation, Overlay, Paned, RadioButton,
+    ScrolledWindow, SearchEntry, SeparatorMenuItem, TextBuffer, TextBufferExt,
+    TextTagTable, TextView, TextViewExt, WidgetExt,
 };
 use std::fmt::Debug;
 
@@ -44,9 +48,14 @@ where
     let widget: &Widget = element.tag();
     let attrs: &[Attribute<MSG>] = element.get_attributes();
     let children: &[crate::Node<MSG>] = element.get_children();
-    let layout = get_layout(&element).expect("must have a layout");
-    let width = layout.size.width;
-    let height = layout.size.height;
+    let (width, height) = match get_layout(&element) {
+        Some(layout) => (layout.size.width, layout.size.height),
+        None => {
+            log::warn!("{}", Error::MissingLayout(widget.clone()));
+            // a size request of -1 lets the widget use its natural size
+            (-1.0, -1.0)
+        }
+    };
 
     let mut widget_children: Vec<GtkWidget> = vec![];
     for child in children.iter() {
@@ -54,7 +63,7 @@ where
         widget_children.push(gtk_child);
     }
 
-    match widget {
+    let gtk_widget = match widget {
         // gbox can have many children
         Widget::Vbox | Widget::Hbox => {
             let orientation = match widget {
@@ -69,19 +78,19 @@ where
                     //container.pack_start(child_widget, false, false, 0);
                     gbox.add(child_widget);
                 } else {
-                    println!(
+                    log::trace!(
                         "was not able to add child widget: {:?}",
                         child.as_widget()
                     );
                 }
             }
             if is_scrollable(&attrs) {
-                println!("wrapping the gbox with ScrolledWindow");
+                log::trace!("wrapping the gbox with ScrolledWindow");
                 let scroll = ScrolledWindow::new(
                     None::<&Adjustment>,
                     None::<&Adjustment>,
                 );
-                println!("gbox size: ({},{})", width, height);
+                log::trace!("gbox size: ({},{})", width, height);
                 scroll.add(&gbox);
                 scroll.set_size_request(width as i32, height as i32);
                 scroll.set_propagate_natural_height(true);
@@ -104,7 +113,7 @@ where
                 if let Some(child_widget) = child.as_widget() {
                     vbox.add(child_widget);
                 } else {
-                    println!(
+                    log::trace!(
                         "was not able to add child widget: {:?}",
                         child.as_widget()
                     );
@@ -113,11 +122,36 @@ where
             frame.add(&vbox);
             GtkWidget::GroupBox(frame)
         }
+        Widget::Grid => {
+            let grid = Grid::new();
+            for (child, widget_child) in
+                children.iter().zip(widget_children.iter())
+            {
+                let cell = get_grid_cell(child.get_attributes().unwrap_or(&[]));
+                if let Some(child_widget) = widget_child.as_widget() {
+                    grid.attach(
+                        child_widget,
+                        cell.column as i32,
+                        cell.row as i32,
+                        cell.column_span as i32,
+                        cell.row_span as i32,
+                    );
+                } else {
+                    log::trace!(
+                        "was not able to add child widget: {:?}",
+                        widget_child.as_widget()
+                    );
+                }
+            }
+            grid.set_size_request(width as i32, height as i32);
+            GtkWidget::Grid(grid)
+        }
         // paned has only 2 children
         Widget::Hpane => {
-            //TODO: make these infallable and more ergonomic
-            let child1_attrs =
-                children[0].get_attributes().expect("must have attributes");
+            let child1_attrs = children
+                .get(0)
+                .and_then(|c| c.get_attributes())
+                .unwrap_or(&[]);
 
             let hpane = Paned::new(Orientation::Horizontal);
             if widget_children.len() != 2 {
@@ -139,8 +173,10 @@ where
             if let Some(child2) =
                 widget_children.get(1).map(|c| c.as_widget()).flatten()
             {
-                let child2_attrs =
-                    children[1].get_attributes().expect("must have attributes");
+                let child2_attrs = children
+                    .get(1)
+                    .and_then(|c| c.get_attributes())
+                    .unwrap_or(&[]);
                 let is_resizable =
                     find_value(AttribKey::Resizable, &child2_attrs)
                         .map(|v| v.as_bool())
@@ -148,11 +184,11 @@ where
                 hpane.pack2(child2, true, true);
                 hpane.set_child_resize(child2, is_resizable);
             }
-            if let Some(first_child) =
-                children.first().map(|c| c.as_element_ref()).flatten()
+            if let Some(child1_layout) = children
+                .first()
+                .and_then(|c| c.as_element_ref())
+                .and_then(|first_child| get_layout(first_child))
             {
-                let child1_layout =
-                    get_layout(first_child).expect("must have a layout");
                 hpane.set_position(child1_layout.size.width as i32);
             }
 
@@ -177,18 +213,18 @@ where
             {
                 vpane.pack2(child2, true, true);
             }
-            if let Some(first_child) =
-                children.first().map(|c| c.as_element_ref()).flatten()
+            if let Some(child1_layout) = children
+                .first()
+                .and_then(|c| c.as_element_ref())
+                .and_then(|first_child| get_layout(first_child))
             {
-                let child1_layout =
-                    get_layout(first_child).expect("must have a layout");
                 vpane.set_position(child1_layout.size.width as i32);
             }
             vpane.set_size_request(width as i32, height as i32);
             GtkWidget::Paned(vpane)
         }
         Widget::Button => {
-            println!("it's a button");
+            log::trace!("it's a button");
             let label =
                 find_value(AttribKey::Label, &attrs).map(|v| v.to_string());
 
@@ -205,7 +241,7 @@ where
                     let cb_clone = cb.clone();
                     let program_clone = program.clone();
                     btn.connect_clicked(move |_| {
-                        println!("btn is clicked..");
+                        log::trace!("btn is clicked..");
                         let mouse_event = MouseEvent::default();
                         let msg = cb_clone.emit(mouse_event);
                         program_clone.dispatch(msg);
@@ -214,7 +250,7 @@ where
             }
 
             if let Some(svg_image_data) = svg_image_data {
-                println!("got an svg image here..");
+                log::trace!("got an svg image here..");
                 let svg_image: Image = images::svg_image(&svg_image_data);
                 btn.set_image(Some(&svg_image));
             }
@@ -274,7 +310,7 @@ where
                     // click event using button_press and button_release
                     event_box.connect_button_press_event(
                         move |_view, event| {
-                            println!("btn is clicked..");
+                            log::trace!("btn is clicked..");
                             let mouse_event = MouseEvent::default();
                             let msg = cb_clone.emit(mouse_event);
                             program_clone.dispatch(msg);
@@ -286,12 +322,12 @@ where
             if let Some(callbacks) = find_callback(AttribKey::MouseDown, &attrs)
             {
                 for cb in callbacks {
-                    println!("label has some mouse down");
+                    log::trace!("label has some mouse down");
                     let cb_clone = cb.clone();
                     let program_clone = program.clone();
                     event_box.connect_button_press_event(
                         move |_view, event| {
-                            println!("label is button pressed");
+                            log::trace!("label is button pressed");
                             let (x, y) = event.get_position();
                             let mouse_event =
                                 MouseEvent::pressed(x as i32, y as i32);
@@ -304,12 +340,12 @@ where
             }
             if let Some(callbacks) = find_callback(AttribKey::MouseUp, &attrs) {
                 for cb in callbacks {
-                    println!("label has some mouse up");
+                    log::trace!("label has some mouse up");
                     let cb_clone = cb.clone();
                     let program_clone = program.clone();
                     event_box.connect_button_release_event(
                         move |_view, event| {
-                            println!("label is button released");
+                            log::trace!("label is button released");
                             let (x, y) = event.get_position();
                             let mouse_event =
                                 MouseEvent::release(x as i32, y as i32);
@@ -324,12 +360,12 @@ where
             if let Some(callbacks) = find_callback(AttribKey::MouseMove, &attrs)
             {
                 for cb in callbacks {
-                    println!("label has some mouse up");
+                    log::trace!("label has some mouse up");
                     let cb_clone = cb.clone();
                     let program_clone = program.clone();
                     event_box.connect_motion_notify_event(
                         move |_view, event| {
-                            println!("label is button released");
+                            log::trace!("label is button released");
                             let (x, y) = event.get_position();
                             let mouse_event =
                                 MouseEvent::mousemove(x as i32, y as i32);
@@ -360,8 +396,7 @@ where
                 .unwrap_or(false);
 
             let cb = CheckButton::new_with_label(&label);
-            cb.set_property("active", &value)
-                .expect("must be able to set property");
+            cb.set_active(value);
             GtkWidget::Checkbox(cb)
         }
         Widget::Radio => {
@@ -373,32 +408,12 @@ where
                 .map(|v| v.as_bool())
                 .unwrap_or(false);
             let rb = RadioButton::new_with_label(&label);
-            rb.set_property("active", &value)
-                .expect("must be able to set property");
+            rb.set_active(value);
             GtkWidget::Radio(rb)
         }
         Widget::Image => {
-            let bytes: &[u8] = find_value(AttribKey::Data, &attrs)
-                .map(|v| v.as_bytes())
-                .flatten()
-                .unwrap_or(&[]);
             let image = Image::new();
-            let mime = image_util::image_mime_type(&bytes)
-                .expect("unsupported have mime type");
-            let pixbuf_loader =
-                PixbufLoader::new_with_mime_type(mime).expect("error loader");
-            pixbuf_loader
-                .write(&bytes)
-                .expect("Unable to write svg data into pixbuf_loader");
-
-            pixbuf_loader.close().expect("error creating pixbuf");
-
-            let pixbuf = pixbuf_loader.get_pixbuf();
-
-            image.set_from_pixbuf(Some(
-                &pixbuf.expect("error in pixbuf_loader"),
-            ));
-
+            set_image_content(program, &image, attrs, (width, height));
             image.set_size_request(width as i32, height as i32);
             GtkWidget::Image(image)
         }
@@ -408,29 +423,15 @@ where
                 .map(|v| v.as_bytes())
                 .flatten()
                 .unwrap_or(&empty);
-            let image = Image::new();
-            let pixbuf_loader =
-                PixbufLoader::new_with_mime_type("image/svg+xml")
-                    .expect("error loader");
-            pixbuf_loader
-                .write(bytes)
-                .expect("Unable to write svg data into pixbuf_loader");
-
-            pixbuf_loader.close().expect("error creating pixbuf");
-
-            let pixbuf = pixbuf_loader.get_pixbuf();
-
-            image.set_from_pixbuf(Some(
-                &pixbuf.expect("error in pixbuf_loader"),
-            ));
+            let image = images::svg_image(bytes);
             if let Some(callbacks) = find_callback(AttribKey::MouseDown, &attrs)
             {
                 for cb in callbacks {
-                    println!("textview has some mouse down");
+                    log::trace!("textview has some mouse down");
                     let cb_clone = cb.clone();
                     let program_clone = program.clone();
                     image.connect_button_press_event(move |_view, event| {
-                        println!("textview is button pressed");
+                        log::trace!("textview is button pressed");
                         let (x, y) = event.get_position();
                         let mouse_event =
                             MouseEvent::pressed(x as i32, y as i32);
@@ -494,12 +495,12 @@ where
             if let Some(callbacks) = find_callback(AttribKey::MouseDown, &attrs)
             {
                 for cb in callbacks {
-                    println!("textview has some mouse down");
+                    log::trace!("textview has some mouse down");
                     let cb_clone = cb.clone();
                     let program_clone = program.clone();
                     text_view.connect_button_press_event(
                         move |_view, event| {
-                            println!("textview is button pressed");
+                            log::trace!("textview is button pressed");
                             let (x, y) = event.get_position();
                             let mouse_event =
                                 MouseEvent::pressed(x as i32, y as i32);
@@ -534,7 +535,7 @@ where
                     let c_index = overlay.get_child_index(child_widget);
                     assert_eq!(c_index, index as i32);
                 } else {
-                    println!(
+                    log::trace!(
                         "was not able to add child widget: {:?}",
                         child.as_widget()
                     );
@@ -551,7 +552,7 @@ where
                 if let Some(child_widget) = child.as_widget() {
                     header_bar.add(child_widget);
                 } else {
-                    println!(
+                    log::trace!(
                         "was not able to add child widget: {:?}",
                         child.as_widget()
                     );
@@ -566,7 +567,7 @@ where
                 if let Some(child_widget) = child.as_widget() {
                     menu_bar.add(child_widget);
                 } else {
-                    println!(
+                    log::trace!(
                         "was not able to add child widget: {:?}",
                         child.as_widget()
                     );
@@ -581,32 +582,36 @@ where
                 if let Some(child_widget) = child.as_widget() {
                     menu.add(child_widget);
                 } else {
-                    println!(
+                    log::trace!(
                         "was not able to add child widget: {:?}",
                         child.as_widget()
                     );
                 }
             }
+            menu::join_radio_groups(children, &widget_children);
             GtkWidget::Menu(menu)
         }
         Widget::MenuItem => {
-            let menu_item = MenuItem::new();
+            let menu_item = menu::new_menu_item(attrs);
 
             if let Some(callbacks) = find_callback(AttribKey::ClickEvent, attrs)
             {
-                println!("menu item has click event");
+                log::trace!("menu item has click event");
                 for cb in callbacks {
                     let cb_clone = cb.clone();
                     let program_clone = program.clone();
-                    menu_item.connect_activate(move |_| {
-                        println!("menu item is clicked..");
+                    menu_item.connect_activate(move |menu_item| {
+                        if !menu::is_activated_by_user(menu_item) {
+                            return;
+                        }
+                        log::trace!("menu item is clicked..");
                         let mouse_event = MouseEvent::default();
                         let msg = cb_clone.emit(mouse_event);
                         program_clone.dispatch(msg);
                     });
                 }
             } else {
-                println!("No click event for menu item");
+                log::trace!("No click event for menu item");
             }
 
             for child in widget_children.iter() {
@@ -618,7 +623,7 @@ where
                         if let Some(child_widget) = child.as_widget() {
                             menu_item.add(child_widget);
                         } else {
-                            println!(
+                            log::trace!(
                                 "was not able to add child widget: {:?}",
                                 child.as_widget()
                             );
@@ -628,6 +633,26 @@ where
             }
             GtkWidget::MenuItem(menu_item)
         }
+        Widget::ContextMenu => {
+            let menu = Menu::new();
+
+            for child in widget_children.iter() {
+                if let Some(child_widget) = child.as_widget() {
+                    menu.add(child_widget);
+                } else {
+                    log::trace!(
+                        "was not able to add child widget: {:?}",
+                        child.as_widget()
+                    );
+                }
+            }
+            menu::join_radio_groups(children, &widget_children);
+            GtkWidget::ContextMenu(menu)
+        }
+        Widget::MenuSeparator => {
+            let separator = SeparatorMenuItem::new();
+            GtkWidget::MenuItem(separator.upcast())
+        }
         Widget::SearchInput => {
             let value = find_value(AttribKey::Value, &attrs)
                 .map(|v| v.to_string())
@@ -670,7 +695,7 @@ where
                     let cb_clone = cb.clone();
                     let program_clone = program.clone();
                     link_btn.connect_clicked(move |_| {
-                        println!("btn is clicked..");
+                        log::trace!("btn is clicked..");
                         let mouse_event = MouseEvent::default();
                         let msg = cb_clone.emit(mouse_event);
                         program_clone.dispatch(msg);
@@ -681,5 +706,149 @@ where
             link_btn.set_size_request(width as i32, height as i32);
             GtkWidget::LinkButton(link_btn)
         }
+        Widget::Canvas => {
+            let drawing_area =
+                canvas::drawing_area(canvas::Content::from_attrs(&attrs));
+            drawing_area.add_events(
+                gdk::EventMask::BUTTON_PRESS_MASK
+                    | gdk::EventMask::BUTTON_RELEASE_MASK
+                    | gdk::EventMask::POINTER_MOTION_MASK,
+            );
+            // the position of the events are relative to the drawing area
+            let mouse_events: [(AttribKey, fn(i32, i32) -> MouseEvent); 4] = [
+                (AttribKey::MouseDown, MouseEvent::pressed),
+                (AttribKey::MouseUp, MouseEvent::release),
+                (AttribKey::ClickEvent, MouseEvent::click),
+                (AttribKey::MouseMove, MouseEvent::mousemove),
+            ];
+            for (key, mouse_event) in mouse_events.iter().cloned() {
+                for cb in find_callback(key.clone(), &attrs).unwrap_or_default()
+                {
+                    let cb_clone = cb.clone();
+                    let program_clone = program.clone();
+                    let emit = move |(x, y): (f64, f64)| {
+                        let msg =
+                            cb_clone.emit(mouse_event(x as i32, y as i32));
+                        program_clone.dispatch(msg);
+                        Inhibit(false)
+                    };
+                    match key {
+                        AttribKey::MouseDown => drawing_area
+                            .connect_button_press_event(move |_, event| {
+                                emit(event.get_position())
+                            }),
+                        AttribKey::MouseMove => drawing_area
+                            .connect_motion_notify_event(move |_, event| {
+                                emit(event.get_position())
+                            }),
+                        _ => drawing_area.connect_button_release_event(
+                            move |_, event| emit(event.get_position()),
+                        ),
+                    };
+                }
+            }
+            for cb in find_callback(AttribKey::ChartHoverEvent, &attrs)
+                .unwrap_or_default()
+            {
+                let cb_clone = cb.clone();
+                let program_clone = program.clone();
+                drawing_area.connect_motion_notify_event(
+                    move |drawing_area, event| {
+                        if let Some(point) = canvas::nearest_point(
+                            drawing_area,
+                            event.get_position(),
+                        ) {
+                            program_clone.dispatch(
+                                cb_clone.emit(Event::ChartHover(point)),
+                            );
+                        }
+                        Inhibit(false)
+                    },
+                );
+            }
+            drawing_area.set_size_request(width as i32, height as i32);
+            GtkWidget::Canvas(drawing_area)
+        }
+    };
+    if let Some(widget) = gtk_widget.as_widget() {
+        drag_drop::connect(program, widget, attrs);
+        for child in widget_children.iter() {
+            if let GtkWidget::ContextMenu(menu) = child {
+                context_menu::attach(widget, menu);
+            }
+        }
+    }
+    gtk_widget
+}
+
+/// display the image from its attributes in the size of the widget,
+/// an animated image is played, replacing the animation it was playing before
+pub(crate) fn set_image_content<MSG, DSP>(
+    program: &DSP,
+    image: &Image,
+    attrs: &[Attribute<MSG>],
+    (width, height): (f32, f32),
+) where
+    MSG: Debug + 'static,
+    DSP: Clone + Dispatch<MSG> + 'static,
+{
+    let scaling = find_value(AttribKey::ImageScaling, &attrs)
+        .and_then(|v| v.as_image_scaling())
+        .unwrap_or_default();
+    match image_util::image_frames(&attrs) {
+        Ok(frames) if frames.len() > 1 => {
+            let paused = find_value(AttribKey::Paused, &attrs)
+                .map(|v| v.as_bool())
+                .unwrap_or(false);
+            let looping = find_value(AttribKey::Looping, &attrs)
+                .map(|v| v.as_bool())
+                .unwrap_or(true);
+            let on_end = on_animation_end(program, attrs);
+            animation::play(
+                image,
+                &frames,
+                (width, height),
+                scaling,
+                paused,
+                looping,
+                on_end,
+            );
+        }
+        Ok(frames) => {
+            animation::stop(image);
+            images::set_image(
+                image,
+                Ok(images::scaled_pixbuf(
+                    &frames[0].image,
+                    width,
+                    height,
+                    scaling,
+                )),
+            )
+        }
+        Err(e) => {
+            animation::stop(image);
+            images::set_image(image, Err(e))
+        }
     }
 }
+
+/// dispatch the msg of the animation end callbacks of the image
+pub(crate) fn on_animation_end<MSG, DSP>(
+    program: &DSP,
+    attrs: &[Attribute<MSG>],
+) -> Box<dyn Fn()>
+where
+    MSG: Debug + 'static,
+    DSP: Clone + Dispatch<MSG> + 'static,
+{
+    let callbacks: Vec<_> = find_callback(AttribKey::AnimationEndEvent, attrs)
+        .map(|cbs| cbs.into_iter().cloned().collect())
+        .unwrap_or_default();
+    let program = program.clone();
+    Box::new(move || {
+        for cb in callbacks.iter() {
+            program.dispatch(cb.emit(Event::AnimationEnd));
+        }
+    })
+}
@@ -0,0 +1,198 @@
+//! drag and drop between the widgets of the app, and of files or text from other applications
+//!
+//! The payload of a drag within the app is kept in the app while it is dragged,
+//! so the selection of the drag only carries an empty app specific target.
+use super::Dispatch;
+use crate::{
+    widget::{
+        attribute::{find_callback, find_value, DropEffect},
+        event::{DragPayload, DropEvent, DroppedFile},
+    },
+    AttribKey, Attribute, Event, Value,
+};
+use gdk::DragAction;
+use gtk::{prelude::*, DestDefaults, Inhibit, TargetEntry, TargetFlags};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+const APP_TARGET: &str = "application/x-sauron-native";
+const URI_LIST_TARGET: &str = "text/uri-list";
+const TEXT_TARGET: &str = "text/plain;charset=utf-8";
+/// the info of the targets, to tell which one is received
+const APP_INFO: u32 = 0;
+const URI_LIST_INFO: u32 = 1;
+const TEXT_INFO: u32 = 2;
+
+thread_local! {
+    /// the payload of the drag sources, removed when the widget is destroyed
+    static DRAG_DATA: RefCell<HashMap<gtk::Widget, Value>> =
+        RefCell::new(HashMap::new());
+    /// the payload which is being dragged within the app
+    static DRAGGED: RefCell<Option<Value>> = RefCell::new(None);
+}
+
+/// make the widget a drag source when it has a drag_data,
+/// and a drop target when it has a drop callback
+pub(crate) fn connect<MSG, DSP>(
+    program: &DSP,
+    widget: &gtk::Widget,
+    attrs: &[Attribute<MSG>],
+) where
+    MSG: 'static,
+    DSP: Clone + Dispatch<MSG> + 'static,
+{
+    let actions = find_value(AttribKey::DropEffect, attrs)
+        .and_then(|v| v.as_drop_effect())
+        .map(drag_action)
+        .unwrap_or(DragAction::COPY | DragAction::MOVE);
+
+    if let Some(data) = find_value(AttribKey::DragData, attrs) {
+        DRAG_DATA.with(|drag_data| {
+            drag_data.borrow_mut().insert(widget.clone(), data.clone())
+        });
+        widget.drag_source_set(
+            gdk::ModifierType::BUTTON1_MASK,
+            &[TargetEntry::new(
+                APP_TARGET,
+                TargetFlags::SAME_APP,
+                APP_INFO,
+            )],
+            actions,
+        );
+        let failed = Rc::new(Cell::new(false));
+        let failed_clone = Rc::clone(&failed);
+        widget.connect_drag_begin(move |widget, _| {
+            failed_clone.set(false);
+            let data = DRAG_DATA
+                .with(|drag_data| drag_data.borrow().get(widget).cloned());
+            DRAGGED.with(|dragged| *dragged.borrow_mut() = data);
+        });
+        widget.connect_drag_data_get(|_, _, selection_data, _, _| {
+            selection_data.set(&selection_data.get_target(), 8, &[]);
+        });
+        let failed_clone = Rc::clone(&failed);
+        widget.connect_drag_failed(move |_, _, _| {
+            failed_clone.set(true);
+            Inhibit(false)
+        });
+        let callbacks: Vec<_> = find_callback(AttribKey::DragEndEvent, attrs)
+            .unwrap_or_default()
+            .into_iter()
+            .cloned()
+            .collect();
+        let program = program.clone();
+        widget.connect_drag_end(move |_, context| {
+            DRAGGED.with(|dragged| dragged.borrow_mut().take());
+            let effect = if failed.get() {
+                DropEffect::None
+            } else {
+                drop_effect(context.get_selected_action())
+            };
+            for cb in callbacks.iter() {
+                program.dispatch(cb.emit(Event::DragEnd(effect)));
+            }
+        });
+        widget.connect_destroy(|widget| {
+            DRAG_DATA.with(|drag_data| drag_data.borrow_mut().remove(widget));
+        });
+    }
+
+    if let Some(callbacks) = find_callback(AttribKey::DropEvent, attrs) {
+        widget.drag_dest_set(
+            DestDefaults::ALL,
+            &[
+                TargetEntry::new(APP_TARGET, TargetFlags::SAME_APP, APP_INFO),
+                TargetEntry::new(
+                    URI_LIST_TARGET,
+                    TargetFlags::OTHER_APP,
+                    URI_LIST_INFO,
+                ),
+                TargetEntry::new(
+                    TEXT_TARGET,
+                    TargetFlags::OTHER_APP,
+                    TEXT_INFO,
+                ),
+            ],
+            actions,
+        );
+        let callbacks: Vec<_> = callbacks.into_iter().cloned().collect();
+        let program = program.clone();
+        widget.connect_drag_data_received(
+            move |_, context, x, y, selection_data, info, _| {
+                let payload = match info {
+                    APP_INFO => DRAGGED
+                        .with(|dragged| dragged.borrow().clone())
+                        .map(DragPayload::Value),
+                    URI_LIST_INFO => Some(DragPayload::Files(
+                        selection_data
+                            .get_uris()
+                            .iter()
+                            .filter_map(|uri| dropped_file(uri))
+                            .collect(),
+                    )),
+                    _ => selection_data
+                        .get_text()
+                        .map(|text| DragPayload::Text(text.to_string())),
+                };
+                if let Some(payload) = payload {
+                    let drop_event = DropEvent {
+                        payload,
+                        x,
+                        y,
+                        effect: drop_effect(context.get_selected_action()),
+                    };
+                    for cb in callbacks.iter() {
+                        program
+                            .dispatch(cb.emit(Event::Drop(drop_event.clone())));
+                    }
+                }
+            },
+        );
+    }
+}
+
+/// replace the payload of the drag source
+pub(crate) fn set_drag_data(widget: &gtk::Widget, data: &Value) {
+    DRAG_DATA.with(|drag_data| {
+        if let Some(current) = drag_data.borrow_mut().get_mut(widget) {
+            *current = data.clone();
+        } else {
+            log::warn!("drag_data can only be changed on a drag source");
+        }
+    });
+}
+
+/// the file of a local uri, the files of remote uris are skipped
+fn dropped_file(uri: &str) -> Option<DroppedFile> {
+    let (path, _hostname) = glib::filename_from_uri(uri).ok()?;
+    let name = path.file_name()?.to_string_lossy().to_string();
+    Some(DroppedFile {
+        name,
+        path: Some(path),
+        data: None,
+    })
+}
+
+fn drag_action(effect: DropEffect) -> DragAction {
+    match effect {
+        DropEffect::None => DragAction::empty(),
+        DropEffect::Copy => DragAction::COPY,
+        DropEffect::Move => DragAction::MOVE,
+        DropEffect::Link => DragAction::LINK,
+    }
+}
+
+fn drop_effect(action: DragAction) -> DropEffect {
+    if action.contains(DragAction::MOVE) {
+        DropEffect::Move
+    } else if action.contains(DragAction::COPY) {
+        DropEffect::Copy
+    } else if action.contains(DragAction::LINK) {
+        DropEffect::Link
+    } else {
+        DropEffect::None
+    }
+}
@@ -1,18 +1,66 @@
-use gdk_pixbuf::{PixbufLoader, PixbufLoaderExt};
-use gtk::{Image, ImageExt};
+use crate::{image_util, widget::attribute::ImageScaling, Error};
+use gdk_pixbuf::{Colorspace, Pixbuf, PixbufLoader, PixbufLoaderExt};
+use gtk::{IconSize, Image, ImageExt, WidgetExt};
+use image::RgbaImage;
+
+pub(crate) const SVG_MIME_TYPE: &str = "image/svg+xml";
 
 pub fn svg_image(bytes: &[u8]) -> Image {
     let image = Image::new();
-    let pixbuf_loader = PixbufLoader::new_with_mime_type("image/svg+xml")
-        .expect("error loader");
+    set_image(&image, load_pixbuf(bytes, SVG_MIME_TYPE));
+    image
+}
+
+/// decode the image data of the given mime type
+pub(crate) fn load_pixbuf(bytes: &[u8], mime: &str) -> Result<Pixbuf, Error> {
+    let to_error = |e: glib::Error| Error::InvalidImage(e.to_string());
+    let pixbuf_loader =
+        PixbufLoader::new_with_mime_type(mime).map_err(to_error)?;
+    pixbuf_loader.write(bytes).map_err(to_error)?;
+    pixbuf_loader.close().map_err(to_error)?;
     pixbuf_loader
-        .write(bytes)
-        .expect("Unable to write svg data into pixbuf_loader");
+        .get_pixbuf()
+        .ok_or_else(|| Error::InvalidImage("no image is decoded".to_string()))
+}
 
-    pixbuf_loader.close().expect("error creating pixbuf");
+/// scale the decoded image into the size of the widget
+pub(crate) fn scaled_pixbuf(
+    image: &RgbaImage,
+    width: f32,
+    height: f32,
+    scaling: ImageScaling,
+) -> Pixbuf {
+    // the size is negative when the widget has no computed layout
+    let (width, height) = (width.max(0.0) as u32, height.max(0.0) as u32);
+    pixbuf_from_rgba(image_util::scale(image, width, height, scaling))
+}
 
-    let pixbuf = pixbuf_loader.get_pixbuf();
+pub(crate) fn pixbuf_from_rgba(image: RgbaImage) -> Pixbuf {
+    let (width, height) = image.dimensions();
+    let bytes = glib::Bytes::from_owned(image.into_raw());
+    Pixbuf::new_from_bytes(
+        &bytes,
+        Colorspace::Rgb,
+        true,
+        8,
+        width as i32,
+        height as i32,
+        width as i32 * 4,
+    )
+}
 
-    image.set_from_pixbuf(Some(&pixbuf.expect("error in pixbuf_loader")));
-    image
+/// set the decoded image, or show a missing image icon
+/// with the error as its tooltip when the image could not be decoded
+pub(crate) fn set_image(image: &Image, pixbuf: Result<Pixbuf, Error>) {
+    match pixbuf {
+        Ok(pixbuf) => {
+            image.set_from_pixbuf(Some(&pixbuf));
+            image.set_tooltip_text(None);
+        }
+        Err(e) => {
+            log::error!("unable to display the image: {}", e);
+            image.set_from_icon_name(Some("image-missing"), IconSize::Dialog);
+            image.set_tooltip_text(Some(&e.to_string()));
+        }
+    }
 }
@@ -0,0 +1,85 @@
+//! the side window which displays the inspector
+use crate::{
+    inspector::Inspector,
+    widget::event::{KeyCode, KeyEvent, Modifier},
+};
+use gdk::ModifierType;
+use gtk::{
+    prelude::*, ApplicationWindow, Inhibit, ScrolledWindow, TextView,
+    WindowType,
+};
+use std::{cell::RefCell, rc::Rc};
+
+pub(crate) struct InspectorWindow {
+    window: gtk::Window,
+    text_view: TextView,
+}
+
+impl InspectorWindow {
+    pub(crate) fn new(
+        parent: &ApplicationWindow,
+        inspector: Rc<RefCell<Inspector>>,
+    ) -> Self {
+        let window = gtk::Window::new(WindowType::Toplevel);
+        window.set_title("Inspector");
+        window.set_default_size(480, 600);
+        window.set_transient_for(Some(parent));
+
+        let text_view = TextView::new();
+        text_view.set_editable(false);
+        text_view.set_monospace(true);
+        let scroll = ScrolledWindow::new(
+            None::<&gtk::Adjustment>,
+            None::<&gtk::Adjustment>,
+        );
+        scroll.add(&text_view);
+        window.add(&scroll);
+
+        // closing the window only hides it, so it can be toggled again
+        window.connect_delete_event(move |window, _| {
+            let mut inspector = inspector.borrow_mut();
+            if inspector.is_enabled() {
+                inspector.toggle();
+            }
+            window.hide();
+            Inhibit(true)
+        });
+
+        InspectorWindow { window, text_view }
+    }
+
+    pub(crate) fn set_visible(&self, visible: bool) {
+        if visible {
+            self.window.show_all();
+        } else {
+            self.window.hide();
+        }
+    }
+
+    pub(crate) fn set_text(&self, text: &str) {
+        if let Some(buffer) = self.text_view.get_buffer() {
+            buffer.set_text(text);
+        }
+    }
+}
+
+/// convert the gdk key event, to check for the inspector key combination
+pub(crate) fn from_gdk_key_event(event: &gdk::EventKey) -> KeyEvent {
+    let keyval = event.get_keyval();
+    let key_code = if keyval == gdk::enums::key::F12 {
+        KeyCode::F(12)
+    } else {
+        gdk::keyval_to_unicode(keyval)
+            .map(KeyCode::Char)
+            .unwrap_or(KeyCode::Null)
+    };
+    let state = event.get_state();
+    KeyEvent {
+        key_code,
+        modifier: Modifier {
+            alt_key: state.contains(ModifierType::MOD1_MASK),
+            ctrl_key: state.contains(ModifierType::CONTROL_MASK),
+            shift_key: state.contains(ModifierType::SHIFT_MASK),
+        },
+    }
+}
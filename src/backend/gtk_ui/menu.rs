@@ -0,0 +1,175 @@
+//! the menu items with a label, an icon and an accelerator, which can be checked
+//!
+//! The check and radio menu items are activated when their checked state is set,
+//! so the activations while the view sets it are not dispatched.
+use super::{images, GtkWidget};
+use crate::{widget::attribute::find_value, AttribKey, Attribute};
+use gtk::{
+    prelude::*, AccelLabel, CheckMenuItem, MenuItem, Orientation, RadioMenuItem,
+};
+use std::{cell::Cell, collections::HashMap};
+
+thread_local! {
+    /// whether the checked state of a menu item is being set from the view
+    static UPDATING: Cell<bool> = Cell::new(false);
+}
+
+/// create the menu item, a check menu item when it has a checked attribute,
+/// and a radio menu item when it also has a name
+pub(crate) fn new_menu_item<MSG>(attrs: &[Attribute<MSG>]) -> MenuItem
+where
+    MSG: 'static,
+{
+    let checked = find_value(AttribKey::Checked, attrs).map(|v| v.as_bool());
+    let group = find_value(AttribKey::Name, attrs);
+    let menu_item: MenuItem = match (checked, group) {
+        (Some(checked), Some(_group)) => {
+            let radio = RadioMenuItem::new();
+            set_active(&radio, checked);
+            radio.upcast()
+        }
+        (Some(checked), None) => {
+            let check = CheckMenuItem::new();
+            set_active(&check, checked);
+            check.upcast()
+        }
+        (None, _) => MenuItem::new(),
+    };
+
+    // the label is the first child of the menu item
+    if let Some(label) = find_value(AttribKey::Label, attrs) {
+        let label_box = gtk::Box::new(Orientation::Horizontal, 6);
+        if let Some(bytes) =
+            find_value(AttribKey::SvgImage, attrs).and_then(|v| v.as_bytes())
+        {
+            label_box.add(&images::svg_image(bytes));
+        }
+        let accel_label = AccelLabel::new(&label.to_string());
+        accel_label.set_xalign(0.0);
+        accel_label.set_hexpand(true);
+        if let Some(shortcut) = find_value(AttribKey::Accelerator, attrs) {
+            let (key, modifiers) = accelerator(&shortcut.to_string());
+            accel_label.set_accel(key, modifiers);
+        }
+        label_box.add(&accel_label);
+        menu_item.add(&label_box);
+    }
+
+    let disabled = find_value(AttribKey::Disabled, attrs)
+        .map(|v| v.as_bool())
+        .unwrap_or(false);
+    menu_item.set_sensitive(!disabled);
+    menu_item
+}
+
+/// whether the activation of the menu item is from the user, and not from the view
+/// setting its checked state or from a radio menu item of its group being activated
+pub(crate) fn is_activated_by_user(menu_item: &MenuItem) -> bool {
+    if UPDATING.with(|updating| updating.get()) {
+        return false;
+    }
+    match menu_item.downcast_ref::<RadioMenuItem>() {
+        Some(radio) => radio.get_active(),
+        None => true,
+    }
+}
+
+/// set the checked state of a check or radio menu item
+pub(crate) fn set_checked(menu_item: &MenuItem, checked: bool) {
+    if let Some(check) = menu_item.downcast_ref::<CheckMenuItem>() {
+        set_active(check, checked);
+    } else {
+        log::warn!(
+            "only the menu items with a checked attribute can be checked"
+        );
+    }
+}
+
+/// replace the text of the label of the menu item
+pub(crate) fn set_label(menu_item: &MenuItem, label: &str) {
+    let accel_label = menu_item
+        .get_child()
+        .and_then(|child| child.downcast::<gtk::Box>().ok())
+        .and_then(|label_box| {
+            label_box
+                .get_children()
+                .into_iter()
+                .find_map(|child| child.downcast::<AccelLabel>().ok())
+        });
+    match accel_label {
+        Some(accel_label) => accel_label.set_text(label),
+        None => log::warn!("only the menu items with a label can be relabeled"),
+    }
+}
+
+/// put the radio menu items with the same name among these menu items into a group,
+/// keeping their checked state
+pub(crate) fn join_radio_groups<MSG>(
+    children: &[crate::Node<MSG>],
+    menu_items: &[GtkWidget],
+) where
+    MSG: 'static,
+{
+    let mut groups: HashMap<String, RadioMenuItem> = HashMap::new();
+    let mut radios = vec![];
+    for (child, menu_item) in children.iter().zip(menu_items.iter()) {
+        let attrs = child.get_attributes().unwrap_or(&[]);
+        let radio = match menu_item {
+            GtkWidget::MenuItem(menu_item) => {
+                menu_item.downcast_ref::<RadioMenuItem>()
+            }
+            _ => None,
+        };
+        if let (Some(radio), Some(group)) =
+            (radio, find_value(AttribKey::Name, attrs))
+        {
+            let checked = find_value(AttribKey::Checked, attrs)
+                .map(|v| v.as_bool())
+                .unwrap_or(false);
+            radios.push((radio.clone(), checked));
+            let group = group.to_string();
+            match groups.get(&group) {
+                Some(first) => {
+                    UPDATING.with(|updating| updating.set(true));
+                    radio.join_group(Some(first));
+                    UPDATING.with(|updating| updating.set(false));
+                }
+                None => {
+                    groups.insert(group, radio.clone());
+                }
+            }
+        }
+    }
+    // a radio menu item which is alone in its group can not be unchecked,
+    // so the checked one is checked again once they are grouped
+    for (radio, checked) in radios {
+        if checked {
+            set_active(&radio, true);
+        }
+    }
+}
+
+fn set_active(check: &impl IsA<CheckMenuItem>, checked: bool) {
+    UPDATING.with(|updating| updating.set(true));
+    check.set_active(checked);
+    UPDATING.with(|updating| updating.set(false));
+}
+
+/// parse the shortcut such as `Ctrl+Shift+S` into the key and modifiers of gtk
+fn accelerator(shortcut: &str) -> (u32, gdk::ModifierType) {
+    let accelerator: String = shortcut
+        .split('+')
+        .map(|part| {
+            let part = part.trim();
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => "<Control>",
+                "shift" => "<Shift>",
+                "alt" => "<Alt>",
+                "super" | "meta" | "cmd" => "<Super>",
+                _ => part,
+            }
+            .to_string()
+        })
+        .collect();
+    gtk::accelerator_parse(&accelerator)
+}
@@ -1,12 +1,20 @@
 //! html backend where all the functionalities is offloaded into sauron
-use crate::{widget::layout::compute_node_layout, Backend, Component};
-use sauron::prelude::*;
-use std::{fmt::Debug, marker::PhantomData};
+use crate::{
+    instrument::{Phase, Span},
+    widget::layout::LayoutTree,
+    Backend, Component,
+};
+use sauron::{prelude::*, Render};
+use std::{cell::RefCell, fmt::Debug, marker::PhantomData};
 use stretch::geometry::Size;
 use stretch::number::Number;
 
+mod animation;
+mod clipboard;
 mod convert_event;
 mod convert_widget;
+mod drag_drop;
+mod menu;
 
 /// We wrap the App's Msg with this such that we can add high level behavior of the app
 /// such as automatically computing the layout when the window is resized
@@ -16,6 +24,9 @@ pub enum BackendMsg<MSG> {
     AppMsg(MSG),
     /// the app container is resized
     Resize(i32, i32),
+    /// an html event which emits no msg for the app, such as the events with no
+    /// equivalent widget event, or a drop of files which is emitted once they are read
+    Ignored,
 }
 
 /// holds the user application,
@@ -27,6 +38,8 @@ where
 {
     app: APP,
     browser_size: (i32, i32),
+    /// the layout tree is updated incrementally on each view
+    layout_tree: RefCell<LayoutTree>,
     _phantom_data: PhantomData<MSG>,
 }
 
@@ -40,6 +53,7 @@ where
         HtmlApp {
             app,
             browser_size,
+            layout_tree: RefCell::new(LayoutTree::new()),
             _phantom_data: PhantomData,
         }
     }
@@ -65,35 +79,43 @@ where
     {
         match msg {
             BackendMsg::AppMsg(msg) => {
+                let span = Span::enter(Phase::Update);
                 self.app.update(msg);
+                span.finish();
+                return sauron::cmd::Cmd::batch(clipboard::take_commands());
             }
             BackendMsg::Resize(w, h) => {
                 log::debug!("window is resizing..");
                 self.browser_size = (w, h);
             }
+            BackendMsg::Ignored => {
+                return sauron::cmd::Cmd::batch(drag_drop::take_commands());
+            }
         }
         sauron::cmd::Cmd::none()
     }
 
     fn view(&self) -> sauron::Node<BackendMsg<MSG>> {
-        let t1 = sauron::now();
-
+        let span = Span::enter(Phase::View);
         let mut view = self.app.view();
+        crate::widget::evict_unused_lazy();
+        span.finish();
+
         let (w, h) = self.browser_size;
         let (adjusted_w, adjusted_h) = (w as f32 - 100.0, h as f32 - 20.0);
-        compute_node_layout(
-            &mut view,
-            Size {
-                width: Number::Defined(adjusted_w),
-                height: Number::Defined(adjusted_h),
-            },
-        );
-
-        let t2 = sauron::now();
-        log::warn!("layout computation took: {}ms", t2 - t1);
+        let parent_size = Size {
+            width: Number::Defined(adjusted_w),
+            height: Number::Defined(adjusted_h),
+        };
+        let mut layout_tree = self.layout_tree.borrow_mut();
+        let span = Span::enter(Phase::ComputeLayout);
+        layout_tree.update(&mut view, parent_size);
+        span.finish();
 
         let html_view = convert_widget::widget_tree_to_html_node(&view, &mut 0);
-        html_view.map_msg(BackendMsg::AppMsg)
+        html_view.map_msg(|msg: Option<MSG>| {
+            msg.map(BackendMsg::AppMsg).unwrap_or(BackendMsg::Ignored)
+        })
     }
 }
 
@@ -108,3 +130,17 @@ where
         sauron::Program::mount_to_body(html_app);
     }
 }
+
+/// render the widget tree into the html markup of this backend,
+/// used in the snapshot of the html output
+pub(crate) fn render_html<MSG>(view: &crate::Node<MSG>) -> String
+where
+    MSG: Clone + Debug + 'static,
+{
+    let html_node = convert_widget::widget_tree_to_html_node(view, &mut 0);
+    let mut buffer = String::new();
+    html_node
+        .render(&mut buffer)
+        .expect("must render the html node");
+    buffer
+}
@@ -0,0 +1,111 @@
+//! plays the frames of an animated image by swapping the src of the img element on a timer
+//!
+//! The browsers play the animated images natively, as many times as the image specifies,
+//! but can not be told to play them once or notify when they have finished.
+//! Those images are decoded and played frame by frame with an inline script instead,
+//! which dispatches an event on the img element when the last frame is shown.
+//! The images loaded from a path are always played by the browser.
+use crate::{
+    image_util::{self, AnimationFrame},
+    widget::attribute::{find_callback, find_value},
+    AttribKey, Error, Event,
+};
+use sauron::html::{attributes::attr, events::on};
+
+/// the event dispatched by the script on the img element when the animation has finished
+const ANIMATION_END: &str = "imageanimationend";
+
+/// the frames are played once the first frame is loaded, the src of the next frame
+/// is set after the delay of the current frame. The frames are read again when
+/// they are replaced by a patch, and the timer stops once the img is removed
+const PLAY: &str = "if (this.dataset.playing) { return; } \
+    this.dataset.playing = 'true'; \
+    let img = this; \
+    let source = img.dataset.frames; \
+    let frames = JSON.parse(source); \
+    let index = 0; \
+    let next = () => { \
+        if (!img.isConnected || !img.dataset.frames) { delete img.dataset.playing; return; } \
+        if (img.dataset.frames !== source) { \
+            source = img.dataset.frames; \
+            frames = JSON.parse(source); \
+            index = -1; \
+        } \
+        index += 1; \
+        if (index >= frames.length) { \
+            if (img.dataset.looping !== 'true') { \
+                delete img.dataset.playing; \
+                img.dispatchEvent(new Event('imageanimationend')); \
+                return; \
+            } \
+            index = 0; \
+        } \
+        img.src = frames[index][0]; \
+        setTimeout(next, frames[index][1]); \
+    }; \
+    setTimeout(next, frames[0][1]);";
+
+/// the browsers clamp the shorter delays, the same as in the gif players
+const MIN_DELAY_MS: u128 = 20;
+
+/// whether the image is played frame by frame instead of by the browser,
+/// which is when it is played once or has an animation end callback
+pub(crate) fn is_played_by_frames<MSG>(attrs: &[crate::Attribute<MSG>]) -> bool
+where
+    MSG: 'static,
+{
+    let looping = find_value(AttribKey::Looping, attrs)
+        .map(|v| v.as_bool())
+        .unwrap_or(true);
+    !looping || find_callback(AttribKey::AnimationEndEvent, attrs).is_some()
+}
+
+/// the src of the first frame, and the attributes of the img element which play the frames
+pub(crate) fn play<MSG>(
+    frames: &[AnimationFrame],
+    attrs: &[crate::Attribute<MSG>],
+) -> Result<(String, Vec<sauron::Attribute<Option<MSG>>>), Error>
+where
+    MSG: 'static,
+{
+    let sources = frames
+        .iter()
+        .map(|frame| {
+            let png = image_util::encode_png(frame.image.clone())?;
+            Ok(format!("data:image/png;base64,{}", base64::encode(&png)))
+        })
+        .collect::<Result<Vec<String>, Error>>()?;
+    let first = sources.first().cloned().unwrap_or_default();
+    if frames.len() < 2 {
+        return Ok((first, vec![]));
+    }
+    let json: Vec<String> = sources
+        .iter()
+        .zip(frames.iter())
+        .map(|(source, frame)| {
+            format!(
+                "[\"{}\",{}]",
+                source,
+                frame.delay.as_millis().max(MIN_DELAY_MS)
+            )
+        })
+        .collect();
+    let looping = find_value(AttribKey::Looping, attrs)
+        .map(|v| v.as_bool())
+        .unwrap_or(true);
+
+    let mut attributes = vec![
+        attr("data-frames", format!("[{}]", json.join(","))),
+        attr("data-looping", looping.to_string()),
+        attr("onload", PLAY),
+    ];
+    for cb in
+        find_callback(AttribKey::AnimationEndEvent, attrs).unwrap_or_default()
+    {
+        let cb = cb.clone();
+        attributes.push(on(ANIMATION_END, move |_event: sauron::Event| {
+            Some(cb.emit(Event::AnimationEnd))
+        }));
+    }
+    Ok((first, attributes))
+}
@@ -0,0 +1,112 @@
+//! carries out the clipboard requests with the async clipboard api of the browser
+//!
+//! The browser only allows reading the clipboard with the permission of the user,
+//! and an image is written and read as a png.
+use super::BackendMsg;
+use crate::{
+    clipboard::{self, Request},
+    image_util, Value,
+};
+use wasm_bindgen::{prelude::*, JsValue};
+
+#[wasm_bindgen(inline_js = r#"
+function to_base64(buffer) {
+    let binary = "";
+    new Uint8Array(buffer).forEach((byte) => binary += String.fromCharCode(byte));
+    return btoa(binary);
+}
+
+function from_base64(base64) {
+    return Uint8Array.from(atob(base64), (c) => c.charCodeAt(0));
+}
+
+export function write_text(text) {
+    navigator.clipboard.writeText(text)
+        .catch((e) => console.warn("unable to write the clipboard", e));
+}
+
+export function write_image(base64) {
+    let blob = new Blob([from_base64(base64)], { type: "image/png" });
+    navigator.clipboard.write([new ClipboardItem({ "image/png": blob })])
+        .catch((e) => console.warn("unable to write the clipboard", e));
+}
+
+export async function read(on_read) {
+    try {
+        for (const item of await navigator.clipboard.read()) {
+            if (item.types.includes("image/png")) {
+                let blob = await item.getType("image/png");
+                return on_read("image", to_base64(await blob.arrayBuffer()));
+            }
+            if (item.types.includes("text/plain")) {
+                let blob = await item.getType("text/plain");
+                return on_read("text", await blob.text());
+            }
+        }
+        on_read("none", "");
+    } catch (e) {
+        console.warn("unable to read the clipboard", e);
+        on_read("none", "");
+    }
+}
+"#)]
+extern "C" {
+    fn write_text(text: &str);
+    fn write_image(base64: &str);
+    fn read(on_read: &JsValue);
+}
+
+/// the commands which carry out the clipboard requests queued in the update
+pub(crate) fn take_commands<DSP, MSG>(
+) -> Vec<sauron::cmd::Cmd<DSP, BackendMsg<MSG>>>
+where
+    DSP: sauron::Dispatch<BackendMsg<MSG>> + Clone + 'static,
+    MSG: 'static,
+{
+    let mut commands = vec![];
+    for request in clipboard::take_requests::<MSG>() {
+        match request {
+            Request::Write(content) => write(content),
+            Request::Read(reader) => {
+                commands.push(sauron::cmd::Cmd::new(move |program: DSP| {
+                    let on_read = Closure::once_into_js(
+                        move |kind: String, data: String| {
+                            let content = match kind.as_str() {
+                                "text" => Some(Value::String(data)),
+                                "image" => base64::decode(&data)
+                                    .ok()
+                                    .map(Value::Bytes),
+                                _ => None,
+                            };
+                            program.dispatch(BackendMsg::AppMsg(
+                                reader.msg(content),
+                            ));
+                        },
+                    );
+                    read(&on_read);
+                }))
+            }
+        }
+    }
+    commands
+}
+
+fn write(content: Value) {
+    match content {
+        Value::String(text) => write_text(&text),
+        Value::Str(text) => write_text(text),
+        // the browsers only support png images in the clipboard
+        Value::Bytes(bytes) => {
+            match image_util::decode(&bytes).and_then(image_util::encode_png) {
+                Ok(png) => write_image(&base64::encode(&png)),
+                Err(e) => {
+                    log::warn!("unable to put the image on the clipboard: {}", e)
+                }
+            }
+        }
+        _ => log::warn!(
+            "only text and images can be put on the clipboard, not {}",
+            content
+        ),
+    }
+}
@@ -4,8 +4,11 @@ use crate::widget::event::{
 };
 use wasm_bindgen::JsCast;
 
-/// convert html mouse event to sauron native Event
-pub fn from_mouse_event(event: sauron::web_sys::MouseEvent) -> MouseEvent {
+/// convert html mouse event to sauron native Event,
+/// the mouse events which have no equivalent are dropped
+pub fn from_mouse_event(
+    event: sauron::web_sys::MouseEvent,
+) -> Option<MouseEvent> {
     let mouse: &web_sys::MouseEvent =
         event.dyn_ref().expect("Unable to cast to mouse event");
 
@@ -40,16 +43,28 @@ pub fn from_mouse_event(event: sauron::web_sys::MouseEvent) -> MouseEvent {
         "mousedown" => "mousedown",
         "mousemove" => "mousemove",
         "dblclick" => "dblclick",
-        _e => {
-            panic!("unhandled event type: {}", _e);
+        e => {
+            log::warn!("unhandled mouse event type: {}", e);
+            return None;
         }
     };
-    MouseEvent {
+    Some(MouseEvent {
         r#type,
         coordinate,
         modifier,
         buttons,
-    }
+    })
+}
+
+/// convert html mouse event to sauron native Event,
+/// with the x and y relative to the element, such as in a canvas
+pub fn to_local_mouse_event(
+    event: sauron::web_sys::MouseEvent,
+) -> Option<MouseEvent> {
+    let mut mouse_event = from_mouse_event(event)?;
+    mouse_event.coordinate.x = mouse_event.coordinate.offset_x;
+    mouse_event.coordinate.y = mouse_event.coordinate.offset_y;
+    Some(mouse_event)
 }
 
 pub fn to_input_event(input_event: sauron::InputEvent) -> InputEvent {
@@ -57,16 +72,19 @@ pub fn to_input_event(input_event: sauron::InputEvent) -> InputEvent {
     InputEvent::new(input_event.value)
 }
 
-pub fn to_key_event(keyboard_event: &sauron::KeyboardEvent) -> KeyEvent {
-    KeyEvent {
-        key_code: get_keycode(keyboard_event),
+/// convert the html keyboard event, the keys which have no equivalent are dropped
+pub fn to_key_event(
+    keyboard_event: &sauron::KeyboardEvent,
+) -> Option<KeyEvent> {
+    Some(KeyEvent {
+        key_code: get_keycode(keyboard_event)?,
         modifier: get_modifier(keyboard_event),
-    }
+    })
 }
 
-fn get_keycode(keyboard_event: &sauron::KeyboardEvent) -> KeyCode {
+fn get_keycode(keyboard_event: &sauron::KeyboardEvent) -> Option<KeyCode> {
     let keycode = keyboard_event.code();
-    match keycode.as_ref() {
+    let key_code = match keycode.as_ref() {
         "Enter" => KeyCode::Enter,
         "Digit1" => KeyCode::Char('1'),
         "Digit2" => KeyCode::Char('2'),
@@ -104,8 +122,12 @@ fn get_keycode(keyboard_event: &sauron::KeyboardEvent) -> KeyCode {
         "KeyX" => KeyCode::Char('x'),
         "KeyY" => KeyCode::Char('y'),
         "KeyZ" => KeyCode::Char('z'),
-        _ => panic!("not yet implemented for {:?}", keycode),
-    }
+        _ => {
+            log::warn!("not yet implemented for {:?}", keycode);
+            return None;
+        }
+    };
+    Some(key_code)
 }
 
 fn get_modifier(keyboard_event: &sauron::KeyboardEvent) -> Modifier {
@@ -1,18 +1,28 @@
+use super::animation;
 use super::convert_event;
+use super::drag_drop;
+use super::menu;
 use crate::image_util;
-use crate::widget::attribute::util::get_layout;
-use crate::{widget::attribute::find_value, AttribKey, Widget};
+use crate::widget::attribute::util::{get_grid_cell, get_layout};
+use crate::{
+    widget::{
+        attribute::{find_value, ImageScaling},
+        canvas, GridTrack, ImageSource,
+    },
+    AttribKey, Event, Widget,
+};
 use sauron::{
     html::{attributes::*, div, img, input, text},
     prelude::*,
 };
 use std::fmt::Debug;
 
-/// converts widget virtual node tree into an html node tree
+/// converts widget virtual node tree into an html node tree,
+/// the html events which have no equivalent widget event emit None
 pub fn widget_tree_to_html_node<MSG>(
     widget_node: &crate::Node<MSG>,
     cur_node_idx: &mut usize,
-) -> sauron::Node<MSG>
+) -> sauron::Node<Option<MSG>>
 where
     MSG: Clone + Debug + 'static,
 {
@@ -29,24 +39,40 @@ where
 fn widget_to_html<MSG>(
     element: &crate::Element<MSG>,
     cur_node_idx: &mut usize,
-) -> sauron::Node<MSG>
+) -> sauron::Node<Option<MSG>>
 where
     MSG: Clone + Debug + 'static,
 {
     let attrs = element.get_attributes();
+    let node_idx = *cur_node_idx;
 
-    let layout = get_layout(&element).expect("must have a layout");
+    let layout = match get_layout(&element) {
+        Some(layout) => layout,
+        None => {
+            return error_placeholder(crate::Error::MissingLayout(
+                element.tag().clone(),
+            ))
+        }
+    };
     //log::debug!("tag: {:?} layout: {:#?}", element.tag(), layout);
 
     let mut html_children = vec![];
+    // the context menus are not laid out with the other children
+    let mut widget_children = vec![];
+    let mut context_menus = vec![];
     for widget_child in element.get_children().iter() {
         *cur_node_idx += 1;
         // convert all widget child to an html child node
-        let html_child: sauron::Node<MSG> =
+        let html_child: sauron::Node<Option<MSG>> =
             widget_tree_to_html_node(widget_child, cur_node_idx);
-        html_children.push(html_child);
+        if widget_child.tag() == Some(&Widget::ContextMenu) {
+            context_menus.push(html_child);
+        } else {
+            widget_children.push(widget_child);
+            html_children.push(html_child);
+        }
     }
-    match element.tag() {
+    let html_node = match element.tag() {
         Widget::Vbox => div(
             vec![
                 class("Vbox"),
@@ -96,19 +122,20 @@ where
         ),
         // the children in overlay will be all in absolute
         Widget::Overlay => {
-            let children_len = html_children.len();
             html_children
                 .iter_mut()
-                .zip(element.get_children().iter())
-                .enumerate()
-                .for_each(|(_child_index, (html_child, widget_child))| {
-                    let widget_child_element = widget_child
+                .zip(widget_children.iter())
+                .for_each(|(html_child, widget_child)| {
+                    // the child without a layout is already an error placeholder
+                    let child_layout = match widget_child
                         .as_element_ref()
-                        .expect("must be an element");
-                    let child_layout = get_layout(&widget_child_element)
-                        .expect("must have a child layout");
+                        .and_then(|child_element| get_layout(child_element))
+                    {
+                        Some(child_layout) => child_layout,
+                        None => return,
+                    };
 
-                    log::error!(
+                    log::trace!(
                         "child attrs: {:#?}",
                         html_child.get_attributes()
                     );
@@ -117,14 +144,14 @@ where
                         .get_attribute_value(&"style")
                         .unwrap_or(vec![]);
 
-                    log::error!("existing styles: {:#?}", existing_style);
+                    log::trace!("existing styles: {:#?}", existing_style);
 
                     let mut styles: Vec<Style> = existing_style
                         .iter()
                         .flat_map(|s| s.as_style().cloned())
                         .flatten()
                         .collect();
-                    log::error!("flatten styles: {:#?}", styles);
+                    log::trace!("flatten styles: {:#?}", styles);
 
                     // Remove and override the position to absolute
                     if let Some(style_position) =
@@ -139,15 +166,12 @@ where
                     if let Some(style_width) =
                         styles.iter_mut().find(|st| st.name == "width")
                     {
-                        style_width.value = Value::from(px(child_layout
-                            .size
-                            .width
-                            * children_len as f32));
+                        style_width.value =
+                            Value::from(px(child_layout.size.width));
                     } else {
                         styles.push(Style::new(
                             "width",
-                            px(child_layout.size.width * children_len as f32)
-                                .into(),
+                            px(child_layout.size.width).into(),
                         ));
                     }
 
@@ -160,7 +184,7 @@ where
                     } else {
                         styles.push(Style::new(
                             "height",
-                            px(child_layout.size.width as f32).into(),
+                            px(child_layout.size.height).into(),
                         ));
                     }
 
@@ -181,6 +205,60 @@ where
                 html_children,
             )
         }
+        // the children are placed in their cells using css grid
+        Widget::Grid => {
+            html_children
+                .iter_mut()
+                .zip(widget_children.iter())
+                .for_each(|(html_child, widget_child)| {
+                    let cell = get_grid_cell(
+                        widget_child.get_attributes().unwrap_or(&[]),
+                    );
+                    html_child.add_attributes_ref_mut(vec![styles([
+                        (
+                            "grid-row",
+                            format!(
+                                "{} / span {}",
+                                cell.row + 1,
+                                cell.row_span
+                            ),
+                        ),
+                        (
+                            "grid-column",
+                            format!(
+                                "{} / span {}",
+                                cell.column + 1,
+                                cell.column_span
+                            ),
+                        ),
+                    ])]);
+                });
+            let grid_rows = find_value(AttribKey::GridRows, &attrs)
+                .map(|v| v.as_grid_tracks())
+                .flatten()
+                .map(grid_template)
+                .unwrap_or_else(|| "auto".to_string());
+            let grid_columns = find_value(AttribKey::GridColumns, &attrs)
+                .map(|v| v.as_grid_tracks())
+                .flatten()
+                .map(grid_template)
+                .unwrap_or_else(|| "auto".to_string());
+            div(
+                vec![
+                    class("Grid"),
+                    styles([
+                        ("display", "grid".to_string()),
+                        ("grid-template-rows", grid_rows),
+                        ("grid-template-columns", grid_columns),
+                    ]),
+                    styles([
+                        ("width", px(layout.size.width)),
+                        ("height", px(layout.size.height)),
+                    ]),
+                ],
+                html_children,
+            )
+        }
         Widget::GroupBox => div(
             vec![
                 class("GroupBox"),
@@ -244,7 +322,8 @@ where
                         for cb in att.get_callback() {
                             let cb = cb.clone();
                             attributes.push(on_click(move |ev| {
-                                cb.emit(convert_event::from_mouse_event(ev))
+                                convert_event::from_mouse_event(ev)
+                                    .map(|me| cb.emit(me))
                             }))
                         }
                     }
@@ -304,7 +383,7 @@ where
                         for cb in att.get_callback() {
                             let cb = cb.clone();
                             attributes.push(on_input(move |ev| {
-                                cb.emit(convert_event::to_input_event(ev))
+                                Some(cb.emit(convert_event::to_input_event(ev)))
                             }));
                         }
                     }
@@ -312,7 +391,8 @@ where
                         for cb in att.get_callback() {
                             let cb = cb.clone();
                             attributes.push(on_keypress(move |ke| {
-                                cb.emit(convert_event::to_key_event(&ke))
+                                convert_event::to_key_event(&ke)
+                                    .map(|ke| cb.emit(ke))
                             }));
                         }
                     }
@@ -344,7 +424,7 @@ where
                         for cb in att.get_callback() {
                             let cb = cb.clone();
                             attributes.push(on_input(move |ev| {
-                                cb.emit(convert_event::to_input_event(ev))
+                                Some(cb.emit(convert_event::to_input_event(ev)))
                             }));
                         }
                     }
@@ -355,7 +435,7 @@ where
             let is_preformatted = find_value(AttribKey::Preformatted, &attrs)
                 .map(|v| v.as_bool())
                 .unwrap_or(false);
-            log::warn!("preformatted: {}", is_preformatted);
+            log::trace!("preformatted: {}", is_preformatted);
 
             let is_monospace = find_value(AttribKey::Monospace, &attrs)
                 .map(|v| v.as_bool())
@@ -364,13 +444,13 @@ where
             let is_selectable = find_value(AttribKey::Selectable, &attrs)
                 .map(|v| v.as_bool())
                 .unwrap_or(true);
-            let st: Attribute<MSG> = styles_flag([
+            let st: Attribute<Option<MSG>> = styles_flag([
                 ("user-select", "none", !is_selectable),
                 ("font-family", "monospace", is_monospace),
                 ("white-space", "pre", is_preformatted),
             ]);
 
-            log::warn!("st: {:#?}", st);
+            log::trace!("st: {:#?}", st);
 
             textarea(
                 vec![
@@ -440,14 +520,59 @@ where
             )
         }
         Widget::Image => {
-            let empty = vec![];
-            let bytes = find_value(AttribKey::Data, &attrs)
-                .map(|v| v.as_bytes())
-                .flatten()
-                .unwrap_or(&empty);
-
-            let mime_type =
-                image_util::image_mime_type(bytes).expect("unsupported image");
+            let paused = find_value(AttribKey::Paused, &attrs)
+                .map(|v| v.as_bool())
+                .unwrap_or(false);
+            let source = find_value(AttribKey::Source, &attrs)
+                .and_then(|v| v.as_image_source());
+            let by_frames = !paused && animation::is_played_by_frames(&attrs);
+            let mut frame_attributes = vec![];
+            let image_src = match source {
+                // the image files are loaded and cached by the browser itself
+                Some(ImageSource::Path(path)) => {
+                    Ok(path.to_string_lossy().into_owned())
+                }
+                _ if by_frames => {
+                    image_util::image_frames(&attrs).and_then(|frames| {
+                        let (first, attributes) =
+                            animation::play(&frames, &attrs)?;
+                        frame_attributes = attributes;
+                        Ok(first)
+                    })
+                }
+                // the browser plays the animated images natively, as many times as the image specifies,
+                // a paused animation is displayed with its first frame
+                _ => image_util::image_data(&attrs)
+                    .and_then(|bytes| {
+                        if paused {
+                            image_util::decode(&bytes)
+                                .and_then(image_util::encode_png)
+                                .map(|png| ("image/png", png))
+                        } else {
+                            image_util::web_image(&bytes)
+                        }
+                    })
+                    .map(|(mime_type, bytes)| {
+                        format!(
+                            "data:{};base64,{}",
+                            mime_type,
+                            base64::encode(&bytes)
+                        )
+                    }),
+            };
+            let image_src = match image_src {
+                Ok(image_src) => image_src,
+                Err(e) => return error_placeholder(e),
+            };
+            let object_fit = match find_value(AttribKey::ImageScaling, &attrs)
+                .and_then(|v| v.as_image_scaling())
+                .unwrap_or_default()
+            {
+                ImageScaling::Fit => "contain",
+                ImageScaling::Fill => "cover",
+                ImageScaling::Stretch => "fill",
+                ImageScaling::None => "none",
+            };
             div(
                 vec![
                     class("Image"),
@@ -455,16 +580,23 @@ where
                         ("width", px(layout.size.width)),
                         ("height", px(layout.size.height)),
                     ]),
-                    styles([("overflow", "auto")]),
+                    styles([("overflow", "hidden")]),
                 ],
                 vec![img(
-                    vec![src(format!(
-                        "data:{};base64,{}",
-                        mime_type,
-                        base64::encode(bytes)
-                    ))],
+                    vec![
+                        // the img is replaced when it switches between the frame by frame playback
+                        // and the still or natively played image, so the playback starts on load
+                        attr("key", if by_frames { "frames" } else { "image" }),
+                        src(image_src),
+                        styles([
+                            ("width", "100%"),
+                            ("height", "100%"),
+                            ("object-fit", object_fit),
+                        ]),
+                    ],
                     vec![],
-                )],
+                )
+                .add_attributes(frame_attributes)],
             )
         }
         Widget::Svg => {
@@ -501,9 +633,11 @@ where
             ],
             html_children,
         ),
-        Widget::MenuBar => div(vec![], vec![text("menu bar")]),
-        Widget::Menu => div(vec![], vec![text("menu")]),
-        Widget::MenuItem => div(vec![], vec![text("menu item")]),
+        Widget::MenuBar => menu::menu_bar(layout.size.width, html_children),
+        Widget::Menu => menu::menu(html_children),
+        Widget::MenuItem => menu::menu_item(element, html_children),
+        Widget::ContextMenu => menu::context_menu(html_children),
+        Widget::MenuSeparator => menu::menu_separator(),
         Widget::SearchInput => {
             div(vec![], vec![input(vec![type_("text")], vec![])])
         }
@@ -523,7 +657,8 @@ where
                         for cb in att.get_callback() {
                             let cb = cb.clone();
                             attributes.push(on_click(move |ev| {
-                                cb.emit(convert_event::from_mouse_event(ev))
+                                convert_event::from_mouse_event(ev)
+                                    .map(|me| cb.emit(me))
                             }))
                         }
                     }
@@ -544,5 +679,105 @@ where
             )
             .add_attributes(attributes)
         }
+        Widget::Canvas => {
+            let size = (layout.size.width, layout.size.height);
+            let commands = canvas::canvas_commands(&attrs, size.0, size.1);
+            let svg = canvas::to_svg(&commands, size.0, size.1);
+            let chart = find_value(AttribKey::Chart, &attrs)
+                .and_then(|v| v.as_chart())
+                .cloned();
+
+            let mut attributes = vec![];
+            for att in attrs {
+                for cb in att.get_callback() {
+                    let cb = cb.clone();
+                    let emit = move |ev| {
+                        convert_event::to_local_mouse_event(ev)
+                            .map(|me| cb.emit(me))
+                    };
+                    match att.name() {
+                        AttribKey::ClickEvent => {
+                            attributes.push(on_click(emit))
+                        }
+                        AttribKey::MouseDown => {
+                            attributes.push(on_mousedown(emit))
+                        }
+                        AttribKey::MouseUp => attributes.push(on_mouseup(emit)),
+                        AttribKey::MouseMove => {
+                            attributes.push(on_mousemove(emit))
+                        }
+                        // a chart without data points has nothing to report
+                        AttribKey::ChartHoverEvent => {
+                            let chart = match &chart {
+                                Some(chart) => chart.clone(),
+                                None => continue,
+                            };
+                            if chart.nearest((0.0, 0.0), size).is_none() {
+                                continue;
+                            }
+                            let cb = cb.clone();
+                            attributes.push(on_mousemove(move |ev| {
+                                let me =
+                                    convert_event::to_local_mouse_event(ev)?;
+                                let position = (me.x() as f64, me.y() as f64);
+                                let point = chart
+                                    .nearest(position, size)
+                                    .expect("chart has data points");
+                                Some(cb.emit(Event::ChartHover(point)))
+                            }))
+                        }
+                        _ => (),
+                    }
+                }
+            }
+
+            img(
+                vec![
+                    class("Canvas"),
+                    src(format!(
+                        "data:image/svg+xml;base64,{}",
+                        base64::encode(&svg)
+                    )),
+                    styles([
+                        ("width", px(layout.size.width)),
+                        ("height", px(layout.size.height)),
+                    ]),
+                ],
+                vec![],
+            )
+            .add_attributes(attributes)
+        }
+    };
+    let html_node =
+        html_node.add_attributes(drag_drop::attributes(attrs, node_idx));
+    if context_menus.is_empty() {
+        html_node
+    } else {
+        menu::with_context_menus(html_node, context_menus)
     }
 }
+
+/// convert the grid tracks into a css grid template
+fn grid_template(tracks: &[GridTrack]) -> String {
+    tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Points(points) => px(points),
+            GridTrack::Percent(percent) => format!("{}%", percent * 100.0),
+            GridTrack::Fraction(fraction) => format!("{}fr", fraction),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// a text which shows the error in place of the widget that can not be displayed
+fn error_placeholder<MSG>(error: crate::Error) -> sauron::Node<MSG>
+where
+    MSG: Clone + Debug + 'static,
+{
+    log::error!("unable to display the widget: {}", error);
+    div(
+        vec![class("Error"), styles([("color", "red")])],
+        vec![text(format!("[{}]", error))],
+    )
+}
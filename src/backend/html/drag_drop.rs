@@ -0,0 +1,369 @@
+//! drag and drop with the html5 drag events
+//!
+//! The payload of a drag source is kept in the app, keyed by its node index,
+//! and the drag only carries the node index in an app specific type.
+//! The start of a drag and the drag over a drop target have no msg to emit,
+//! so they are handled with inline event handlers.
+//! The browsers only give the content of the dropped files asynchronously,
+//! so a drop of files emits no msg, the files are read in the commands of the update
+//! which follows and the drop is dispatched with their content once they are all read.
+use super::BackendMsg;
+use crate::{
+    widget::{
+        attribute::{find_callback, find_value, DropEffect},
+        event::{DragPayload, DropEvent, DroppedFile},
+    },
+    AttribKey, Event, Value,
+};
+use sauron::{
+    html::{attributes::attr, events::on},
+    web_sys::{DataTransfer, DragEvent, File},
+};
+use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc};
+use wasm_bindgen::{prelude::*, JsCast, JsValue};
+
+#[wasm_bindgen(inline_js = r#"
+export async function read_file(file, on_read) {
+    try {
+        let binary = "";
+        new Uint8Array(await file.arrayBuffer()).forEach((byte) => binary += String.fromCharCode(byte));
+        on_read(btoa(binary));
+    } catch (e) {
+        console.warn("unable to read the dropped file", e);
+        on_read(null);
+    }
+}
+"#)]
+extern "C" {
+    fn read_file(file: &File, on_read: &JsValue);
+}
+
+const APP_TYPE: &str = "application/x-sauron-native";
+
+thread_local! {
+    /// the payload of the drag sources, keyed by their node index
+    static DRAG_DATA: RefCell<HashMap<usize, Value>> =
+        RefCell::new(HashMap::new());
+    /// the drops of files which are emitted once their files are read
+    static FILE_DROPS: RefCell<Vec<FileDrop>> = RefCell::new(vec![]);
+}
+
+/// a drop of files, with the callback of the drop target which creates the msg
+struct FileDrop {
+    drop_event: DropEvent,
+    files: Vec<File>,
+    emit: Box<dyn Fn(Event) -> Box<dyn Any>>,
+}
+
+/// the attributes which make the element a drag source when it has a drag_data,
+/// and a drop target when it has a drop callback
+pub(crate) fn attributes<MSG>(
+    attrs: &[crate::Attribute<MSG>],
+    node_idx: usize,
+) -> Vec<sauron::Attribute<Option<MSG>>>
+where
+    MSG: 'static,
+{
+    let mut attributes = vec![];
+    let effect = find_value(AttribKey::DropEffect, attrs)
+        .and_then(|v| v.as_drop_effect());
+
+    if let Some(data) = find_value(AttribKey::DragData, attrs) {
+        DRAG_DATA.with(|drag_data| {
+            drag_data.borrow_mut().insert(node_idx, data.clone())
+        });
+        let effect_allowed = match effect {
+            Some(effect) => effect_name(effect),
+            None => "copyMove",
+        };
+        attributes.push(attr("draggable", "true"));
+        attributes.push(attr(
+            "ondragstart",
+            format!(
+                "event.dataTransfer.setData('{}', '{}'); event.dataTransfer.effectAllowed = '{}';",
+                APP_TYPE, node_idx, effect_allowed
+            ),
+        ));
+        for cb in
+            find_callback(AttribKey::DragEndEvent, attrs).unwrap_or_default()
+        {
+            let cb = cb.clone();
+            attributes.push(on("dragend", move |event: sauron::Event| {
+                let effect = event
+                    .dyn_ref::<DragEvent>()
+                    .and_then(|event| event.data_transfer())
+                    .map(|data_transfer| {
+                        drop_effect(&data_transfer.drop_effect())
+                    })
+                    .unwrap_or(DropEffect::None);
+                Some(cb.emit(Event::DragEnd(effect)))
+            }));
+        }
+    }
+
+    if let Some(callbacks) = find_callback(AttribKey::DropEvent, attrs) {
+        // the browsers only allow the drop when the drag over is prevented
+        let set_drop_effect = match effect {
+            Some(effect) => format!(
+                " event.dataTransfer.dropEffect = '{}';",
+                effect_name(effect)
+            ),
+            None => String::new(),
+        };
+        attributes.push(attr(
+            "ondragover",
+            format!("event.preventDefault();{}", set_drop_effect),
+        ));
+        for cb in callbacks {
+            let cb = cb.clone();
+            attributes.push(on("drop", move |event: sauron::Event| {
+                event.prevent_default();
+                let drag_event: &DragEvent = event.unchecked_ref();
+                let drop_event = to_drop_event(drag_event);
+                let files = dropped_files(drag_event);
+                if files.is_empty() {
+                    return Some(cb.emit(Event::Drop(drop_event)));
+                }
+                let cb = cb.clone();
+                FILE_DROPS.with(|file_drops| {
+                    file_drops.borrow_mut().push(FileDrop {
+                        drop_event,
+                        files,
+                        emit: Box::new(move |event| Box::new(cb.emit(event))),
+                    })
+                });
+                None
+            }));
+        }
+    }
+    attributes
+}
+
+/// the commands which read the dropped files,
+/// and dispatch the drop once all of its files are read
+pub(crate) fn take_commands<DSP, MSG>(
+) -> Vec<sauron::cmd::Cmd<DSP, BackendMsg<MSG>>>
+where
+    DSP: sauron::Dispatch<BackendMsg<MSG>> + Clone + 'static,
+    MSG: 'static,
+{
+    FILE_DROPS
+        .with(|file_drops| {
+            file_drops.borrow_mut().drain(..).collect::<Vec<_>>()
+        })
+        .into_iter()
+        .map(|file_drop| {
+            sauron::cmd::Cmd::new(move |program: DSP| {
+                read_files(file_drop, program)
+            })
+        })
+        .collect()
+}
+
+fn read_files<DSP, MSG>(file_drop: FileDrop, program: DSP)
+where
+    DSP: sauron::Dispatch<BackendMsg<MSG>> + Clone + 'static,
+    MSG: 'static,
+{
+    let FileDrop {
+        drop_event,
+        files,
+        emit,
+    } = file_drop;
+    let pending =
+        Rc::new(RefCell::new(PendingDrop::new(drop_event, files.len())));
+    let emit = Rc::new(emit);
+    for (index, file) in files.iter().enumerate() {
+        let pending = Rc::clone(&pending);
+        let emit = Rc::clone(&emit);
+        let program = program.clone();
+        let on_read = Closure::once_into_js(move |content: Option<String>| {
+            let drop_event = match pending.borrow_mut().read(index, content) {
+                Some(drop_event) => drop_event,
+                None => return,
+            };
+            match emit(Event::Drop(drop_event)).downcast::<MSG>() {
+                Ok(msg) => program.dispatch(BackendMsg::AppMsg(*msg)),
+                Err(_) => log::error!(
+                    "the drop is emitted as a msg which is not a {}",
+                    std::any::type_name::<MSG>()
+                ),
+            }
+        });
+        read_file(file, &on_read);
+    }
+}
+
+/// a drop of files which are being read
+struct PendingDrop {
+    /// the number of files which are not read yet
+    unread: usize,
+    /// the drop which the content of the files is read into
+    drop_event: DropEvent,
+}
+
+impl PendingDrop {
+    fn new(drop_event: DropEvent, unread: usize) -> Self {
+        PendingDrop { unread, drop_event }
+    }
+
+    /// set the base64 `content` of the file at `index`, which is `None` when it can't be read,
+    /// and return the drop once all of its files are read
+    fn read(
+        &mut self,
+        index: usize,
+        content: Option<String>,
+    ) -> Option<DropEvent> {
+        if let DragPayload::Files(dropped) = &mut self.drop_event.payload {
+            if let Some(dropped) = dropped.get_mut(index) {
+                dropped.data =
+                    content.and_then(|content| base64::decode(&content).ok());
+            }
+        }
+        self.unread = self.unread.saturating_sub(1);
+        if self.unread > 0 {
+            None
+        } else {
+            Some(self.drop_event.clone())
+        }
+    }
+}
+
+/// the files of the drop, these are only accessible in the drop event handler
+fn dropped_files(event: &DragEvent) -> Vec<File> {
+    event
+        .data_transfer()
+        .and_then(|data_transfer| data_transfer.files())
+        .map(|files| (0..files.length()).filter_map(|i| files.get(i)).collect())
+        .unwrap_or_default()
+}
+
+fn to_drop_event(event: &DragEvent) -> DropEvent {
+    let data_transfer = event.data_transfer();
+    DropEvent {
+        payload: data_transfer
+            .as_ref()
+            .map(payload)
+            .unwrap_or(DragPayload::Files(vec![])),
+        x: event.offset_x(),
+        y: event.offset_y(),
+        effect: data_transfer
+            .map(|data_transfer| drop_effect(&data_transfer.drop_effect()))
+            .unwrap_or(DropEffect::None),
+    }
+}
+
+fn payload(data_transfer: &DataTransfer) -> DragPayload {
+    let dragged = data_transfer
+        .get_data(APP_TYPE)
+        .ok()
+        .and_then(|node_idx| node_idx.parse::<usize>().ok())
+        .and_then(|node_idx| {
+            DRAG_DATA
+                .with(|drag_data| drag_data.borrow().get(&node_idx).cloned())
+        });
+    if let Some(value) = dragged {
+        return DragPayload::Value(value);
+    }
+    if let Some(files) = data_transfer.files() {
+        if files.length() > 0 {
+            return DragPayload::Files(
+                (0..files.length())
+                    .filter_map(|i| files.get(i))
+                    .map(|file| DroppedFile {
+                        name: file.name(),
+                        path: None,
+                        data: None,
+                    })
+                    .collect(),
+            );
+        }
+    }
+    DragPayload::Text(data_transfer.get_data("text/plain").unwrap_or_default())
+}
+
+fn effect_name(effect: DropEffect) -> &'static str {
+    match effect {
+        DropEffect::None => "none",
+        DropEffect::Copy => "copy",
+        DropEffect::Move => "move",
+        DropEffect::Link => "link",
+    }
+}
+
+fn drop_effect(name: &str) -> DropEffect {
+    match name {
+        "copy" => DropEffect::Copy,
+        "move" => DropEffect::Move,
+        "link" => DropEffect::Link,
+        _ => DropEffect::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_drop(names: &[&str]) -> DropEvent {
+        DropEvent {
+            payload: DragPayload::Files(
+                names
+                    .iter()
+                    .map(|name| DroppedFile {
+                        name: name.to_string(),
+                        path: None,
+                        data: None,
+                    })
+                    .collect(),
+            ),
+            x: 10,
+            y: 20,
+            effect: DropEffect::Copy,
+        }
+    }
+
+    fn data(drop_event: &DropEvent) -> Vec<Option<Vec<u8>>> {
+        match &drop_event.payload {
+            DragPayload::Files(files) => {
+                files.iter().map(|file| file.data.clone()).collect()
+            }
+            payload => panic!("expecting files, got {:?}", payload),
+        }
+    }
+
+    #[test]
+    fn drop_is_complete_once_all_the_files_are_read() {
+        let mut pending = PendingDrop::new(file_drop(&["a.txt", "b.txt"]), 2);
+        // the files can be read in any order
+        assert_eq!(pending.read(1, Some(base64::encode("bee"))), None);
+        let drop_event = pending
+            .read(0, Some(base64::encode("ay")))
+            .expect("must be complete");
+        assert_eq!((drop_event.x, drop_event.y), (10, 20));
+        assert_eq!(
+            drop_event.payload,
+            DragPayload::Files(vec![
+                DroppedFile {
+                    name: "a.txt".to_string(),
+                    path: None,
+                    data: Some(b"ay".to_vec()),
+                },
+                DroppedFile {
+                    name: "b.txt".to_string(),
+                    path: None,
+                    data: Some(b"bee".to_vec()),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn unreadable_files_have_no_data() {
+        let mut pending = PendingDrop::new(file_drop(&["a.txt", "b.txt"]), 2);
+        assert_eq!(pending.read(0, None), None);
+        let drop_event = pending
+            .read(1, Some("not base64!".to_string()))
+            .expect("must be complete");
+        assert_eq!(data(&drop_event), vec![None, None]);
+    }
+}
@@ -0,0 +1,221 @@
+//! the menus, which are sized by their content instead of their layout
+//!
+//! Showing a submenu or a context menu has no msg to emit,
+//! so they are shown with inline event handlers.
+use super::convert_event;
+use crate::{
+    widget::attribute::{find_callback, find_value},
+    AttribKey, Widget,
+};
+use sauron::{
+    html::{attributes::*, div, img, span, text},
+    prelude::*,
+};
+use std::fmt::Debug;
+
+/// the submenu drops down from a menu bar, and to the side in a menu
+const SHOW_SUBMENU: &str = "let menu = this.querySelector(':scope > .Menu'); \
+    if (menu) { \
+        let in_bar = this.parentElement.classList.contains('MenuBar'); \
+        menu.style.top = in_bar ? '100%' : '0'; \
+        menu.style.left = in_bar ? '0' : '100%'; \
+        menu.style.display = 'flex'; \
+    }";
+
+const HIDE_SUBMENU: &str = "let menu = this.querySelector(':scope > .Menu'); \
+    if (menu) { menu.style.display = 'none'; }";
+
+/// the browsers emit the contextmenu event on a right click, the menu key and a long press.
+/// The context menu is shown at the pointer, and hidden on the next click
+const SHOW_CONTEXT_MENU: &str = "let menu = this.querySelector(':scope > .ContextMenu'); \
+    if (menu) { \
+        event.preventDefault(); \
+        event.stopPropagation(); \
+        menu.style.left = event.clientX + 'px'; \
+        menu.style.top = event.clientY + 'px'; \
+        menu.style.display = 'flex'; \
+        setTimeout(() => document.addEventListener('click', () => menu.style.display = 'none', { once: true })); \
+    }";
+
+/// the menu bar lays out its menu items in a row
+pub(crate) fn menu_bar<MSG>(width: f32, children: Vec<Node<MSG>>) -> Node<MSG>
+where
+    MSG: Clone + Debug + 'static,
+{
+    div(
+        vec![
+            class("MenuBar"),
+            styles([("display", "flex"), ("flex-direction", "row")]),
+            styles([("width", px(width))]),
+        ],
+        children,
+    )
+}
+
+/// the menu is hidden until its menu item is hovered
+pub(crate) fn menu<MSG>(children: Vec<Node<MSG>>) -> Node<MSG>
+where
+    MSG: Clone + Debug + 'static,
+{
+    div(
+        vec![class("Menu"), styles(popup_styles("absolute"))],
+        children,
+    )
+}
+
+/// the menu item shows its label and the value of its text labels as text,
+/// since the labels have no size in the layout of a menu
+pub(crate) fn menu_item<MSG>(
+    element: &crate::Element<MSG>,
+    html_children: Vec<Node<Option<MSG>>>,
+) -> Node<Option<MSG>>
+where
+    MSG: Clone + Debug + 'static,
+{
+    let attrs = element.get_attributes();
+    let disabled = find_value(AttribKey::Disabled, attrs)
+        .map(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let mut attributes = vec![
+        class("MenuItem"),
+        styles([
+            ("position", "relative"),
+            ("display", "flex"),
+            ("align-items", "center"),
+            ("gap", "6px"),
+            ("padding", "4px 8px"),
+            ("white-space", "nowrap"),
+            ("cursor", "default"),
+        ]),
+        styles_flag([("opacity", "0.5", disabled)]),
+        attr("onmouseenter", SHOW_SUBMENU),
+        attr("onmouseleave", HIDE_SUBMENU),
+    ];
+    if !disabled {
+        for cb in
+            find_callback(AttribKey::ClickEvent, attrs).unwrap_or_default()
+        {
+            let cb = cb.clone();
+            attributes.push(on_click(move |ev| {
+                convert_event::from_mouse_event(ev).map(|me| cb.emit(me))
+            }));
+        }
+    }
+
+    let mut children = vec![];
+    if let Some(checked) = find_value(AttribKey::Checked, attrs) {
+        let is_radio = find_value(AttribKey::Name, attrs).is_some();
+        let mark = match (is_radio, checked.as_bool()) {
+            (true, true) => "●",
+            (true, false) => "○",
+            (false, true) => "✓",
+            (false, false) => "",
+        };
+        children.push(span(
+            vec![class("MenuItemMark"), styles([("width", "1em")])],
+            vec![text(mark)],
+        ));
+    }
+    if let Some(bytes) =
+        find_value(AttribKey::SvgImage, attrs).and_then(|v| v.as_bytes())
+    {
+        children.push(img(
+            vec![
+                class("MenuItemIcon"),
+                src(format!(
+                    "data:image/svg+xml;base64,{}",
+                    base64::encode(bytes)
+                )),
+                styles([("width", "16px"), ("height", "16px")]),
+            ],
+            vec![],
+        ));
+    }
+    if let Some(label) = find_value(AttribKey::Label, attrs) {
+        children.push(span(
+            vec![class("MenuItemLabel"), styles([("flex-grow", "1")])],
+            vec![text(label.to_string())],
+        ));
+    }
+    children.extend(element.get_children().iter().zip(html_children).map(
+        |(widget_child, html_child)| match widget_child.tag() {
+            Some(Widget::Label) => {
+                let child_attrs = widget_child.get_attributes().unwrap_or(&[]);
+                let value = find_value(AttribKey::Value, child_attrs)
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
+                text(value)
+            }
+            _ => html_child,
+        },
+    ));
+    if let Some(shortcut) = find_value(AttribKey::Accelerator, attrs) {
+        children.push(span(
+            vec![
+                class("MenuItemAccelerator"),
+                styles([("margin-left", "auto"), ("opacity", "0.6")]),
+            ],
+            vec![text(shortcut.to_string())],
+        ));
+    }
+    div(attributes, children)
+}
+
+/// the separator is a line between the menu items
+pub(crate) fn menu_separator<MSG>() -> Node<MSG>
+where
+    MSG: Clone + Debug + 'static,
+{
+    div(
+        vec![
+            class("MenuSeparator"),
+            styles([("border-top", "1px solid #ccc"), ("margin", "4px 0")]),
+        ],
+        vec![],
+    )
+}
+
+/// the context menu is hidden until it is shown at the pointer
+pub(crate) fn context_menu<MSG>(children: Vec<Node<MSG>>) -> Node<MSG>
+where
+    MSG: Clone + Debug + 'static,
+{
+    div(
+        vec![class("ContextMenu"), styles(popup_styles("fixed"))],
+        children,
+    )
+}
+
+/// wrap the element with its context menus, since the inputs and images can not have children.
+/// The wrapper is sized by the element, and is placed by its parent in place of the element
+pub(crate) fn with_context_menus<MSG>(
+    html_node: Node<MSG>,
+    context_menus: Vec<Node<MSG>>,
+) -> Node<MSG>
+where
+    MSG: Clone + Debug + 'static,
+{
+    let mut children = vec![html_node];
+    children.extend(context_menus);
+    div(
+        vec![
+            class("ContextMenuTarget"),
+            styles([("display", "flex")]),
+            attr("oncontextmenu", SHOW_CONTEXT_MENU),
+        ],
+        children,
+    )
+}
+
+fn popup_styles(position: &'static str) -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("display", "none"),
+        ("position", position),
+        ("flex-direction", "column"),
+        ("z-index", "1000"),
+        ("background-color", "white"),
+        ("border", "1px solid #ccc"),
+        ("box-shadow", "0 2px 6px rgba(0, 0, 0, 0.2)"),
+    ]
+}
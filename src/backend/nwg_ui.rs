@@ -1,9 +1,13 @@
 //! native windows gui backend
 //!
+//! The nwg widgets do not emit their events into the app yet,
+//! so the drag and drop of the widgets and the files dropped from the desktop
+//! are not supported in this backend.
 use super::Dispatch;
 use crate::{
-    widget::attribute::find_value, AttribKey, Attribute, Backend, Component,
-    Node, Widget,
+    instrument,
+    widget::{attribute::find_value, canvas},
+    AttribKey, Attribute, Backend, Component, Error, Node, Widget,
 };
 use image::{bmp::BMPEncoder, ColorType, GenericImageView, ImageEncoder};
 use native_windows_gui as nwg;
@@ -14,12 +18,13 @@ use nwg::{
 use std::{cell::RefCell, fmt, fmt::Debug, marker::PhantomData, rc::Rc};
 use stretch::style::FlexDirection;
 
+mod clipboard;
+
 /// native windows Gui backend
 pub struct NwgBackend<APP, MSG>
 where
     MSG: 'static,
 {
-    #[allow(unused)]
     app: Rc<RefCell<APP>>,
     #[allow(unused)]
     current_vdom: Rc<RefCell<Node<MSG>>>,
@@ -29,13 +34,14 @@ where
 }
 
 impl<APP, MSG> NwgBackend<APP, MSG> {
-    fn new(app: APP) -> Self
+    fn new(app: APP) -> Result<Self, Error>
     where
         MSG: Debug + 'static,
         APP: Component<MSG> + 'static,
     {
-        println!("1 new");
-        nwg::init().expect("Failed to init Native Windows GUI");
+        nwg::init().map_err(|e| {
+            Error::Backend(format!("unable to init nwg: {:?}", e))
+        })?;
         let current_vdom = app.view();
         let root_vdom = app.view();
 
@@ -50,9 +56,9 @@ impl<APP, MSG> NwgBackend<APP, MSG> {
             .position((300, 300))
             .title("Windows Backend")
             .build(&mut window)
-            .unwrap();
-
-        println!("2 new");
+            .map_err(|e| {
+                Error::Backend(format!("unable to build the window: {:?}", e))
+            })?;
 
         let root_widget: Option<NwgWidget> = None;
 
@@ -64,17 +70,12 @@ impl<APP, MSG> NwgBackend<APP, MSG> {
             _phantom_msg: PhantomData,
         };
 
-        println!("3 new");
-        println!("3.1 new");
         let (root_widget, _children_widgets) =
             NwgWidget::from_node_tree(&backend.window, &backend, root_vdom);
-        println!("3.5 new");
         *backend.root_node.borrow_mut() = Some(root_widget);
-        println!("3.6 new");
 
         let events_window = backend.window.clone();
 
-        println!("4 new");
         let handler = nwg::full_bind_event_handler(
             &backend.window.handle,
             move |evt, _evt_data, handle| {
@@ -93,9 +94,8 @@ impl<APP, MSG> NwgBackend<APP, MSG> {
         );
         nwg::dispatch_thread_events();
         nwg::unbind_event_handler(&handler);
-        println!("last part new");
 
-        backend
+        Ok(backend)
     }
 }
 
@@ -105,8 +105,10 @@ where
     MSG: Clone + Debug + 'static,
 {
     fn init(app: APP) {
-        println!("init app..");
-        NwgBackend::new(app);
+        log::trace!(target: instrument::TARGET, "init app..");
+        if let Err(e) = NwgBackend::new(app) {
+            log::error!("unable to start the nwg backend: {}", e);
+        }
     }
 }
 
@@ -115,7 +117,13 @@ where
     MSG: Debug + 'static,
     APP: Component<MSG> + 'static,
 {
-    fn dispatch(&self, _msg: MSG) {}
+    /// the view is not rendered again after the update yet
+    fn dispatch(&self, msg: MSG) {
+        self.app.borrow_mut().update(msg);
+        for msg in clipboard::run_requests(&self.window) {
+            self.dispatch(msg);
+        }
+    }
 }
 
 enum NwgWidget {
@@ -168,10 +176,8 @@ impl NwgWidget {
         MSG: Debug + 'static,
         DSP: Dispatch<MSG> + 'static,
     {
-        println!("from node tree..");
         match widget_node {
             crate::Node::Element(element) => {
-                println!("element...");
                 let children: Vec<(Self, Vec<Self>)> = element
                     .children
                     .into_iter()
@@ -187,12 +193,27 @@ impl NwgWidget {
                     element.tag,
                     &direct,
                     element.attrs,
-                );
+                )
+                .unwrap_or_else(|e| error_placeholder(window, e));
                 all_children.extend(direct);
                 all_children.extend(indirect.into_iter().flatten());
                 (nwg_widget, all_children)
             }
-            crate::Node::Text(_txt) => unreachable!(),
+            crate::Node::Text(txt) => {
+                let mut lbl = Label::default();
+                let nwg_widget = match Label::builder()
+                    .text(&txt.to_string())
+                    .parent(window)
+                    .build(&mut lbl)
+                {
+                    Ok(()) => NwgWidget::Label(lbl),
+                    Err(e) => error_placeholder(
+                        window,
+                        build_error(&Widget::Label)(e),
+                    ),
+                };
+                (nwg_widget, vec![])
+            }
         }
     }
 
@@ -202,15 +223,15 @@ impl NwgWidget {
         widget: Widget,
         children: &Vec<Self>,
         attrs: Vec<Attribute<MSG>>,
-    ) -> Self
+    ) -> Result<Self, Error>
     where
         MSG: Debug + 'static,
         DSP: Dispatch<MSG> + 'static,
     {
-        println!("from node...");
-        match widget {
+        log::trace!(target: instrument::TARGET, "converting {:?}", widget);
+        let build_error = build_error(&widget);
+        let nwg_widget = match &widget {
             Widget::Vbox => {
-                println!("vbox..");
                 let mut box_layout = FlexboxLayout::default();
 
                 let mut builder = FlexboxLayout::builder()
@@ -258,12 +279,11 @@ impl NwgWidget {
                     }
                 }
 
-                builder.build(&mut box_layout).expect("must not error");
+                builder.build(&mut box_layout).map_err(&build_error)?;
 
                 NwgWidget::Box(box_layout)
             }
             Widget::Hbox => {
-                println!("hbox..");
                 let mut box_layout = FlexboxLayout::default();
 
                 let mut builder = FlexboxLayout::builder()
@@ -311,36 +331,33 @@ impl NwgWidget {
                     }
                 }
 
-                builder.build(&mut box_layout).expect("must not error");
+                builder.build(&mut box_layout).map_err(&build_error)?;
 
                 NwgWidget::Box(box_layout)
             }
             Widget::Hpane => {
-                println!("hpane");
                 let mut box_layout = FlexboxLayout::default();
 
                 let builder = FlexboxLayout::builder()
                     .parent(window)
                     .flex_direction(FlexDirection::Row);
 
-                builder.build(&mut box_layout).expect("must not error");
+                builder.build(&mut box_layout).map_err(&build_error)?;
 
                 NwgWidget::Box(box_layout)
             }
             Widget::Vpane => {
-                println!("hpane");
                 let mut box_layout = FlexboxLayout::default();
 
                 let builder = FlexboxLayout::builder()
                     .parent(window)
                     .flex_direction(FlexDirection::Column);
 
-                builder.build(&mut box_layout).expect("must not error");
+                builder.build(&mut box_layout).map_err(&build_error)?;
 
                 NwgWidget::Box(box_layout)
             }
             Widget::Button => {
-                println!("button..");
                 let label = find_value(AttribKey::Label, &attrs)
                     .map(|v| v.to_string())
                     .unwrap_or(String::new());
@@ -351,13 +368,12 @@ impl NwgWidget {
                     .text(&label)
                     .parent(window)
                     .build(&mut btn)
-                    .expect("must build button");
+                    .map_err(&build_error)?;
 
                 NwgWidget::Button(btn)
             }
 
             Widget::Label => {
-                println!("label..");
                 let label_value = find_value(AttribKey::Value, &attrs)
                     .map(|v| v.to_string())
                     .unwrap_or(String::new());
@@ -368,7 +384,7 @@ impl NwgWidget {
                     .text(&label_value)
                     .parent(window)
                     .build(&mut lbl)
-                    .expect("must build button");
+                    .map_err(&build_error)?;
 
                 NwgWidget::Label(lbl)
             }
@@ -382,12 +398,11 @@ impl NwgWidget {
                     .text(&txt)
                     .parent(window)
                     .build(&mut rtb)
-                    .expect("must build rich textbox");
+                    .map_err(&build_error)?;
 
                 NwgWidget::Paragraph(rtb)
             }
             Widget::TextInput => {
-                println!("textinput..");
                 let value = find_value(AttribKey::Value, &attrs)
                     .map(|v| v.to_string())
                     .unwrap_or(String::new());
@@ -398,12 +413,11 @@ impl NwgWidget {
                     .text(&value)
                     .parent(window)
                     .build(&mut text_input)
-                    .expect("must build text input");
+                    .map_err(&build_error)?;
 
                 NwgWidget::TextInput(text_input)
             }
             Widget::TextArea => {
-                println!("textinput..");
                 let value = find_value(AttribKey::Value, &attrs)
                     .map(|v| v.to_string())
                     .unwrap_or(String::new());
@@ -414,13 +428,12 @@ impl NwgWidget {
                     .text(&value)
                     .parent(window)
                     .build(&mut text_box)
-                    .expect("must build textbox");
+                    .map_err(&build_error)?;
 
                 NwgWidget::TextArea(text_box)
             }
 
             Widget::Checkbox => {
-                println!("checkbox..");
                 let label = find_value(AttribKey::Label, &attrs)
                     .map(|v| v.to_string())
                     .unwrap_or(String::new());
@@ -434,12 +447,11 @@ impl NwgWidget {
                     .text(&label)
                     .parent(window)
                     .build(&mut checkbox)
-                    .expect("must build checkbox");
+                    .map_err(&build_error)?;
 
                 NwgWidget::Checkbox(checkbox)
             }
             Widget::Radio => {
-                println!("radio button..");
                 let label = find_value(AttribKey::Label, &attrs)
                     .map(|v| v.to_string())
                     .unwrap_or(String::new());
@@ -453,7 +465,7 @@ impl NwgWidget {
                     .text(&label)
                     .parent(window)
                     .build(&mut radio)
-                    .expect("must build checkbox");
+                    .map_err(&build_error)?;
 
                 NwgWidget::Radio(radio)
             }
@@ -464,24 +476,22 @@ impl NwgWidget {
                     .flatten()
                     .unwrap_or(&empty);
 
-                let img = image::load_from_memory(&blob).expect("should load");
+                let img = image::load_from_memory(&blob)?;
                 let (width, height) = img.dimensions();
                 let mut bytes: Vec<u8> = vec![];
 
-                BMPEncoder::new(&mut bytes)
-                    .write_image(
-                        &img.to_rgb().into_raw(),
-                        width,
-                        height,
-                        ColorType::Rgb8,
-                    )
-                    .expect("must write image");
+                BMPEncoder::new(&mut bytes).write_image(
+                    &img.to_rgb().into_raw(),
+                    width,
+                    height,
+                    ColorType::Rgb8,
+                )?;
 
                 let mut bitmap = Bitmap::default();
                 Bitmap::builder()
                     .source_bin(Some(&bytes))
                     .build(&mut bitmap)
-                    .expect("must not error");
+                    .map_err(&build_error)?;
 
                 let mut image_frame = ImageFrame::default();
                 ImageFrame::builder()
@@ -489,7 +499,7 @@ impl NwgWidget {
                     .bitmap(Some(&bitmap))
                     .parent(window)
                     .build(&mut image_frame)
-                    .expect("must build image_frame");
+                    .map_err(&build_error)?;
 
                 NwgWidget::Image(image_frame, bitmap)
             }
@@ -499,49 +509,19 @@ impl NwgWidget {
                     .map(|v| v.as_bytes())
                     .flatten()
                     .unwrap_or(&empty);
-                let rtree = resvg::usvg::Tree::from_data(
-                    &bytes,
-                    &resvg::usvg::Options::default(),
-                )
-                .expect("must be parse into tree");
-                let svg_size = rtree.svg_node().size;
-                let (width, height) =
-                    (svg_size.width() as u32, svg_size.height() as u32);
-                let backend = resvg::default_backend();
-                let mut img = backend
-                    .render_to_image(&rtree, &resvg::Options::default())
-                    .expect("must render to image");
-                let rgba_vec = img.make_rgba_vec();
-                let rgba_raw: Vec<u8> = rgba_vec.chunks(4).flat_map(|pixel|
-                    // make transparent pixel white
-                    if pixel[3] == 0 {
-                        vec![255,255,255]
-                    }else{
-                        vec![pixel[0], pixel[1], pixel[2]]
-                    }
-                 ).collect();
-
-                let mut bytes: Vec<u8> = vec![];
-
-                BMPEncoder::new(&mut bytes)
-                    .write_image(&rgba_raw, width, height, ColorType::Rgb8)
-                    .expect("must write image");
-
-                let mut bitmap = Bitmap::default();
-                Bitmap::builder()
-                    .source_bin(Some(&bytes))
-                    .build(&mut bitmap)
-                    .expect("must not error");
-
-                let mut image_frame = ImageFrame::default();
-                ImageFrame::builder()
-                    .size((width as i32, height as i32))
-                    .bitmap(Some(&bitmap))
-                    .parent(window)
-                    .build(&mut image_frame)
-                    .expect("must build image_frame");
-
-                NwgWidget::Image(image_frame, bitmap)
+                svg_image_frame(window, Widget::Svg, bytes)?
+            }
+            // the canvas is rasterized with resvg using its raqote backend,
+            // the same as the svg images
+            Widget::Canvas => {
+                let (width, height) = find_value(AttribKey::Layout, &attrs)
+                    .and_then(|v| v.as_layout())
+                    .map(|layout| (layout.size.width, layout.size.height))
+                    .filter(|(width, height)| *width > 0.0 && *height > 0.0)
+                    .unwrap_or(DEFAULT_CANVAS_SIZE);
+                let commands = canvas::canvas_commands(&attrs, width, height);
+                let svg = canvas::to_svg(&commands, width, height);
+                svg_image_frame(window, Widget::Canvas, svg.as_bytes())?
             }
 
             // TODO:
@@ -552,7 +532,7 @@ impl NwgWidget {
                     .parent(window)
                     .flex_direction(FlexDirection::Row);
 
-                builder.build(&mut box_layout).expect("must not error");
+                builder.build(&mut box_layout).map_err(&build_error)?;
 
                 NwgWidget::Overlay(box_layout)
             }
@@ -564,22 +544,20 @@ impl NwgWidget {
                     .parent(window)
                     .flex_direction(FlexDirection::Row);
 
-                builder.build(&mut box_layout).expect("must not error");
+                builder.build(&mut box_layout).map_err(&build_error)?;
 
                 NwgWidget::GroupBox(box_layout)
             }
-        }
+            // the other widgets have no nwg control yet, and show a placeholder instead
+            _ => return Err(Error::UnsupportedWidget(widget.clone())),
+        };
+        Ok(nwg_widget)
     }
     /*
     fn add_children(&self, children: &Vec<Self>) {
-        println!("adding children...");
-        for child in children.iter() {
-            println!("child: {:?}", child);
-        }
         match self {
             NwgWidget::Box(container) => {
                 for (i, child) in children.iter().enumerate() {
-                    println!("child {}", i);
                     match child {
                         NwgWidget::Box(child) => (),
                         NwgWidget::Button(child) => container.add_child(i as u32, child),
@@ -603,3 +581,79 @@ impl NwgWidget {
     }
     */
 }
+
+/// the size of a canvas which has no computed layout
+const DEFAULT_CANVAS_SIZE: (f32, f32) = (400.0, 200.0);
+
+/// rasterize the svg into a bitmap shown in an image frame
+fn svg_image_frame(
+    window: &Window,
+    widget: Widget,
+    bytes: &[u8],
+) -> Result<NwgWidget, Error> {
+    let build_error = build_error(&widget);
+    let rtree =
+        resvg::usvg::Tree::from_data(bytes, &resvg::usvg::Options::default())
+            .map_err(|e| Error::InvalidImage(e.to_string()))?;
+    let svg_size = rtree.svg_node().size;
+    let (width, height) = (svg_size.width() as u32, svg_size.height() as u32);
+    let backend = resvg::default_backend();
+    let mut img = backend
+        .render_to_image(&rtree, &resvg::Options::default())
+        .ok_or_else(|| {
+            Error::InvalidImage("unable to render the svg".to_string())
+        })?;
+    let rgba_vec = img.make_rgba_vec();
+    let rgba_raw: Vec<u8> = rgba_vec.chunks(4).flat_map(|pixel|
+        // make transparent pixel white
+        if pixel[3] == 0 {
+            vec![255,255,255]
+        }else{
+            vec![pixel[0], pixel[1], pixel[2]]
+        }
+     ).collect();
+
+    let mut bytes: Vec<u8> = vec![];
+
+    BMPEncoder::new(&mut bytes).write_image(
+        &rgba_raw,
+        width,
+        height,
+        ColorType::Rgb8,
+    )?;
+
+    let mut bitmap = Bitmap::default();
+    Bitmap::builder()
+        .source_bin(Some(&bytes))
+        .build(&mut bitmap)
+        .map_err(&build_error)?;
+
+    let mut image_frame = ImageFrame::default();
+    ImageFrame::builder()
+        .size((width as i32, height as i32))
+        .bitmap(Some(&bitmap))
+        .parent(window)
+        .build(&mut image_frame)
+        .map_err(&build_error)?;
+
+    Ok(NwgWidget::Image(image_frame, bitmap))
+}
+
+/// convert the error of building the nwg control of the widget
+fn build_error(widget: &Widget) -> impl Fn(nwg::NwgError) -> Error + '_ {
+    move |e| Error::Backend(format!("unable to build {:?}: {:?}", widget, e))
+}
+
+/// a label which shows the error in place of the widget that can not be displayed
+fn error_placeholder(window: &Window, error: Error) -> NwgWidget {
+    log::error!("unable to display the widget: {}", error);
+    let mut lbl = Label::default();
+    if let Err(e) = Label::builder()
+        .text(&format!("[{}]", error))
+        .parent(window)
+        .build(&mut lbl)
+    {
+        log::error!("unable to build the error placeholder: {:?}", e);
+    }
+    NwgWidget::Label(lbl)
+}
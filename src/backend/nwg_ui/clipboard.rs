@@ -0,0 +1,47 @@
+//! the clipboard of windows, read and written with nwg
+//!
+//! nwg only reads and writes text on the clipboard,
+//! so an image is not put on the clipboard and an image on the clipboard is read as None.
+use crate::{
+    clipboard::{self, Request},
+    Value,
+};
+use native_windows_gui as nwg;
+use nwg::{Clipboard, Window};
+
+/// carry out the clipboard requests queued in the update,
+/// returning the msgs of the content which is read
+pub(crate) fn run_requests<MSG>(window: &Window) -> Vec<MSG>
+where
+    MSG: 'static,
+{
+    let mut msgs = vec![];
+    for request in clipboard::take_requests::<MSG>() {
+        match request {
+            Request::Write(content) => write(window, content),
+            Request::Read(reader) => {
+                let content = Clipboard::data_text(window).map(Value::String);
+                msgs.push(reader.msg(content));
+            }
+        }
+    }
+    msgs
+}
+
+fn write(window: &Window, content: Value) {
+    match &content {
+        Value::String(_) | Value::Str(_) => {
+            let text = content.as_str().unwrap_or_default();
+            Clipboard::set_data_text(window, text);
+        }
+        Value::Bytes(_) => {
+            log::warn!(
+                "the image is not put on the clipboard, nwg only supports text"
+            )
+        }
+        _ => log::warn!(
+            "only text and images can be put on the clipboard, not {}",
+            content
+        ),
+    }
+}
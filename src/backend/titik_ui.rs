@@ -1,8 +1,16 @@
 //! functionalities specific to titik ui
 //!
+//! The animated images are not played in the terminal, since the titik renderer
+//! only redraws on terminal events, they are displayed with their first frame.
+//! The context menus are not supported, titik has no popups to show them in.
+//! The inspector is written into a file instead of a panel,
+//! see the [inspector](crate::inspector) module.
 use crate::{
+    inspector::{self, Inspector},
+    instrument::{self, Phase, Span},
     widget::{
         attribute::{find_callback, find_value},
+        layout::LayoutTree,
         Widget,
     },
     AttribKey, Attribute, Backend, Component, Node,
@@ -12,18 +20,28 @@ use mt_dom::Callback;
 use std::{
     cell::RefCell,
     fmt::Debug,
+    fs,
     io::{self},
     marker::PhantomData,
+    path::PathBuf,
     rc::Rc,
 };
+use stretch::{geometry::Size, number::Number};
 use titik::{
     renderer::Renderer, Button, Checkbox, Dispatch, FlexBox, GroupBox, Image,
     Link, Radio, TextArea, TextInput, TextLabel, Widget as Control,
 };
 
 mod apply_patches;
+mod clipboard;
 mod convert_event;
 mod convert_widget;
+mod grid;
+mod svg;
+
+/// the size of a terminal cell in the points of the computed layout,
+/// a cell is about twice as tall as it is wide
+pub(crate) const CELL_SIZE: (f32, f32) = (10.0, 20.0);
 
 /// Titik Backend
 pub struct TitikBackend<APP, MSG>
@@ -32,6 +50,10 @@ where
 {
     app: Rc<RefCell<APP>>,
     current_dom: Rc<RefCell<Node<MSG>>>,
+    layout_tree: RefCell<LayoutTree>,
+    inspector: RefCell<Inspector>,
+    /// the file the inspector is written to, the inspector is enabled when it is set
+    inspector_path: Option<PathBuf>,
     _phantom_msg: PhantomData<MSG>,
 }
 
@@ -40,6 +62,20 @@ where
     APP: Component<MSG> + 'static,
     MSG: Debug + 'static,
 {
+    /// write the inspector report of the current view into its file
+    fn write_inspector(&self) {
+        if let Some(path) = self.inspector_path.as_ref() {
+            let report =
+                self.inspector.borrow().report(&self.current_dom.borrow());
+            if let Err(e) = fs::write(path, report) {
+                log::error!(
+                    "unable to write the inspector to {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
 }
 
 impl<APP, MSG> Backend<APP, MSG> for TitikBackend<APP, MSG>
@@ -49,45 +85,130 @@ where
 {
     fn init(app: APP) {
         let mut stdout = io::stdout();
-        let vdom = app.view();
-        let current_dom = app.view();
-        let mut root_node = convert_widget::from_node_tree(vdom);
+        let mut layout_tree = LayoutTree::new();
+        let mut current_dom = app.view();
+        crate::widget::evict_unused_lazy();
+        layout_tree.rebuild(&current_dom);
+        layout_tree.compute(&mut current_dom, terminal_size());
+        let mut root_node = convert_widget::from_node_tree(&current_dom);
+        let inspector_path = inspector::output_path();
+        let mut inspector = Inspector::new();
+        if inspector_path.is_some() {
+            inspector.toggle();
+        }
 
         let backend = TitikBackend {
             app: Rc::new(RefCell::new(app)),
             current_dom: Rc::new(RefCell::new(current_dom)),
+            layout_tree: RefCell::new(layout_tree),
+            inspector: RefCell::new(inspector),
+            inspector_path,
             _phantom_msg: PhantomData,
         };
+        backend.write_inspector();
         let mut renderer =
             Renderer::new(&mut stdout, Some(&backend), root_node.as_mut());
         renderer.run().expect("must run");
     }
 }
 
-impl<APP, MSG> Dispatch<MSG> for TitikBackend<APP, MSG>
+impl<APP, MSG> Dispatch<Option<MSG>> for TitikBackend<APP, MSG>
 where
     MSG: Debug + 'static,
     APP: Component<MSG> + 'static,
 {
     /// root_node is added as argument in this dispatch function so that they are in the same
     /// borrow, otherwise an AlreadyBorrowedError will be invoke at runtime.
-    fn dispatch(&self, msg: MSG, root_node: &mut dyn titik::Widget<MSG>) {
-        eprintln!("dispatching... {:?}", msg);
+    /// The widgets emit None for the titik events which have no equivalent, these are dropped.
+    fn dispatch(
+        &self,
+        msg: Option<MSG>,
+        root_node: &mut dyn titik::Widget<Option<MSG>>,
+    ) {
+        let msg = match msg {
+            Some(msg) => msg,
+            None => return,
+        };
+        log::debug!("dispatching... {:?}", msg);
+        self.inspector.borrow_mut().start_dispatch();
+        let span = Span::enter(Phase::Update);
         self.app.borrow_mut().update(msg);
-        let new_view = self.app.borrow().view();
-        let current_view = self.app.borrow().view();
+        let update_duration = span.finish();
+        let span = Span::enter(Phase::View);
+        let mut new_view = self.app.borrow().view();
+        crate::widget::evict_unused_lazy();
+        let view_duration = span.finish();
+
+        let span = Span::enter(Phase::ComputeLayout);
+        self.layout_tree
+            .borrow_mut()
+            .update(&mut new_view, terminal_size());
+        let layout_duration = span.finish();
 
         {
             let previous_dom = self.current_dom.borrow();
+            let span = Span::enter(Phase::Diff);
             let diff = mt_dom::diff_with_key(
                 &previous_dom,
                 &new_view,
                 &AttribKey::Key,
             );
-            eprintln!("diff: {:#?}", diff);
-            apply_patches::apply_patches(&self, root_node, &diff);
+            let diff_duration = span.finish();
+            instrument::record_patches(&diff);
+            log::trace!("diff: {:#?}", diff);
+            let span = Span::enter(Phase::ApplyPatches);
+            if let Err(e) = apply_patches::apply_patches(
+                &self,
+                root_node,
+                &previous_dom,
+                &diff,
+            ) {
+                log::error!("unable to apply the patches, re-rendering: {}", e);
+                rerender(root_node, &new_view);
+            }
+            let patch_duration = span.finish();
+
+            let mut inspector = self.inspector.borrow_mut();
+            inspector.record_timing("update", update_duration);
+            inspector.record_timing("view", view_duration);
+            inspector.record_timing("layout", layout_duration);
+            inspector.record_timing("diff", diff_duration);
+            inspector.record_timing("apply_patches", patch_duration);
+            if inspector.is_enabled() {
+                inspector.record_patches(&diff);
+            }
+        }
+
+        *self.current_dom.borrow_mut() = new_view;
+        self.write_inspector();
+
+        for msg in clipboard::run_requests() {
+            self.dispatch(Some(msg), root_node);
         }
+    }
+}
+
+/// replace the children of the root widget with the widgets converted from the view,
+/// the root widget itself is kept since the renderer holds on to it
+fn rerender<MSG>(
+    root_node: &mut dyn titik::Widget<Option<MSG>>,
+    view: &Node<MSG>,
+) where
+    MSG: Debug + 'static,
+{
+    let mut new_root = convert_widget::from_node_tree(view);
+    while root_node.take_child(0).is_some() {}
+    while let Some(child) = new_root.take_child(0) {
+        root_node.add_child(child);
+    }
+}
 
-        *self.current_dom.borrow_mut() = current_view;
+/// the size of the terminal in the points of the layout, the widgets are laid out
+/// in points so the sizes of the widgets are the same as in the other backends
+fn terminal_size() -> Size<Number> {
+    let (columns, rows) = termion::terminal_size().unwrap_or((80, 24));
+    Size {
+        width: Number::Defined(columns as f32 * CELL_SIZE.0),
+        height: Number::Defined(rows as f32 * CELL_SIZE.1),
     }
 }
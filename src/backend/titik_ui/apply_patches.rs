@@ -1,42 +1,63 @@
-use super::convert_widget;
-use crate::{AttribKey, Attribute, Patch, Widget};
-use std::fmt::Debug;
+use super::{convert_widget, grid};
+use crate::{AttribKey, Attribute, Error, Node, Patch, Widget};
+use std::{any::type_name, fmt::Debug};
 use titik::{Button, TextArea};
 
 pub fn apply_patches<MSG, DSP>(
     _program: &DSP,
-    root_node: &mut dyn titik::Widget<MSG>,
+    root_node: &mut dyn titik::Widget<Option<MSG>>,
+    old_dom: &Node<MSG>,
     patches: &[Patch<MSG>],
-) where
+) -> Result<(), Error>
+where
     MSG: Debug + 'static,
 {
+    // the node index of the patches is of the old widget tree
+    let widget_indexes = grid::widget_indexes(old_dom);
     for patch in patches {
         let patch_node_idx = patch.node_idx();
-        let widget: &mut dyn titik::Widget<MSG> =
-            titik::find_widget_mut(root_node, patch_node_idx)
-                .expect("must have a node to patch");
+        let widget_index = widget_indexes
+            .get(patch_node_idx)
+            .ok_or(Error::MissingNode(patch_node_idx))?;
+        let widget: &mut dyn titik::Widget<Option<MSG>> =
+            titik::find_widget_mut(root_node, widget_index.titik_idx)
+                .ok_or(Error::MissingNode(patch_node_idx))?;
         match patch {
+            // the children of a grid are put into its rows from their layout,
+            // so the grid is converted again when they are moved
+            Patch::AddAttributes(_tag, _node_idx, attrs)
+                if widget_index.in_grid
+                    && attrs
+                        .iter()
+                        .any(|att| *att.name() == AttribKey::Layout) =>
+            {
+                return Err(Error::UnsupportedPatch(Widget::Grid));
+            }
+            Patch::AppendChildren(Widget::Grid, _node_idx, _)
+            | Patch::RemoveChildren(Widget::Grid, _node_idx, _) => {
+                return Err(Error::UnsupportedPatch(Widget::Grid));
+            }
             Patch::AddAttributes(tag, _node_idx, attrs) => {
-                eprintln!("setting attributes...");
-                set_widget_attributes::<MSG>(tag, widget, attrs);
+                log::trace!("setting attributes...");
+                set_widget_attributes::<MSG>(tag, widget, attrs)?;
             }
-            Patch::AppendChildren(_tag, _node_idx, children) => {
-                eprintln!("adding children..");
+            Patch::AppendChildren(tag, _node_idx, children) => {
+                log::trace!("adding children..");
                 for child in children {
-                    let child_element =
-                        child.as_element_ref().expect("must be an element");
-                    let child_widget = convert_widget::from_node(
-                        &child_element.tag,
-                        &child_element.attrs,
-                    );
-                    eprintln!("added 1 {:?} to {:?}", child_widget, widget);
+                    let child_widget = convert_widget::from_node_tree(child);
+                    log::trace!("added 1 {:?} to {:?}", child_widget, widget);
                     let added = widget.add_child(child_widget);
-                    eprintln!("widget becomes: {:?}", widget);
-                    assert!(added);
+                    log::trace!("widget becomes: {:?}", widget);
+                    if !added {
+                        return Err(Error::WidgetMismatch {
+                            widget: tag.clone(),
+                            expected: "container",
+                        });
+                    }
                 }
             }
             Patch::RemoveChildren(_tag, _node_idx, children_index) => {
-                eprintln!("truncating children..");
+                log::trace!("truncating children..");
                 let mut sorted_children_index = children_index.clone();
                 sorted_children_index.sort();
                 for child_index in sorted_children_index.iter().rev() {
@@ -44,22 +65,20 @@ pub fn apply_patches<MSG, DSP>(
                 }
             }
             // todo for other patches here.
-            _ => eprintln!("todo for: {:?}", patch),
+            _ => log::warn!("todo for: {:?}", patch),
         }
     }
+    Ok(())
 }
 
 fn set_widget_attributes<MSG: 'static>(
     tag: &crate::Widget,
-    widget: &mut dyn titik::Widget<MSG>,
+    widget: &mut dyn titik::Widget<Option<MSG>>,
     attrs: &[&Attribute<MSG>],
-) {
+) -> Result<(), Error> {
     match tag {
         Widget::TextArea => {
-            let text_area: &mut TextArea<MSG> = widget
-                .as_any_mut()
-                .downcast_mut()
-                .expect("must be a textarea");
+            let text_area: &mut TextArea<Option<MSG>> = downcast(tag, widget)?;
             for att in attrs {
                 for value in att.get_plain() {
                     match att.name() {
@@ -72,10 +91,7 @@ fn set_widget_attributes<MSG: 'static>(
             }
         }
         Widget::Button => {
-            let btn: &mut Button<MSG> = widget
-                .as_any_mut()
-                .downcast_mut()
-                .expect("must be a button");
+            let btn: &mut Button<Option<MSG>> = downcast(tag, widget)?;
 
             for att in attrs {
                 for value in att.get_plain() {
@@ -88,6 +104,28 @@ fn set_widget_attributes<MSG: 'static>(
                 }
             }
         }
-        _ => eprintln!("todo for other widgets.. {:?}", tag),
+        // the canvas is drawn from all of its attributes at its laid out size,
+        // so it is converted again when any of them changes
+        Widget::Canvas => return Err(Error::UnsupportedPatch(tag.clone())),
+        _ => log::warn!("todo for other widgets.. {:?}", tag),
     }
+    Ok(())
+}
+
+/// cast the titik widget into the type expected for the widget
+fn downcast<'a, T, MSG>(
+    tag: &crate::Widget,
+    widget: &'a mut dyn titik::Widget<Option<MSG>>,
+) -> Result<&'a mut T, Error>
+where
+    T: 'static,
+    MSG: 'static,
+{
+    widget
+        .as_any_mut()
+        .downcast_mut()
+        .ok_or_else(|| Error::WidgetMismatch {
+            widget: tag.clone(),
+            expected: type_name::<T>(),
+        })
 }
@@ -0,0 +1,65 @@
+//! the clipboard of the terminal, written with the OSC 52 escape sequence
+//!
+//! Most terminals do not allow reading the clipboard with OSC 52,
+//! and the reply would be mixed into the input of the renderer,
+//! so the clipboard is read from the content last written by the app.
+//! OSC 52 only carries text, so an image is only kept for the app to read back.
+use crate::{
+    clipboard::{self, Request},
+    Value,
+};
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+};
+
+thread_local! {
+    static CONTENT: RefCell<Option<Value>> = RefCell::new(None);
+}
+
+/// carry out the clipboard requests queued in the update,
+/// returning the msgs of the content which is read
+pub(crate) fn run_requests<MSG>() -> Vec<MSG>
+where
+    MSG: 'static,
+{
+    let mut msgs = vec![];
+    for request in clipboard::take_requests::<MSG>() {
+        match request {
+            Request::Write(content) => write(content),
+            Request::Read(reader) => {
+                let content = CONTENT.with(|c| c.borrow().clone());
+                msgs.push(reader.msg(content));
+            }
+        }
+    }
+    msgs
+}
+
+fn write(content: Value) {
+    match &content {
+        Value::String(_) | Value::Str(_) => {
+            let text = content.as_str().unwrap_or_default();
+            if let Err(e) = write_osc52(text) {
+                log::warn!("unable to write the clipboard: {}", e);
+            }
+        }
+        Value::Bytes(_) => {
+            log::debug!("the image is not put on the clipboard of the terminal")
+        }
+        _ => {
+            log::warn!(
+                "only text and images can be put on the clipboard, not {}",
+                content
+            );
+            return;
+        }
+    }
+    CONTENT.with(|c| *c.borrow_mut() = Some(content));
+}
+
+fn write_osc52(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64::encode(text))?;
+    stdout.flush()
+}
@@ -53,9 +53,9 @@ fn from_titik_key_event(tke: titik::event::KeyEvent) -> KeyEvent {
     }
 }
 
-// TODO:
-pub fn from_titik(t_event: titik::Event) -> Event {
-    match t_event {
+/// convert the titik event, the events which have no equivalent are dropped
+pub fn from_titik(t_event: titik::Event) -> Option<Event> {
+    let event = match t_event {
         titik::Event::Key(ke) => {
             let key_event = from_titik_key_event(ke);
             Event::KeyEvent(key_event)
@@ -68,7 +68,8 @@ pub fn from_titik(t_event: titik::Event) -> Event {
             titik::event::MouseEvent::Up(_, _, _, _) => {
                 Event::MouseEvent(MouseEvent::release(0, 0))
             }
-            _ => todo!(),
+            // the other mouse events such as drag and scroll have no equivalent yet
+            _ => return None,
         },
         titik::Event::InputEvent(ie) => {
             Event::InputEvent(InputEvent::from(from_titik_value(ie.value)))
@@ -76,5 +77,6 @@ pub fn from_titik(t_event: titik::Event) -> Event {
         titik::Event::Resize(width, height) => {
             Event::Resize(width as f32, height as f32)
         }
-    }
+    };
+    Some(event)
 }
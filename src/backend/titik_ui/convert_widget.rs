@@ -1,29 +1,37 @@
-use super::convert_event;
+use super::{convert_event, grid, svg, CELL_SIZE};
 use crate::{
+    image_util,
     widget::{
         attribute::{find_callback, find_value},
-        Widget,
+        canvas, Widget,
     },
-    AttribKey, Attribute, Backend, Component, Node,
+    AttribKey, Attribute, Backend, Component, Error, Node,
 };
-use image::GenericImageView;
 use mt_dom::Callback;
 use std::fmt::Debug;
+use stretch::style::Dimension;
 use titik::{
     renderer::Renderer, Button, Checkbox, Dispatch, FlexBox, GroupBox, Image,
     Link, Radio, TextArea, TextInput, TextLabel, Widget as Control,
 };
 
+/// the size in points of a canvas which is not sized by its layout, 40 by 10 cells
+const DEFAULT_CANVAS_SIZE: (f32, f32) = (400.0, 200.0);
+
 pub(crate) fn from_node_tree<MSG>(
-    widget_node: crate::Node<MSG>,
-) -> Box<dyn titik::Widget<MSG>>
+    widget_node: &crate::Node<MSG>,
+) -> Box<dyn titik::Widget<Option<MSG>>>
 where
     MSG: Debug + 'static,
 {
     match widget_node {
         crate::Node::Element(element) => {
             let mut control = from_node(&element.tag, &element.attrs);
-            for child in element.children {
+            if element.tag == Widget::Grid {
+                grid::add_children(control.as_mut(), &element.children);
+                return control;
+            }
+            for child in element.children.iter() {
                 let child_widget = from_node_tree(child);
                 control.add_child(child_widget);
             }
@@ -36,7 +44,7 @@ where
 pub(crate) fn from_node<MSG>(
     widget: &Widget,
     attrs: &[Attribute<MSG>],
-) -> Box<dyn titik::Widget<MSG>>
+) -> Box<dyn titik::Widget<Option<MSG>>>
 where
     MSG: Debug + 'static,
 {
@@ -62,6 +70,12 @@ where
             hbox.horizontal();
             Box::new(hbox)
         }
+        // the grid children are put in rows of the grid from their layout
+        Widget::Grid => {
+            let mut grid = FlexBox::new();
+            grid.vertical();
+            Box::new(grid)
+        }
         Widget::GroupBox => {
             let mut groupbox = GroupBox::new();
             let label = find_value(AttribKey::Label, &attrs)
@@ -75,7 +89,7 @@ where
                 .map(|v| v.to_string())
                 .unwrap_or(String::new());
 
-            let mut btn: Button<MSG> = Button::new(&label);
+            let mut btn: Button<Option<MSG>> = Button::new(&label);
             if let Some(callbacks) =
                 find_callback(AttribKey::ClickEvent, &attrs)
             {
@@ -83,7 +97,8 @@ where
                     let cb = cb.clone();
                     btn.add_click_listener(Callback::from(
                         move |t_event: titik::Event| {
-                            cb.emit(convert_event::from_titik(t_event))
+                            convert_event::from_titik(t_event)
+                                .map(|event| cb.emit(event))
                         },
                     ));
                 }
@@ -118,11 +133,12 @@ where
                 find_callback(AttribKey::InputEvent, &attrs)
             {
                 for cb in callbacks {
-                    eprintln!("checkbox has an input event");
+                    log::trace!("checkbox has an input event");
                     let cb = cb.clone();
                     checkbox.add_input_listener(Callback::from(
                         move |t_event: titik::Event| {
-                            cb.emit(convert_event::from_titik(t_event))
+                            convert_event::from_titik(t_event)
+                                .map(|event| cb.emit(event))
                         },
                     ));
                 }
@@ -144,42 +160,62 @@ where
             Box::new(rb)
         }
         Widget::Image => {
+            // the titik renderer owns the event loop and only redraws on terminal events,
+            // it has no timer to advance the frames on, so the animated images are displayed
+            // with their first frame and the animation end callbacks are never emitted
+            let loaded = image_util::image_frames(&attrs).and_then(|frames| {
+                Ok((
+                    image_util::image_data(&attrs)?,
+                    frames[0].image.dimensions(),
+                ))
+            });
+            match loaded {
+                Ok((bytes, (width, height))) => {
+                    let mut img = Image::new(bytes);
+                    img.set_size(
+                        Some(width as f32 / 10.0),
+                        Some(height as f32 / 10.0 / 2.0),
+                    );
+                    Box::new(img)
+                }
+                Err(e) => error_placeholder(e),
+            }
+        }
+        Widget::Svg => {
             let empty = vec![];
             let bytes = find_value(AttribKey::Data, &attrs)
                 .map(|v| v.as_bytes())
                 .flatten()
                 .unwrap_or(&empty);
-            let image = image::load_from_memory(&bytes).expect("should load");
-            let mut img = Image::new(bytes.to_vec());
-            let (width, height) = image.dimensions();
-            img.set_size(
-                Some(width as f32 / 10.0),
-                Some(height as f32 / 10.0 / 2.0),
-            );
-            Box::new(img)
+            let rendered = svg_cells(bytes, attrs).and_then(|cells| {
+                svg::rasterize(bytes, cells).map(|png| (cells, png))
+            });
+            match rendered {
+                Ok(((width, height), png)) => {
+                    let mut img = Image::new(png);
+                    img.set_size(Some(width), Some(height));
+                    Box::new(img)
+                }
+                Err(e) => error_placeholder(e),
+            }
         }
-        Widget::Svg => todo!(),
         Widget::TextArea => {
             let value = find_value(AttribKey::Value, &attrs)
                 .map(|v| v.to_string())
                 .unwrap_or(String::new());
-            let height = find_value(AttribKey::Height, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
-            let width = find_value(AttribKey::Width, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
+            let (width, height) = widget_size(&attrs);
             let mut textarea = TextArea::new(value);
             textarea.set_size(width, height);
             if let Some(callbacks) =
                 find_callback(AttribKey::InputEvent, &attrs)
             {
                 for cb in callbacks {
-                    eprintln!("textarea has an input event");
+                    log::trace!("textarea has an input event");
                     let cb = cb.clone();
                     textarea.add_input_listener(Callback::from(
                         move |t_event: titik::Event| {
-                            cb.emit(convert_event::from_titik(t_event))
+                            convert_event::from_titik(t_event)
+                                .map(|event| cb.emit(event))
                         },
                     ));
                 }
@@ -200,12 +236,7 @@ where
         Widget::HeaderBar => {
             //TODO: make a header bar equivalent in titik ui
             let mut flex = FlexBox::new();
-            let height = find_value(AttribKey::Height, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
-            let width = find_value(AttribKey::Width, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
+            let (width, height) = widget_size(&attrs);
             flex.set_size(width, height);
             Box::new(flex)
         }
@@ -213,12 +244,7 @@ where
             let mut flex = FlexBox::new();
             flex.set_border(true);
             flex.horizontal();
-            let height = find_value(AttribKey::Height, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
-            let width = find_value(AttribKey::Width, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
+            let (width, height) = widget_size(&attrs);
             flex.set_size(width, height);
             Box::new(flex)
         }
@@ -226,28 +252,75 @@ where
             let mut flex = FlexBox::new();
             flex.set_border(true);
             flex.vertical();
-            let height = find_value(AttribKey::Height, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
-            let width = find_value(AttribKey::Width, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
+            let (width, height) = widget_size(&attrs);
             flex.set_size(width, height);
             Box::new(flex)
         }
+        //TODO: titik has no menu item with a submenu, so a menu item with a label
+        // is shown as a button with its check mark and accelerator as text,
+        // and can not have a submenu. The icons are not shown in the terminal
+        Widget::MenuItem if find_value(AttribKey::Label, &attrs).is_some() => {
+            let label = find_value(AttribKey::Label, &attrs)
+                .map(|v| v.to_string())
+                .unwrap_or(String::new());
+            let mark = match find_value(AttribKey::Checked, &attrs) {
+                Some(checked) => {
+                    let is_radio =
+                        find_value(AttribKey::Name, &attrs).is_some();
+                    match (is_radio, checked.as_bool()) {
+                        (true, true) => "(*) ",
+                        (true, false) => "( ) ",
+                        (false, true) => "[x] ",
+                        (false, false) => "[ ] ",
+                    }
+                }
+                None => "",
+            };
+            let accelerator = find_value(AttribKey::Accelerator, &attrs)
+                .map(|v| format!("  {}", v))
+                .unwrap_or(String::new());
+            let disabled = find_value(AttribKey::Disabled, &attrs)
+                .map(|v| v.as_bool())
+                .unwrap_or(false);
+
+            let mut btn: Button<Option<MSG>> =
+                Button::new(&format!("{}{}{}", mark, label, accelerator));
+            if let (false, Some(callbacks)) =
+                (disabled, find_callback(AttribKey::ClickEvent, &attrs))
+            {
+                for cb in callbacks {
+                    let cb = cb.clone();
+                    btn.add_click_listener(Callback::from(
+                        move |t_event: titik::Event| {
+                            convert_event::from_titik(t_event)
+                                .map(|event| cb.emit(event))
+                        },
+                    ));
+                }
+            }
+            Box::new(btn)
+        }
         Widget::MenuItem => {
             let mut flex = FlexBox::new();
             flex.set_border(true);
             flex.vertical();
-            let height = find_value(AttribKey::Height, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
-            let width = find_value(AttribKey::Width, &attrs)
-                .map(|v| v.as_f64().map(|v| v as f32))
-                .flatten();
+            let (width, height) = widget_size(&attrs);
             flex.set_size(width, height);
             Box::new(flex)
         }
+        Widget::MenuSeparator => {
+            let separator = TextLabel::new("─".repeat(16));
+            Box::new(separator)
+        }
+        // titik has no popups and no right click, the context menu is not supported
+        // and is kept as a hidden list of its menu items, so the widgets are still
+        // at the node index of the patches
+        Widget::ContextMenu => {
+            let mut flex = FlexBox::new();
+            flex.vertical();
+            flex.set_size(Some(0.0), Some(0.0));
+            Box::new(flex)
+        }
         Widget::SearchInput => {
             // TODO: make a search input equivalent in titik
             let mut search_input = TextInput::new("");
@@ -257,16 +330,118 @@ where
             let label = find_value(AttribKey::Label, &attrs)
                 .map(|v| v.to_string())
                 .unwrap_or(String::new());
-            println!("link label: {}", label);
+            log::trace!("link label: {}", label);
 
             let uri = find_value(AttribKey::Uri, &attrs)
                 .map(|v| v.to_string())
                 .unwrap_or(String::new());
 
-            println!("link uri: {}", uri);
+            log::trace!("link uri: {}", uri);
 
             let mut link = Link::new(uri, label);
             Box::new(link)
         }
+        // the mouse events of titik have no coordinates,
+        // so the canvas is only drawn and a chart has no hover events
+        Widget::Canvas => {
+            let (width, height) = canvas_size(attrs);
+            let commands = canvas::canvas_commands(&attrs, width, height);
+            let svg = canvas::to_svg(&commands, width, height);
+            let cells = (width / CELL_SIZE.0, height / CELL_SIZE.1);
+            let rendered = svg::braille(svg.as_bytes(), cells);
+            match rendered {
+                Ok(braille) => Box::new(TextLabel::new(braille)),
+                Err(e) => error_placeholder(e),
+            }
+        }
     }
 }
+
+/// the size in points of the canvas from its computed layout, the canvas has no content
+/// so an axis which is not sized by the layout takes the size of the default canvas
+fn canvas_size<MSG>(attrs: &[Attribute<MSG>]) -> (f32, f32)
+where
+    MSG: 'static,
+{
+    let (width, height) = find_value(AttribKey::Layout, attrs)
+        .and_then(|v| v.as_layout())
+        .map(|layout| (layout.size.width, layout.size.height))
+        .unwrap_or((0.0, 0.0));
+    (
+        if width >= CELL_SIZE.0 {
+            width
+        } else {
+            DEFAULT_CANVAS_SIZE.0
+        },
+        if height >= CELL_SIZE.1 {
+            height
+        } else {
+            DEFAULT_CANVAS_SIZE.1
+        },
+    )
+}
+
+/// the size in cells of the svg, from its width and height attributes,
+/// or from its computed layout, or else its original size
+fn svg_cells<MSG>(
+    bytes: &[u8],
+    attrs: &[Attribute<MSG>],
+) -> Result<(f32, f32), Error>
+where
+    MSG: 'static,
+{
+    if let (Some(width), Some(height)) = widget_size(attrs) {
+        return Ok((width, height));
+    }
+    let layout = find_value(AttribKey::Layout, attrs)
+        .map(|v| v.as_layout())
+        .flatten();
+    match layout {
+        Some(layout) => Ok((
+            layout.size.width / CELL_SIZE.0,
+            layout.size.height / CELL_SIZE.1,
+        )),
+        None => svg::original_size(bytes),
+    }
+}
+
+/// the size in cells of the widget from its width and height attributes,
+/// a dimension which is not in points, such as a percentage, takes the size
+/// of its computed layout
+fn widget_size<MSG>(attrs: &[Attribute<MSG>]) -> (Option<f32>, Option<f32>)
+where
+    MSG: 'static,
+{
+    let layout = find_value(AttribKey::Layout, attrs)
+        .map(|v| v.as_layout())
+        .flatten();
+    let size = |key, layout_size: Option<f32>| match find_value(key, attrs)
+        .map(|v| v.as_dimension())
+        .flatten()
+    {
+        Some(Dimension::Points(points)) => Some(points),
+        Some(_) => layout_size,
+        None => None,
+    };
+    (
+        size(
+            AttribKey::Width,
+            layout.map(|layout| layout.size.width / CELL_SIZE.0),
+        ),
+        size(
+            AttribKey::Height,
+            layout.map(|layout| layout.size.height / CELL_SIZE.1),
+        ),
+    )
+}
+
+/// a label which shows the error in place of the widget that can not be displayed
+pub(crate) fn error_placeholder<MSG>(
+    error: Error,
+) -> Box<dyn titik::Widget<Option<MSG>>>
+where
+    MSG: Debug + 'static,
+{
+    log::error!("unable to display the widget: {}", error);
+    Box::new(TextLabel::new(format!("[{}]", error)))
+}
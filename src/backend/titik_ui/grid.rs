@@ -0,0 +1,173 @@
+//! lays out the grid children in the terminal from their computed layout
+//!
+//! titik has no absolute positioning, so the children of a grid are put into horizontal rows,
+//! grouped by the line of their computed location and ordered by their column,
+//! with blank labels for the gaps between them. A child which spans several rows
+//! is put in the row of its first line.
+//!
+//! The rows and the gaps are titik widgets which have no node in the widget tree,
+//! so the node index of the patches is mapped to the index of its titik widget.
+use super::{convert_widget, CELL_SIZE};
+use crate::{widget::attribute::util::get_layout, Node, Widget};
+use std::{collections::BTreeMap, fmt::Debug};
+use titik::{FlexBox, TextLabel, Widget as Control};
+
+/// a row of the grid, with the height of its tallest child in cells
+struct Row {
+    height: f32,
+    items: Vec<Item>,
+}
+
+/// an item in a row of the grid
+enum Item {
+    /// a gap of this number of columns
+    Gap(usize),
+    /// the child of the grid at this index
+    Child(usize),
+}
+
+/// the titik widget of a node of the widget tree
+pub(crate) struct WidgetIndex {
+    /// the depth first index of the titik widget
+    pub titik_idx: usize,
+    /// whether the node is a child of a grid, which is positioned from its layout
+    pub in_grid: bool,
+}
+
+/// the titik widgets of the children of the grid, put into their rows
+pub(crate) fn add_children<MSG>(
+    grid: &mut dyn titik::Widget<Option<MSG>>,
+    children: &[Node<MSG>],
+) where
+    MSG: Debug + 'static,
+{
+    for row in rows(children) {
+        let mut row_box = FlexBox::new();
+        row_box.horizontal();
+        row_box.set_size(None, Some(row.height));
+        for item in row.items {
+            let widget: Box<dyn titik::Widget<Option<MSG>>> = match item {
+                Item::Gap(columns) => {
+                    Box::new(TextLabel::new(" ".repeat(columns)))
+                }
+                Item::Child(index) => {
+                    convert_widget::from_node_tree(&children[index])
+                }
+            };
+            row_box.add_child(widget);
+        }
+        grid.add_child(Box::new(row_box));
+    }
+}
+
+/// the titik widget of each node of the widget tree, in the order of the node index
+pub(crate) fn widget_indexes<MSG>(node: &Node<MSG>) -> Vec<WidgetIndex> {
+    let mut indexes = vec![];
+    collect_indexes(node, 0, false, &mut indexes);
+    indexes
+}
+
+fn collect_indexes<MSG>(
+    node: &Node<MSG>,
+    titik_idx: usize,
+    in_grid: bool,
+    indexes: &mut Vec<WidgetIndex>,
+) {
+    indexes.push(WidgetIndex { titik_idx, in_grid });
+    let children = node.get_children().unwrap_or(&[]);
+    let is_grid = is_grid(node);
+    let mut starts = vec![0; children.len()];
+    let mut next = titik_idx + 1;
+    if is_grid {
+        for row in rows(children) {
+            next += 1;
+            for item in row.items {
+                match item {
+                    Item::Gap(_) => next += 1,
+                    Item::Child(index) => {
+                        starts[index] = next;
+                        next += widget_count(&children[index]);
+                    }
+                }
+            }
+        }
+    } else {
+        for (start, child) in starts.iter_mut().zip(children) {
+            *start = next;
+            next += widget_count(child);
+        }
+    }
+    for (child, start) in children.iter().zip(starts) {
+        collect_indexes(child, start, is_grid, indexes);
+    }
+}
+
+/// the number of titik widgets of the node and its descendants
+fn widget_count<MSG>(node: &Node<MSG>) -> usize {
+    let children = node.get_children().unwrap_or(&[]);
+    let wrappers = if is_grid(node) {
+        rows(children)
+            .iter()
+            .map(|row| {
+                1 + row
+                    .items
+                    .iter()
+                    .filter(|item| matches!(item, Item::Gap(_)))
+                    .count()
+            })
+            .sum()
+    } else {
+        0
+    };
+    1 + wrappers + children.iter().map(widget_count).sum::<usize>()
+}
+
+fn is_grid<MSG>(node: &Node<MSG>) -> bool {
+    node.tag() == Some(&Widget::Grid)
+}
+
+/// group the children into rows by the line of their location, the children
+/// which have no layout are put in a last row in the order they are declared
+fn rows<MSG>(children: &[Node<MSG>]) -> Vec<Row> {
+    let mut lines: BTreeMap<i64, Vec<(f32, f32, f32, usize)>> = BTreeMap::new();
+    for (index, child) in children.iter().enumerate() {
+        let layout = match child {
+            Node::Element(element) => get_layout(element),
+            Node::Text(_) => None,
+        };
+        let (line, column, width, height) = match layout {
+            Some(layout) => (
+                (layout.location.y / CELL_SIZE.1).round() as i64,
+                layout.location.x / CELL_SIZE.0,
+                layout.size.width / CELL_SIZE.0,
+                layout.size.height / CELL_SIZE.1,
+            ),
+            None => (i64::MAX, 0.0, 0.0, 1.0),
+        };
+        lines
+            .entry(line)
+            .or_default()
+            .push((column, width, height, index));
+    }
+    lines
+        .into_iter()
+        .map(|(_line, mut children)| {
+            children.sort_by(|a, b| {
+                a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            let mut cursor = 0.0;
+            let mut height: f32 = 1.0;
+            let mut items = vec![];
+            for (column, width, child_height, index) in children {
+                let gap = (column - cursor).round();
+                if gap >= 1.0 {
+                    items.push(Item::Gap(gap as usize));
+                }
+                items.push(Item::Child(index));
+                cursor = column + width;
+                height = height.max(child_height);
+            }
+            Row { height, items }
+        })
+        .collect()
+}
@@ -0,0 +1,120 @@
+//! rasterize svg into an image, which titik draws into terminal cells
+//! using half block characters with truecolor, or into braille characters
+use crate::Error;
+use image::{DynamicImage, ImageOutputFormat, RgbaImage};
+
+/// a half block character draws 2 pixels vertically in one cell
+const PIXELS_PER_CELL: (u32, u32) = (1, 2);
+
+/// the size in cells of the svg data when displayed at its original size,
+/// using the same scale as the raster images
+pub(crate) fn original_size(bytes: &[u8]) -> Result<(f32, f32), Error> {
+    let tree = parse(bytes)?;
+    let size = tree.svg_node().size;
+    Ok((
+        size.width() as f32 / 10.0,
+        size.height() as f32 / 10.0 / 2.0,
+    ))
+}
+
+/// render the svg to fit into the given number of cells, keeping its aspect ratio,
+/// returns the png encoded image
+pub(crate) fn rasterize(
+    bytes: &[u8],
+    cells: (f32, f32),
+) -> Result<Vec<u8>, Error> {
+    let image = render(
+        bytes,
+        (
+            cells.0 * PIXELS_PER_CELL.0 as f32,
+            cells.1 * PIXELS_PER_CELL.1 as f32,
+        ),
+    )?;
+    let mut png = vec![];
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut png, ImageOutputFormat::Png)?;
+    Ok(png)
+}
+
+/// render the svg to fit into the given number of cells as braille characters,
+/// each cell has 2 by 4 dots which are set where the svg is drawn
+pub(crate) fn braille(
+    bytes: &[u8],
+    cells: (f32, f32),
+) -> Result<String, Error> {
+    let (columns, rows) = (cells.0.max(1.0) as u32, cells.1.max(1.0) as u32);
+    let image = render(bytes, ((columns * 2) as f32, (rows * 4) as f32))?;
+    let is_drawn = |x: u32, y: u32| {
+        x < image.width()
+            && y < image.height()
+            && image.get_pixel(x, y)[3] > 127
+    };
+    let mut lines = vec![];
+    for row in 0..rows {
+        let mut line = String::new();
+        for column in 0..columns {
+            let (x, y) = (column * 2, row * 4);
+            let dots = BRAILLE_DOTS
+                .iter()
+                .filter(|(dx, dy, _)| is_drawn(x + dx, y + dy))
+                .fold(0, |dots, (_, _, bit)| dots | bit);
+            line.push(std::char::from_u32(BRAILLE_BLANK + dots).unwrap_or(' '));
+        }
+        lines.push(line);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// the blank braille character, the dots are added to it
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// the position of each dot in a braille cell and its bit
+const BRAILLE_DOTS: [(u32, u32, u32); 8] = [
+    (0, 0, 0x01),
+    (0, 1, 0x02),
+    (0, 2, 0x04),
+    (1, 0, 0x08),
+    (1, 1, 0x10),
+    (1, 2, 0x20),
+    (0, 3, 0x40),
+    (1, 3, 0x80),
+];
+
+/// render the svg to fit into the size in pixels, keeping its aspect ratio
+fn render(
+    bytes: &[u8],
+    (max_width, max_height): (f32, f32),
+) -> Result<RgbaImage, Error> {
+    let tree = parse(bytes)?;
+    let svg_size = tree.svg_node().size;
+    let (max_width, max_height) =
+        (max_width.max(1.0) as f64, max_height.max(1.0) as f64);
+    let scale =
+        (max_width / svg_size.width()).min(max_height / svg_size.height());
+    let width = (svg_size.width() * scale).ceil().max(1.0) as u32;
+
+    let fit_to = resvg::FitTo::Width(width);
+    // the size of the rendered image is the one resvg fits the svg into,
+    // the height is rounded by resvg independently of the width
+    let size = fit_to.fit_to(svg_size.to_screen_size()).ok_or_else(|| {
+        Error::InvalidImage("unable to fit the svg".to_string())
+    })?;
+    let options = resvg::Options {
+        fit_to,
+        ..Default::default()
+    };
+    let mut rendered = resvg::default_backend()
+        .render_to_image(&tree, &options)
+        .ok_or_else(|| {
+            Error::InvalidImage("unable to render the svg".to_string())
+        })?;
+    let rgba = rendered.make_rgba_vec();
+    RgbaImage::from_raw(size.width(), size.height(), rgba).ok_or_else(|| {
+        Error::InvalidImage("unexpected size of the rendered svg".to_string())
+    })
+}
+
+fn parse(bytes: &[u8]) -> Result<resvg::usvg::Tree, Error> {
+    resvg::usvg::Tree::from_data(bytes, &resvg::usvg::Options::default())
+        .map_err(|e| Error::InvalidImage(e.to_string()))
+}
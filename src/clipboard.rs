@@ -0,0 +1,249 @@
+//! Reading and writing the system clipboard
+//!
+//! The clipboard is written and read by queueing a request, which the backend
+//! carries out after the app is updated, so they can be made in `Component::update`
+//! or in an event callback.
+//! The text is a `Value::String`, and an image is a `Value::Bytes` of its encoded data,
+//! a png when it is read from the clipboard.
+//! The nwg clipboard only has text, so an image is not written there and is read back as None.
+//!
+//! The content is read into the msg of the app, a child component which reads the clipboard
+//! is updated with `Child::update_with` so its msg is wrapped into the msg of the app.
+//!
+//! ```rust,ignore
+//! fn update(&mut self, msg: Msg) {
+//!     match msg {
+//!         Msg::Copy => clipboard::write_text(self.log.join("\n")),
+//!         Msg::Paste => clipboard::read(Msg::Pasted),
+//!         Msg::Pasted(Some(Value::String(text))) => self.input.push_str(&text),
+//!         Msg::Pasted(_) => (),
+//!     }
+//! }
+//! ```
+use crate::Value;
+use std::{
+    any::{type_name, Any},
+    cell::RefCell,
+    fmt, mem,
+    rc::Rc,
+};
+
+thread_local! {
+    static REQUESTS: RefCell<Vec<Queued>> = RefCell::new(vec![]);
+}
+
+/// A request in the queue, the reader is kept with the name of the msg type it creates
+enum Queued {
+    Write(Value),
+    Read {
+        msg_type_name: &'static str,
+        /// a `Reader` of the msg type
+        reader: Box<dyn Any>,
+    },
+}
+
+/// A clipboard request queued for the backend
+pub(crate) enum Request<MSG> {
+    /// put the text or the image on the clipboard
+    Write(Value),
+    /// read the clipboard and emit the msg with its content
+    Read(Reader<MSG>),
+}
+
+/// Creates the msg with the content of the clipboard
+pub(crate) struct Reader<MSG>(Box<dyn FnOnce(Option<Value>) -> MSG>);
+
+impl<MSG> Reader<MSG> {
+    /// the msg with the content of the clipboard
+    pub(crate) fn msg(self, content: Option<Value>) -> MSG {
+        (self.0)(content)
+    }
+}
+
+impl<MSG> fmt::Debug for Request<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Request::Write(content) => write!(f, "Write({})", content),
+            Request::Read(_) => write!(f, "Read"),
+        }
+    }
+}
+
+/// put the text on the clipboard
+pub fn write_text(text: impl Into<String>) {
+    write(Value::String(text.into()))
+}
+
+/// put the content on the clipboard,
+/// a `Value::String` or `Value::Str` as text, and a `Value::Bytes` as an image.
+/// The nwg clipboard only has text, so the images are not put on it
+pub fn write(content: Value) {
+    REQUESTS
+        .with(|requests| requests.borrow_mut().push(Queued::Write(content)));
+}
+
+/// read the content of the clipboard and emit it as the msg,
+/// the content is None when the clipboard is empty or can not be read.
+/// The nwg clipboard only has text, so an image on it is read as None.
+///
+/// The msg is the msg of the app, a child component which reads the clipboard
+/// is updated with `Child::update_with`, which wraps its msg into the msg of the app.
+pub fn read<F, MSG>(func: F)
+where
+    F: FnOnce(Option<Value>) -> MSG + 'static,
+    MSG: 'static,
+{
+    let reader: Reader<MSG> = Reader(Box::new(func));
+    REQUESTS.with(|requests| {
+        requests.borrow_mut().push(Queued::Read {
+            msg_type_name: type_name::<MSG>(),
+            reader: Box::new(reader),
+        })
+    });
+}
+
+/// wrap the msg of the reads queued by a child component with `wrap`,
+/// so they are read into the msg of its parent
+pub(crate) fn map_reads<MSG, PMSG, F>(wrap: F)
+where
+    MSG: 'static,
+    PMSG: 'static,
+    F: Fn(MSG) -> PMSG + 'static,
+{
+    let wrap = Rc::new(wrap);
+    REQUESTS.with(|requests| {
+        for queued in requests.borrow_mut().iter_mut() {
+            if let Queued::Read {
+                msg_type_name,
+                reader,
+            } = queued
+            {
+                let child_reader = match mem::replace(reader, Box::new(()))
+                    .downcast::<Reader<MSG>>()
+                {
+                    Ok(child_reader) => child_reader,
+                    // the read of another component
+                    Err(other) => {
+                        *reader = other;
+                        continue;
+                    }
+                };
+                let wrap = Rc::clone(&wrap);
+                let parent_reader: Reader<PMSG> =
+                    Reader(Box::new(move |content| {
+                        wrap(child_reader.msg(content))
+                    }));
+                *msg_type_name = type_name::<PMSG>();
+                *reader = Box::new(parent_reader);
+            }
+        }
+    });
+}
+
+/// take the requests queued since the last time,
+/// which are then carried out by the backend of the app with the msg `MSG`.
+/// The reads into another msg are dropped, these are from the child components
+/// which are not updated with `Child::update_with`
+pub(crate) fn take_requests<MSG>() -> Vec<Request<MSG>>
+where
+    MSG: 'static,
+{
+    let queued: Vec<Queued> =
+        REQUESTS.with(|requests| requests.borrow_mut().drain(..).collect());
+    queued
+        .into_iter()
+        .filter_map(|queued| match queued {
+            Queued::Write(content) => Some(Request::Write(content)),
+            Queued::Read {
+                msg_type_name,
+                reader,
+            } => match reader.downcast::<Reader<MSG>>() {
+                Ok(reader) => Some(Request::Read(*reader)),
+                Err(_) => {
+                    log::error!(
+                        "the clipboard is read into a {} which is not a {}, \
+                        the child component is to be updated with `Child::update_with`",
+                        msg_type_name,
+                        type_name::<MSG>()
+                    );
+                    None
+                }
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum ChildMsg {
+        Pasted(Option<Value>),
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Msg {
+        Pasted(Option<Value>),
+        Child(ChildMsg),
+    }
+
+    fn read_msgs<MSG>(content: Option<Value>) -> Vec<MSG>
+    where
+        MSG: 'static,
+    {
+        take_requests::<MSG>()
+            .into_iter()
+            .filter_map(|request| match request {
+                Request::Read(reader) => Some(reader.msg(content.clone())),
+                Request::Write(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn requests_are_taken_in_order() {
+        write_text("copied");
+        read(Msg::Pasted);
+        write(Value::Bytes(vec![1, 2, 3]));
+        let requests = take_requests::<Msg>();
+        assert_eq!(
+            format!("{:?}", requests),
+            format!(
+                "[Write({}), Read, Write({})]",
+                Value::String("copied".to_string()),
+                Value::Bytes(vec![1, 2, 3])
+            )
+        );
+        let pasted = requests.into_iter().find_map(|request| match request {
+            Request::Read(reader) => {
+                Some(reader.msg(Some(Value::String("pasted".to_string()))))
+            }
+            Request::Write(_) => None,
+        });
+        assert_eq!(
+            pasted,
+            Some(Msg::Pasted(Some(Value::String("pasted".to_string()))))
+        );
+        // the requests are only carried out once
+        assert!(take_requests::<Msg>().is_empty());
+    }
+
+    #[test]
+    fn child_reads_are_wrapped_into_the_parent_msg() {
+        read(ChildMsg::Pasted);
+        read(Msg::Pasted);
+        map_reads(Msg::Child);
+        assert_eq!(
+            read_msgs::<Msg>(None),
+            vec![Msg::Child(ChildMsg::Pasted(None)), Msg::Pasted(None)]
+        );
+    }
+
+    #[test]
+    fn reads_into_another_msg_are_dropped() {
+        read(ChildMsg::Pasted);
+        assert_eq!(read_msgs::<Msg>(None), vec![]);
+        assert!(take_requests::<ChildMsg>().is_empty());
+    }
+}
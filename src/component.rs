@@ -1,4 +1,5 @@
-use crate::Node;
+use crate::{clipboard, Callback, Event, Node};
+use std::{marker::PhantomData, rc::Rc};
 
 /// Applications must implement Component
 pub trait Component<MSG> {
@@ -13,3 +14,135 @@ pub trait Component<MSG> {
     /// returns a Node tree for rendering the view
     fn view(&self) -> Node<MSG>;
 }
+
+/// Convert the msg emitted by the callbacks of a node tree,
+/// so the view of a child component can be embedded in the view of its parent
+pub trait MapMsg<MSG> {
+    /// wrap the msg of every callback in the tree with `func`
+    fn map_msg<F, MSG2>(self, func: F) -> Node<MSG2>
+    where
+        F: Fn(MSG) -> MSG2 + 'static,
+        MSG2: 'static;
+}
+
+impl<MSG> MapMsg<MSG> for Node<MSG>
+where
+    MSG: 'static,
+{
+    fn map_msg<F, MSG2>(self, func: F) -> Node<MSG2>
+    where
+        F: Fn(MSG) -> MSG2 + 'static,
+        MSG2: 'static,
+    {
+        map_node_msg(self, &Rc::new(func))
+    }
+}
+
+fn map_node_msg<MSG, MSG2, F>(node: Node<MSG>, func: &Rc<F>) -> Node<MSG2>
+where
+    MSG: 'static,
+    MSG2: 'static,
+    F: Fn(MSG) -> MSG2 + 'static,
+{
+    match node {
+        Node::Element(element) => {
+            let mut attrs = vec![];
+            for att in element.attrs.iter() {
+                for value in att.get_plain() {
+                    attrs.push(mt_dom::attr(att.name().clone(), value.clone()));
+                }
+                for cb in att.get_callback() {
+                    let cb = cb.clone();
+                    let func = Rc::clone(func);
+                    let mapped_cb = Callback::from(move |event: Event| {
+                        func(cb.emit(event))
+                    });
+                    attrs.push(mt_dom::on(att.name().clone(), mapped_cb));
+                }
+            }
+            let children = element
+                .children
+                .into_iter()
+                .map(|child| map_node_msg(child, func))
+                .collect();
+            mt_dom::element(element.tag, attrs, children)
+        }
+        Node::Text(txt) => Node::Text(txt),
+    }
+}
+
+/// A child component which owns its state and is embedded in a parent component.
+///
+/// The parent wraps the child msg in one of its own msg variants in `view`,
+/// and forwards it back to the child in `update`.
+///
+/// ```rust,ignore
+/// enum Msg {
+///     DatePicker(date_picker::Msg),
+/// }
+///
+/// impl Component<Msg> for App {
+///     fn update(&mut self, msg: Msg) {
+///         match msg {
+///             Msg::DatePicker(msg) => self.date_picker.update(msg),
+///         }
+///     }
+///
+///     fn view(&self) -> Node<Msg> {
+///         column(vec![], vec![self.date_picker.view(Msg::DatePicker)])
+///     }
+/// }
+/// ```
+pub struct Child<COMP, MSG> {
+    component: COMP,
+    _phantom_msg: PhantomData<MSG>,
+}
+
+impl<COMP, MSG> Child<COMP, MSG>
+where
+    COMP: Component<MSG>,
+    MSG: 'static,
+{
+    /// embed the component as a child
+    pub fn new(component: COMP) -> Self {
+        Child {
+            component,
+            _phantom_msg: PhantomData,
+        }
+    }
+
+    /// the state of the child component
+    pub fn component(&self) -> &COMP {
+        &self.component
+    }
+
+    /// the mutable state of the child component
+    pub fn component_mut(&mut self) -> &mut COMP {
+        &mut self.component
+    }
+
+    /// forward the msg to the child component
+    pub fn update(&mut self, msg: MSG) {
+        self.component.update(msg);
+    }
+
+    /// forward the msg to the child component, wrapping the msg of the clipboard reads
+    /// it makes into the parent msg, the same way as in `view`
+    pub fn update_with<F, PMSG>(&mut self, msg: MSG, wrap: F)
+    where
+        F: Fn(MSG) -> PMSG + 'static,
+        PMSG: 'static,
+    {
+        self.component.update(msg);
+        clipboard::map_reads(wrap);
+    }
+
+    /// the view of the child component, with its msg wrapped into the parent msg
+    pub fn view<F, PMSG>(&self, wrap: F) -> Node<PMSG>
+    where
+        F: Fn(MSG) -> PMSG + 'static,
+        PMSG: 'static,
+    {
+        MapMsg::map_msg(self.component.view(), wrap)
+    }
+}
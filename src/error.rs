@@ -0,0 +1,106 @@
+//! The errors encountered by the backends when converting and patching widgets,
+//! and in loading the widget tree from a markup
+#[cfg(feature = "with-markup")]
+use crate::markup::MarkupError;
+use crate::Widget;
+use std::fmt;
+
+/// An error in converting the widget tree into the backend widgets,
+/// or in applying the patches to them.
+///
+/// The backends recover from these errors by re-rendering the widget tree,
+/// or by showing a placeholder in place of the widget that failed.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// the image data is not in a supported format
+    UnsupportedImage,
+    /// the image data can not be decoded
+    InvalidImage(String),
+    /// the image data can not be loaded from its source
+    MissingImage(String),
+    /// the widget is not supported by the backend
+    UnsupportedWidget(Widget),
+    /// the node has no computed layout
+    MissingLayout(Widget),
+    /// the backend widget can not be patched in place, and is converted again
+    UnsupportedPatch(Widget),
+    /// there is no backend widget for the node at this index
+    MissingNode(usize),
+    /// the backend widget is not of the type expected for the widget
+    WidgetMismatch {
+        /// the widget of the node
+        widget: Widget,
+        /// the type of the backend widget that is expected
+        expected: &'static str,
+    },
+    /// the backend widget does not have the same number of children as the node
+    ChildrenMismatch {
+        /// the widget of the node
+        widget: Widget,
+        /// the number of children in the node
+        expected: usize,
+        /// the number of children in the backend widget
+        found: usize,
+    },
+    /// the backend failed to create or update its controls
+    Backend(String),
+    /// the markup can not be loaded into a widget tree
+    #[cfg(feature = "with-markup")]
+    Markup(MarkupError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnsupportedImage => write!(f, "unsupported image format"),
+            Error::InvalidImage(e) => write!(f, "invalid image: {}", e),
+            Error::MissingImage(e) => write!(f, "missing image: {}", e),
+            Error::UnsupportedWidget(widget) => {
+                write!(f, "{:?} is not supported by this backend", widget)
+            }
+            Error::UnsupportedPatch(widget) => {
+                write!(f, "{:?} can not be patched by this backend", widget)
+            }
+            Error::MissingLayout(widget) => {
+                write!(f, "{:?} has no computed layout", widget)
+            }
+            Error::MissingNode(node_idx) => {
+                write!(f, "there is no widget for node {}", node_idx)
+            }
+            Error::WidgetMismatch { widget, expected } => {
+                write!(f, "expecting {:?} to be a {}", widget, expected)
+            }
+            Error::ChildrenMismatch {
+                widget,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expecting {:?} to have {} children, found {}",
+                widget, expected, found
+            ),
+            Error::Backend(e) => write!(f, "backend error: {}", e),
+            #[cfg(feature = "with-markup")]
+            Error::Markup(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(feature = "with-markup")]
+impl From<MarkupError> for Error {
+    fn from(e: MarkupError) -> Self {
+        Error::Markup(e)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        match e {
+            image::ImageError::Unsupported(_) => Error::UnsupportedImage,
+            e => Error::InvalidImage(e.to_string()),
+        }
+    }
+}
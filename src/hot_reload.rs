@@ -0,0 +1,162 @@
+//! Development mode which keeps the state of the app across rebuilds
+//!
+//! Hot reload is enabled by setting the `SAURON_NATIVE_HOT_RELOAD` environment
+//! variable, either to `1` which watches the executable itself or to the path
+//! of the binary or dynamic library to watch.
+//! When the watched file is rebuilt, the backend saves the app state and the window
+//! geometry into a snapshot file, restarts the executable and restores them
+//! so the new `view()` is shown with the old data.
+//!
+//! ```sh
+//! SAURON_NATIVE_HOT_RELOAD=1 target/debug/todomvc &
+//! cargo watch -x 'build --features with-hot-reload'
+//! ```
+use once_cell::sync::OnceCell;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// the environment variable which enables the hot reload
+pub const HOT_RELOAD_ENV: &str = "SAURON_NATIVE_HOT_RELOAD";
+
+/// the position and size of the main window
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    /// x position of the window
+    pub x: i32,
+    /// y position of the window
+    pub y: i32,
+    /// width of the window
+    pub width: i32,
+    /// height of the window
+    pub height: i32,
+}
+
+/// The state saved before restarting
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot<APP> {
+    /// the state of the app
+    pub app: APP,
+    /// the window geometry of the app
+    pub window: Option<WindowGeometry>,
+}
+
+/// the path of the executable, resolved when it is first needed at startup.
+/// Once the compiler replaces the executable, the current executable of the
+/// running process resolves to a deleted file
+static EXE_PATH: OnceCell<PathBuf> = OnceCell::new();
+
+/// whether the hot reload is enabled
+pub fn is_enabled() -> bool {
+    env::var_os(HOT_RELOAD_ENV).is_some()
+}
+
+/// the file watched for rebuilds, the current executable
+/// unless a path is specified in the environment variable
+pub fn watched_path() -> io::Result<PathBuf> {
+    match env::var_os(HOT_RELOAD_ENV) {
+        Some(path) if path != "1" => Ok(PathBuf::from(path)),
+        _ => exe_path(),
+    }
+}
+
+/// the path of the executable as it was when the app started
+pub fn exe_path() -> io::Result<PathBuf> {
+    EXE_PATH.get_or_try_init(env::current_exe).map(PathBuf::clone)
+}
+
+/// the location of the snapshot, which is unique to the executable
+pub fn snapshot_path() -> io::Result<PathBuf> {
+    let exe = exe_path()?;
+    let name = exe
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "app".to_string());
+    Ok(env::temp_dir().join(format!("{}.hot-reload.json", name)))
+}
+
+/// save the app state and window geometry into the snapshot file
+pub fn save<APP>(app: &APP, window: Option<WindowGeometry>) -> io::Result<()>
+where
+    APP: Serialize,
+{
+    let snapshot = Snapshot { app, window };
+    let json = serde_json::to_vec(&snapshot)?;
+    fs::write(snapshot_path()?, json)
+}
+
+/// take the snapshot saved by the previous run, if there is any.
+/// The snapshot file is removed so a normal restart starts with a fresh state
+pub fn restore<APP>() -> Option<Snapshot<APP>>
+where
+    APP: DeserializeOwned,
+{
+    let path = snapshot_path().ok()?;
+    let json = fs::read(&path).ok()?;
+    let _ = fs::remove_file(&path);
+    match serde_json::from_slice(&json) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            // the shape of the state changed in the new build
+            log::warn!("unable to restore the hot reload snapshot: {}", e);
+            None
+        }
+    }
+}
+
+/// replace the current process with a fresh start of the executable
+pub fn restart() -> io::Result<()> {
+    let exe = exe_path()?;
+    let args: Vec<_> = env::args_os().skip(1).collect();
+    let mut command = std::process::Command::new(exe);
+    command.args(args);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // exec only returns when it failed
+        Err(command.exec())
+    }
+    #[cfg(not(unix))]
+    {
+        command.spawn()?;
+        std::process::exit(0);
+    }
+}
+
+/// Watches the modification time of a file
+pub struct Watcher {
+    path: PathBuf,
+    started: Option<SystemTime>,
+    last_seen: Option<SystemTime>,
+}
+
+impl Watcher {
+    /// watch the file in `path`
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let started = modified(&path);
+        Watcher {
+            path,
+            started,
+            last_seen: started,
+        }
+    }
+
+    /// returns true when the file has been modified since the watcher started
+    /// and it did not change since the last poll, so the file is not read
+    /// while the compiler is still writing it
+    pub fn poll(&mut self) -> bool {
+        let current = modified(&self.path);
+        let is_settled = current == self.last_seen;
+        self.last_seen = current;
+        current.is_some() && current != self.started && is_settled
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
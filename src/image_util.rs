@@ -1,12 +1,313 @@
-use image::ImageFormat;
+use crate::{
+    widget::{
+        attribute::{find_value, ImageScaling},
+        ImageSource,
+    },
+    AttribKey, Attribute, Error,
+};
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder},
+    imageops::{self, FilterType},
+    AnimationDecoder, DynamicImage, ImageFormat, ImageOutputFormat, RgbaImage,
+};
+use std::{
+    cell::RefCell, collections::VecDeque, io::Cursor, rc::Rc, time::Duration,
+};
+
+/// browsers display the frames with a shorter delay than this using the default delay
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// the number of decoded images kept in the cache
+const IMAGE_CACHE_SIZE: usize = 32;
+
+thread_local! {
+    /// the decoded images by their source, the most recently used is in front
+    static IMAGE_CACHE: RefCell<VecDeque<(ImageSource, Frames)>> =
+        RefCell::new(VecDeque::new());
+}
+
+/// the decoded frames of an image, a still image has a single frame
+pub(crate) type Frames = Rc<Vec<AnimationFrame>>;
+
+/// a frame of an animated image and how long it is displayed
+pub(crate) struct AnimationFrame {
+    pub image: RgbaImage,
+    pub delay: Duration,
+}
 
 /// get the mime type of image
-pub(crate) fn image_mime_type(bytes: &[u8]) -> Option<&'static str> {
-    let img_format =
-        image::guess_format(bytes).expect("must have an image format");
-    match img_format {
-        ImageFormat::Png => Some("image/png"),
-        ImageFormat::Jpeg => Some("image/jpeg"),
-        _ => None,
+pub(crate) fn image_mime_type(bytes: &[u8]) -> Result<&'static str, Error> {
+    match image::guess_format(bytes)? {
+        ImageFormat::Png => Ok("image/png"),
+        ImageFormat::Jpeg => Ok("image/jpeg"),
+        ImageFormat::Gif => Ok("image/gif"),
+        ImageFormat::Bmp => Ok("image/bmp"),
+        ImageFormat::WebP => Ok("image/webp"),
+        ImageFormat::Ico => Ok("image/x-icon"),
+        ImageFormat::Tiff => Ok("image/tiff"),
+        _ => Err(Error::UnsupportedImage),
+    }
+}
+
+/// get the mime type of the image when it is in a format the browsers can display,
+/// otherwise the image is converted into png
+pub(crate) fn web_image(
+    bytes: &[u8],
+) -> Result<(&'static str, Vec<u8>), Error> {
+    match image_mime_type(bytes) {
+        Ok("image/tiff") | Err(Error::UnsupportedImage) => {
+            Ok(("image/png", encode_png(decode(bytes)?)?))
+        }
+        Ok(mime) => Ok((mime, bytes.to_vec())),
+        Err(e) => Err(e),
+    }
+}
+
+/// decode the image in any of the formats supported by the image crate
+/// into rgba pixels
+pub(crate) fn decode(bytes: &[u8]) -> Result<RgbaImage, Error> {
+    Ok(image::load_from_memory(bytes)?.to_rgba8())
+}
+
+/// the image data of the widget, from its source or its data attribute
+pub(crate) fn image_data<MSG>(
+    attrs: &[Attribute<MSG>],
+) -> Result<Vec<u8>, Error> {
+    match find_value(AttribKey::Source, attrs).and_then(|v| v.as_image_source())
+    {
+        Some(source) => Ok(source.load()?.to_vec()),
+        None => Ok(find_value(AttribKey::Data, attrs)
+            .and_then(|v| v.as_bytes())
+            .unwrap_or(&[])
+            .to_vec()),
+    }
+}
+
+/// the decoded frames of the image of the widget,
+/// the image data attribute is decoded every time.
+pub(crate) fn image_frames<MSG>(
+    attrs: &[Attribute<MSG>],
+) -> Result<Frames, Error> {
+    let source = match find_value(AttribKey::Source, attrs)
+        .and_then(|v| v.as_image_source())
+    {
+        Some(source) => source,
+        None => {
+            let bytes = find_value(AttribKey::Data, attrs)
+                .and_then(|v| v.as_bytes())
+                .unwrap_or(&[]);
+            return decode_all(bytes).map(Rc::new);
+        }
+    };
+    source_frames(source)
+}
+
+/// the decoded frames of the image from the source, which are kept in the cache
+pub(crate) fn source_frames(source: &ImageSource) -> Result<Frames, Error> {
+    let cached = IMAGE_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let index = cache.iter().position(|(cached, _)| cached == source)?;
+        let entry = cache.remove(index)?;
+        let frames = Rc::clone(&entry.1);
+        cache.push_front(entry);
+        Some(frames)
+    });
+    if let Some(frames) = cached {
+        return Ok(frames);
+    }
+    let frames = Rc::new(decode_all(&source.load()?)?);
+    IMAGE_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        cache.push_front((source.clone(), Rc::clone(&frames)));
+        cache.truncate(IMAGE_CACHE_SIZE);
+    });
+    Ok(frames)
+}
+
+/// remove all the decoded images from the cache
+pub(crate) fn clear_image_cache() {
+    IMAGE_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+/// decode the frames of an animated image, or the image as a single frame
+fn decode_all(bytes: &[u8]) -> Result<Vec<AnimationFrame>, Error> {
+    match decode_frames(bytes)? {
+        Some(frames) => Ok(frames),
+        None => Ok(vec![AnimationFrame {
+            image: decode(bytes)?,
+            delay: Duration::default(),
+        }]),
+    }
+}
+
+/// decode the frames of an animated gif or apng,
+/// returns None when the image is not animated
+fn decode_frames(bytes: &[u8]) -> Result<Option<Vec<AnimationFrame>>, Error> {
+    let frames = match image::guess_format(bytes)? {
+        ImageFormat::Gif => GifDecoder::new(Cursor::new(bytes))?
+            .into_frames()
+            .collect_frames()?,
+        ImageFormat::Png => {
+            let decoder = PngDecoder::new(Cursor::new(bytes))?;
+            if !decoder.is_apng() {
+                return Ok(None);
+            }
+            decoder.apng().into_frames().collect_frames()?
+        }
+        _ => return Ok(None),
+    };
+    if frames.len() < 2 {
+        return Ok(None);
+    }
+    Ok(Some(
+        frames
+            .into_iter()
+            .map(|frame| {
+                let delay = Duration::from(frame.delay());
+                AnimationFrame {
+                    image: frame.into_buffer(),
+                    delay: if delay < MIN_FRAME_DELAY {
+                        DEFAULT_FRAME_DELAY
+                    } else {
+                        delay
+                    },
+                }
+            })
+            .collect(),
+    ))
+}
+
+/// encode the rgba pixels into png
+pub(crate) fn encode_png(image: RgbaImage) -> Result<Vec<u8>, Error> {
+    let mut png = vec![];
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut png, ImageOutputFormat::Png)?;
+    Ok(png)
+}
+
+/// scale the image to be displayed in a widget of the given size
+pub(crate) fn scale(
+    image: &RgbaImage,
+    width: u32,
+    height: u32,
+    scaling: ImageScaling,
+) -> RgbaImage {
+    if width == 0 || height == 0 || image.width() == 0 || image.height() == 0 {
+        return image.clone();
+    }
+    let scale_x = width as f32 / image.width() as f32;
+    let scale_y = height as f32 / image.height() as f32;
+    match scaling {
+        ImageScaling::Stretch => {
+            imageops::resize(image, width, height, FilterType::Triangle)
+        }
+        ImageScaling::Fit => {
+            let factor = scale_x.min(scale_y);
+            resize_by(image, factor)
+        }
+        ImageScaling::Fill => {
+            let factor = scale_x.max(scale_y);
+            crop_center(resize_by(image, factor), width, height)
+        }
+        ImageScaling::None => crop_center(image.clone(), width, height),
+    }
+}
+
+fn resize_by(image: &RgbaImage, factor: f32) -> RgbaImage {
+    let width = (image.width() as f32 * factor).round().max(1.0) as u32;
+    let height = (image.height() as f32 * factor).round().max(1.0) as u32;
+    imageops::resize(image, width, height, FilterType::Triangle)
+}
+
+/// cut the part of the image which is larger than the size, keeping the center
+fn crop_center(image: RgbaImage, width: u32, height: u32) -> RgbaImage {
+    if image.width() <= width && image.height() <= height {
+        return image;
+    }
+    let crop_width = image.width().min(width);
+    let crop_height = image.height().min(height);
+    let x = (image.width() - crop_width) / 2;
+    let y = (image.height() - crop_height) / 2;
+    imageops::crop_imm(&image, x, y, crop_width, crop_height).to_image()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// an image where each pixel has its own coordinates as its red and green
+    fn coordinates(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| {
+            Rgba([x as u8, y as u8, 0, 255])
+        })
+    }
+
+    fn size(image: &RgbaImage) -> (u32, u32) {
+        (image.width(), image.height())
+    }
+
+    #[test]
+    fn stretched_image_has_the_size_of_the_widget() {
+        let image = coordinates(40, 20);
+        let scaled = scale(&image, 10, 10, ImageScaling::Stretch);
+        assert_eq!(size(&scaled), (10, 10));
+    }
+
+    #[test]
+    fn fitted_image_keeps_its_aspect_ratio_inside_the_widget() {
+        let image = coordinates(40, 20);
+        assert_eq!(size(&scale(&image, 10, 10, ImageScaling::Fit)), (10, 5));
+        assert_eq!(size(&scale(&image, 80, 80, ImageScaling::Fit)), (80, 40));
+    }
+
+    #[test]
+    fn filled_image_covers_the_widget_and_is_cut_off() {
+        let image = coordinates(40, 20);
+        assert_eq!(size(&scale(&image, 10, 10, ImageScaling::Fill)), (10, 10));
+        assert_eq!(size(&scale(&image, 80, 80, ImageScaling::Fill)), (80, 80));
+    }
+
+    #[test]
+    fn unscaled_image_is_cut_off_around_its_center() {
+        let image = coordinates(40, 20);
+        let scaled = scale(&image, 10, 10, ImageScaling::None);
+        assert_eq!(size(&scaled), (10, 10));
+        assert_eq!(*scaled.get_pixel(0, 0), Rgba([15, 5, 0, 255]));
+
+        let larger = scale(&image, 100, 100, ImageScaling::None);
+        assert_eq!(larger, image);
+    }
+
+    #[test]
+    fn zero_sized_widget_keeps_the_image() {
+        let image = coordinates(40, 20);
+        for scaling in &[
+            ImageScaling::Fit,
+            ImageScaling::Fill,
+            ImageScaling::Stretch,
+            ImageScaling::None,
+        ] {
+            assert_eq!(scale(&image, 0, 10, *scaling), image);
+            assert_eq!(scale(&image, 10, 0, *scaling), image);
+        }
+    }
+
+    #[test]
+    fn crop_keeps_the_center_on_each_axis() {
+        let cropped = crop_center(coordinates(40, 20), 30, 30);
+        assert_eq!(size(&cropped), (30, 20));
+        assert_eq!(*cropped.get_pixel(0, 0), Rgba([5, 0, 0, 255]));
+
+        let cropped = crop_center(coordinates(40, 20), 40, 10);
+        assert_eq!(size(&cropped), (40, 10));
+        assert_eq!(*cropped.get_pixel(0, 0), Rgba([0, 5, 0, 255]));
+    }
+
+    #[test]
+    fn smaller_image_is_not_cropped() {
+        let image = coordinates(4, 2);
+        assert_eq!(crop_center(image.clone(), 10, 10), image);
     }
 }
@@ -0,0 +1,139 @@
+//! A developer inspector which lists the current widget tree, the attributes
+//! and computed layout of each widget, and the patches applied on the last
+//! dispatch together with the time spent in each phase.
+//!
+//! The gtk backend shows the inspector in a side window toggled with `F12`
+//! or `Ctrl+Shift+I`.
+//! The titik backend owns the whole terminal, so its inspector is written into
+//! the file named by the `SAURON_NATIVE_INSPECTOR` environment variable after
+//! each dispatch, to be followed from another terminal.
+//!
+//! ```sh
+//! SAURON_NATIVE_INSPECTOR=/tmp/inspector.txt target/debug/counter &
+//! watch -n 0.5 cat /tmp/inspector.txt
+//! ```
+use crate::{
+    instrument::PatchCounts,
+    snapshot::render_node,
+    widget::event::{KeyCode, KeyEvent},
+    Node, Patch,
+};
+use std::{env, fmt::Write, path::PathBuf, time::Duration};
+
+/// the environment variable which names the file the titik inspector is written to
+pub const INSPECTOR_ENV: &str = "SAURON_NATIVE_INSPECTOR";
+
+/// the file the inspector is written to, when it is set in the environment
+pub fn output_path() -> Option<PathBuf> {
+    env::var_os(INSPECTOR_ENV).map(PathBuf::from)
+}
+
+/// The state of the inspector, updated by the backend on each dispatch
+#[derive(Default, Debug)]
+pub struct Inspector {
+    enabled: bool,
+    timings: Vec<(&'static str, Duration)>,
+    patches: Vec<String>,
+}
+
+impl Inspector {
+    /// create a disabled inspector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// whether the inspector panel is shown
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// show or hide the inspector panel
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    /// whether the key is the combination which toggles the inspector
+    pub fn is_toggle_key(key: &KeyEvent) -> bool {
+        match key.key_code {
+            KeyCode::F(12) => true,
+            KeyCode::Char('i') | KeyCode::Char('I') => {
+                key.modifier.ctrl_key && key.modifier.shift_key
+            }
+            _ => false,
+        }
+    }
+
+    /// clear the timings and patches of the previous dispatch
+    pub fn start_dispatch(&mut self) {
+        self.timings.clear();
+        self.patches.clear();
+    }
+
+    /// record the time spent in a phase of the dispatch
+    pub fn record_timing(&mut self, phase: &'static str, duration: Duration) {
+        self.timings.push((phase, duration));
+    }
+
+    /// record the patches applied to the widgets
+    pub fn record_patches<MSG>(&mut self, patches: &[Patch<MSG>]) {
+        self.patches = patches.iter().map(describe_patch).collect();
+    }
+
+    /// the text displayed in the inspector panel
+    pub fn report<MSG>(&self, current_view: &Node<MSG>) -> String
+    where
+        MSG: 'static,
+    {
+        let mut buffer = String::new();
+        writeln!(buffer, "== Timings ==").expect("must write");
+        for (phase, duration) in self.timings.iter() {
+            writeln!(buffer, "{:<16} {:?}", phase, duration)
+                .expect("must write");
+        }
+        writeln!(buffer, "\n== Patches ({}) ==", self.patches.len())
+            .expect("must write");
+        for patch in self.patches.iter() {
+            writeln!(buffer, "{}", patch).expect("must write");
+        }
+        writeln!(buffer, "\n== Widget tree ==").expect("must write");
+        buffer.push_str(&render_node(current_view));
+        buffer
+    }
+}
+
+/// a one line description of the patch, without the content of the nodes
+fn describe_patch<MSG>(patch: &Patch<MSG>) -> String {
+    let detail = match patch {
+        Patch::AddAttributes(_tag, _node_idx, attrs) => {
+            let names: Vec<String> = attrs
+                .iter()
+                .map(|att| format!("{:?}", att.name()))
+                .collect();
+            names.join(", ")
+        }
+        Patch::RemoveAttributes(_tag, _node_idx, attrs) => {
+            let names: Vec<String> = attrs
+                .iter()
+                .map(|att| format!("{:?}", att.name()))
+                .collect();
+            names.join(", ")
+        }
+        Patch::AppendChildren(_tag, _node_idx, children) => {
+            format!("{} children", children.len())
+        }
+        Patch::RemoveChildren(_tag, _node_idx, children_index) => {
+            format!("at {:?}", children_index)
+        }
+        Patch::Replace(_tag, _node_idx, replacement) => {
+            format!("with {:?}", replacement.tag())
+        }
+        _ => String::new(),
+    };
+    format!(
+        "{} node {} {:?} {}",
+        PatchCounts::kind(patch),
+        patch.node_idx(),
+        patch.tag(),
+        detail
+    )
+}
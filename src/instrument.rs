@@ -0,0 +1,224 @@
+//! Instrumentation of the update, view, layout, diff and patch cycle
+//!
+//! The backends time each phase of a dispatch with a `Span` and report the
+//! duration and the number of patches by type through the `log` crate under
+//! the `sauron_native::cycle` target.
+//! When the `with-tracing` feature is enabled, each phase is also a `tracing` span,
+//! and the patch counts are emitted as a `tracing` event with a field for each type.
+//!
+//! Nothing is emitted unless the app installs a logger or a tracing subscriber.
+//!
+//! ```rust,ignore
+//! // show the timings of each phase
+//! std::env::set_var("RUST_LOG", "sauron_native::cycle=debug");
+//! env_logger::init();
+//! ```
+use crate::Patch;
+use std::{fmt, time::Duration};
+
+/// the log target of the instrumentation
+pub const TARGET: &str = "sauron_native::cycle";
+
+/// The phases of the update cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// the app updates its state with the dispatched msg
+    Update,
+    /// the app creates its view
+    View,
+    /// the layout of the view is computed
+    ComputeLayout,
+    /// the new view is diffed with the current view
+    Diff,
+    /// the patches are applied to the backend widgets
+    ApplyPatches,
+}
+
+impl Phase {
+    /// the name of the phase, which is also the name of the tracing span
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Update => "update",
+            Phase::View => "view",
+            Phase::ComputeLayout => "compute_node_layout",
+            Phase::Diff => "diff_with_key",
+            Phase::ApplyPatches => "apply_patches",
+        }
+    }
+
+    #[cfg(feature = "with-tracing")]
+    fn tracing_span(&self) -> tracing::Span {
+        // the span name is part of the static metadata, so it can't be a variable
+        match self {
+            Phase::Update => tracing::debug_span!(target: TARGET, "update"),
+            Phase::View => tracing::debug_span!(target: TARGET, "view"),
+            Phase::ComputeLayout => {
+                tracing::debug_span!(target: TARGET, "compute_node_layout")
+            }
+            Phase::Diff => {
+                tracing::debug_span!(target: TARGET, "diff_with_key")
+            }
+            Phase::ApplyPatches => {
+                tracing::debug_span!(target: TARGET, "apply_patches")
+            }
+        }
+    }
+}
+
+/// Measures the time spent in a phase, from `enter` until `finish`
+#[must_use = "the span is only reported when finished"]
+pub struct Span {
+    phase: Phase,
+    started: clock::Timestamp,
+    #[cfg(feature = "with-tracing")]
+    _entered: tracing::span::EnteredSpan,
+}
+
+impl Span {
+    /// start measuring the phase
+    pub fn enter(phase: Phase) -> Self {
+        Span {
+            phase,
+            started: clock::now(),
+            #[cfg(feature = "with-tracing")]
+            _entered: phase.tracing_span().entered(),
+        }
+    }
+
+    /// stop measuring the phase, log and return the time spent in it
+    pub fn finish(self) -> Duration {
+        let duration = clock::elapsed(&self.started);
+        log::debug!(target: TARGET, "{} took {:?}", self.phase.name(), duration);
+        duration
+    }
+}
+
+/// The number of patches of each type
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PatchCounts {
+    /// the number of AddAttributes patches
+    pub add_attributes: usize,
+    /// the number of RemoveAttributes patches
+    pub remove_attributes: usize,
+    /// the number of AppendChildren patches
+    pub append_children: usize,
+    /// the number of RemoveChildren patches
+    pub remove_children: usize,
+    /// the number of Replace patches
+    pub replace: usize,
+    /// the number of the other patches
+    pub other: usize,
+}
+
+impl PatchCounts {
+    /// count the patches by their type
+    pub fn from_patches<MSG>(patches: &[Patch<MSG>]) -> Self {
+        let mut counts = PatchCounts::default();
+        for patch in patches {
+            *counts.count_mut(patch) += 1;
+        }
+        counts
+    }
+
+    /// the name of the type of the patch, as it is displayed in the counts
+    pub fn kind<MSG>(patch: &Patch<MSG>) -> &'static str {
+        match patch {
+            Patch::AddAttributes(..) => "AddAttributes",
+            Patch::RemoveAttributes(..) => "RemoveAttributes",
+            Patch::AppendChildren(..) => "AppendChildren",
+            Patch::RemoveChildren(..) => "RemoveChildren",
+            Patch::Replace(..) => "Replace",
+            _ => "Other",
+        }
+    }
+
+    fn count_mut<MSG>(&mut self, patch: &Patch<MSG>) -> &mut usize {
+        match patch {
+            Patch::AddAttributes(..) => &mut self.add_attributes,
+            Patch::RemoveAttributes(..) => &mut self.remove_attributes,
+            Patch::AppendChildren(..) => &mut self.append_children,
+            Patch::RemoveChildren(..) => &mut self.remove_children,
+            Patch::Replace(..) => &mut self.replace,
+            _ => &mut self.other,
+        }
+    }
+
+    /// the total number of patches
+    pub fn total(&self) -> usize {
+        self.add_attributes
+            + self.remove_attributes
+            + self.append_children
+            + self.remove_children
+            + self.replace
+            + self.other
+    }
+}
+
+impl fmt::Display for PatchCounts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} patches (AddAttributes: {}, RemoveAttributes: {}, \
+             AppendChildren: {}, RemoveChildren: {}, Replace: {}, Other: {})",
+            self.total(),
+            self.add_attributes,
+            self.remove_attributes,
+            self.append_children,
+            self.remove_children,
+            self.replace,
+            self.other
+        )
+    }
+}
+
+/// log the number of patches by type
+pub fn record_patches<MSG>(patches: &[Patch<MSG>]) -> PatchCounts {
+    let counts = PatchCounts::from_patches(patches);
+    log::debug!(target: TARGET, "{}", counts);
+    #[cfg(feature = "with-tracing")]
+    tracing::debug!(
+        target: TARGET,
+        total = counts.total(),
+        add_attributes = counts.add_attributes,
+        remove_attributes = counts.remove_attributes,
+        append_children = counts.append_children,
+        remove_children = counts.remove_children,
+        replace = counts.replace,
+        other = counts.other,
+        "patches"
+    );
+    counts
+}
+
+/// `Instant::now` is not available in the browser, so the html backend
+/// uses the performance timer instead
+mod clock {
+    use std::time::Duration;
+
+    #[cfg(not(all(target_arch = "wasm32", feature = "with-html")))]
+    pub(super) type Timestamp = std::time::Instant;
+
+    #[cfg(not(all(target_arch = "wasm32", feature = "with-html")))]
+    pub(super) fn now() -> Timestamp {
+        std::time::Instant::now()
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", feature = "with-html")))]
+    pub(super) fn elapsed(started: &Timestamp) -> Duration {
+        started.elapsed()
+    }
+
+    /// milliseconds from the performance timer
+    #[cfg(all(target_arch = "wasm32", feature = "with-html"))]
+    pub(super) type Timestamp = f64;
+
+    #[cfg(all(target_arch = "wasm32", feature = "with-html"))]
+    pub(super) fn now() -> Timestamp {
+        sauron::now()
+    }
+
+    #[cfg(all(target_arch = "wasm32", feature = "with-html"))]
+    pub(super) fn elapsed(started: &Timestamp) -> Duration {
+        Duration::from_secs_f64((sauron::now() - started).max(0.0) / 1000.0)
+    }
+}
@@ -5,8 +5,11 @@
 #![deny(clippy::all)]
 
 pub use backend::Backend;
-pub use component::Component;
+pub use component::{Child, Component, MapMsg};
+pub use error::Error;
 pub use mt_dom;
+#[cfg(feature = "with-node-macro")]
+pub use sauron_native_macro::node;
 pub use stretch;
 pub use widget::{
     attribute::{AttribKey, Value},
@@ -14,8 +17,21 @@ pub use widget::{
 };
 
 pub mod backend;
+pub mod clipboard;
 mod component;
+mod error;
+#[cfg(feature = "with-hot-reload")]
+pub mod hot_reload;
 pub(crate) mod image_util;
+pub mod inspector;
+pub mod instrument;
+#[cfg(feature = "with-markup")]
+pub mod markup;
+#[cfg(feature = "with-record")]
+pub mod record;
+#[cfg(feature = "serde")]
+pub mod serialize;
+pub mod snapshot;
 pub mod widget;
 
 /// It is an abstracted event to allow conversion
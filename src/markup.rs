@@ -0,0 +1,769 @@
+//! Builds a Node tree from a declarative xml markup at runtime
+//!
+//! The tags are the names of the builder functions such as `column`, `row`,
+//! `button` and the attributes are the names of the attribute functions.
+//! Events are bound to named slots which are registered with `Markup::on`.
+//!
+//! ```xml
+//! <column>
+//!     <text_label value="Hello" height="40"/>
+//!     <row flex_grow="1" justify_content="space-between">
+//!         <button label="+" on_click="increment"/>
+//!         <button label="-" on_click="decrement"/>
+//!     </row>
+//! </column>
+//! ```
+//!
+//! Values are parsed according to the attribute they are assigned to:
+//! - dimensions are in points `100`, percent `50%` or `auto`
+//! - grid tracks are space separated points, percent or fractions `100 25% 1fr`
+//! - `data`, `svg_image` and `source` are paths to a file, relative to the markup file
+//! - `image_scaling` is one of `fit`, `fill`, `stretch` or `none`
+//! - `drop_effect` is one of `none`, `copy`, `move` or `link`
+//!
+//! The charts take their series and axes as arguments, so these are not declared in markup.
+use crate::{
+    widget::{
+        self,
+        attribute::{get_style, merge_style, style, DropEffect, ImageScaling},
+        event::{self, Event},
+        GridTrack, ImageSource, Widget,
+    },
+    AttribKey, Attribute, Error, Node, Value,
+};
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+use stretch::style::{
+    AlignItems, AlignSelf, Dimension, FlexWrap, JustifyContent, PositionType,
+};
+
+/// The errors encountered when loading a markup,
+/// these are returned as the `Markup` variant of the crate Error
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkupError {
+    /// the markup is not a well formed xml
+    Xml(String),
+    /// the markup file or a file it refers to can not be read
+    Io(String),
+    /// the tag does not correspond to any widget
+    UnknownWidget {
+        /// the tag name
+        tag: String,
+        /// the line where the tag is
+        line: u32,
+    },
+    /// the widget can not be declared in markup, such as the charts
+    /// which take their data as arguments of the builder function
+    UnsupportedWidget {
+        /// the tag name
+        tag: String,
+        /// the line where the tag is
+        line: u32,
+    },
+    /// the attribute does not correspond to any AttribKey or event
+    UnknownAttribute {
+        /// the attribute name
+        name: String,
+        /// the line where the attribute is
+        line: u32,
+    },
+    /// the value can not be parsed for the attribute it is assigned to
+    InvalidValue {
+        /// the attribute name
+        name: String,
+        /// the value that failed to parse
+        value: String,
+        /// the line where the attribute is
+        line: u32,
+    },
+    /// no function is bound to the event slot
+    UnboundEvent {
+        /// the slot name
+        slot: String,
+        /// the line where the event is
+        line: u32,
+    },
+    /// children are used on a widget which is not a container
+    NotContainer {
+        /// the tag name
+        tag: String,
+        /// the line where the tag is
+        line: u32,
+    },
+    /// text content is used inside of an element
+    UnexpectedText {
+        /// the text content
+        text: String,
+        /// the line where the text is
+        line: u32,
+    },
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkupError::Xml(e) => write!(f, "{}", e),
+            MarkupError::Io(e) => write!(f, "{}", e),
+            MarkupError::UnknownWidget { tag, line } => {
+                write!(f, "line {}: unknown widget `{}`", line, tag)
+            }
+            MarkupError::UnsupportedWidget { tag, line } => write!(
+                f,
+                "line {}: `{}` can not be declared in markup, use its builder function instead",
+                line, tag
+            ),
+            MarkupError::UnknownAttribute { name, line } => {
+                write!(f, "line {}: unknown attribute `{}`", line, name)
+            }
+            MarkupError::InvalidValue { name, value, line } => write!(
+                f,
+                "line {}: invalid value `{}` for attribute `{}`",
+                line, value, name
+            ),
+            MarkupError::UnboundEvent { slot, line } => write!(
+                f,
+                "line {}: no function is bound to the event slot `{}`",
+                line, slot
+            ),
+            MarkupError::NotContainer { tag, line } => write!(
+                f,
+                "line {}: `{}` is not a container and can not have children",
+                line, tag
+            ),
+            MarkupError::UnexpectedText { text, line } => write!(
+                f,
+                "line {}: unexpected text `{}`, use the `value` or `label` attribute instead",
+                line, text
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+impl From<roxmltree::Error> for MarkupError {
+    fn from(e: roxmltree::Error) -> Self {
+        MarkupError::Xml(e.to_string())
+    }
+}
+
+impl From<io::Error> for MarkupError {
+    fn from(e: io::Error) -> Self {
+        MarkupError::Io(e.to_string())
+    }
+}
+
+/// Loads a Node tree from markup, with the events bound to named slots
+pub struct Markup<MSG> {
+    slots: BTreeMap<String, Rc<dyn Fn(Event) -> MSG>>,
+}
+
+impl<MSG> Default for Markup<MSG> {
+    fn default() -> Self {
+        Markup {
+            slots: BTreeMap::new(),
+        }
+    }
+}
+
+impl<MSG> Markup<MSG>
+where
+    MSG: 'static,
+{
+    /// create a markup loader with no event slots
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// bind the event slot `slot` to a function which creates the MSG,
+    /// ie: `on_click="increment"` is bound with `.on("increment", |_| Msg::Increment)`
+    pub fn on<F>(mut self, slot: &str, func: F) -> Self
+    where
+        F: Fn(Event) -> MSG + 'static,
+    {
+        self.slots.insert(slot.to_string(), Rc::new(func));
+        self
+    }
+
+    /// build the Node tree from a markup string,
+    /// the files it refers to are relative to the current directory
+    pub fn load_str(&self, markup: &str) -> Result<Node<MSG>, Error> {
+        Ok(self.load(markup, Path::new(""))?)
+    }
+
+    /// build the Node tree from a markup file,
+    /// the files it refers to are relative to the directory of the markup file
+    pub fn load_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Node<MSG>, Error> {
+        let path = path.as_ref();
+        let markup = fs::read_to_string(path).map_err(MarkupError::from)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        Ok(self.load(&markup, base_dir)?)
+    }
+
+    fn load(
+        &self,
+        markup: &str,
+        base_dir: &Path,
+    ) -> Result<Node<MSG>, MarkupError> {
+        let doc = roxmltree::Document::parse(markup)?;
+        let loader = Loader {
+            markup: self,
+            doc: &doc,
+            base_dir: base_dir.to_path_buf(),
+        };
+        loader.build_node(doc.root_element())
+    }
+}
+
+struct Loader<'a, 'd, MSG> {
+    markup: &'a Markup<MSG>,
+    doc: &'d roxmltree::Document<'d>,
+    base_dir: PathBuf,
+}
+
+impl<'a, 'd, MSG> Loader<'a, 'd, MSG>
+where
+    MSG: 'static,
+{
+    fn line(&self, pos: usize) -> u32 {
+        self.doc.text_pos_at(pos).row
+    }
+
+    fn build_node(
+        &self,
+        xml: roxmltree::Node,
+    ) -> Result<Node<MSG>, MarkupError> {
+        let tag = xml.tag_name().name();
+        let line = self.line(xml.range().start);
+        let widget = widget_from_tag(tag).ok_or_else(|| {
+            if UNSUPPORTED_TAGS.contains(&tag) {
+                MarkupError::UnsupportedWidget {
+                    tag: tag.to_string(),
+                    line,
+                }
+            } else {
+                MarkupError::UnknownWidget {
+                    tag: tag.to_string(),
+                    line,
+                }
+            }
+        })?;
+
+        let mut attrs = vec![];
+        for attribute in xml.attributes() {
+            attrs.push(self.build_attribute(attribute)?);
+        }
+
+        let mut children = vec![];
+        for child in xml.children() {
+            if child.is_element() {
+                children.push(self.build_node(child)?);
+            } else if let Some(text) = child.text() {
+                if !text.trim().is_empty() {
+                    return Err(MarkupError::UnexpectedText {
+                        text: text.trim().to_string(),
+                        line: self.line(child.range().start),
+                    });
+                }
+            }
+        }
+
+        // any widget can have a context menu
+        let (context_menus, children): (Vec<_>, Vec<_>) =
+            children.into_iter().partition(|child: &Node<MSG>| {
+                child.tag() == Some(&Widget::ContextMenu)
+            });
+        if !children.is_empty() && !widget.is_container() {
+            return Err(MarkupError::NotContainer {
+                tag: tag.to_string(),
+                line,
+            });
+        }
+
+        let node = match widget {
+            Widget::Vbox => widget::column(attrs, children),
+            Widget::Hbox => widget::row(attrs, children),
+            Widget::Vpane => widget::vpane(attrs, children),
+            Widget::Hpane => widget::hpane(attrs, children),
+            Widget::Overlay => widget::overlay(attrs, children),
+            Widget::GroupBox => widget::groupbox(attrs, children),
+            Widget::Grid => widget::grid(attrs, children),
+            Widget::HeaderBar => widget::header_bar(attrs, children),
+            Widget::MenuBar => widget::menu_bar(attrs, children),
+            Widget::Menu => widget::menu(attrs, children),
+            Widget::MenuItem => widget::menu_item(attrs, children),
+            Widget::ContextMenu => widget::context_menu(attrs, children),
+            Widget::MenuSeparator => widget::menu_separator(attrs),
+            Widget::Button => widget::button(attrs),
+            // the same as `widget::paragraph`, with the other attributes passed through
+            // and the layout attributes merged into its style
+            Widget::Paragraph => {
+                let txt = xml.attribute("value").unwrap_or("");
+                let node_style = merge_style(
+                    &attrs,
+                    get_style(&widget::paragraph::<MSG>(txt))
+                        .cloned()
+                        .unwrap_or_default(),
+                );
+                // the text is kept as it is, without being trimmed
+                attrs.retain(|att| *att.name() != AttribKey::Value);
+                attrs.push(widget::attribute::value(txt.to_string()));
+                attrs.push(style(node_style));
+                widget::widget(Widget::Paragraph, attrs, vec![])
+            }
+            Widget::TextInput => widget::text_input(attrs),
+            Widget::Checkbox => widget::checkbox(attrs),
+            Widget::Radio => widget::radio(attrs),
+            Widget::Image => widget::image(attrs),
+            Widget::Svg => widget::svg(attrs),
+            Widget::TextArea => widget::textarea(attrs),
+            Widget::Label => widget::text_label(attrs),
+            Widget::SearchInput => widget::search_input(attrs),
+            Widget::Link => widget::link(attrs),
+            Widget::Canvas => widget::canvas(attrs),
+        };
+        let node =
+            context_menus.into_iter().fold(node, |node, context_menu| {
+                widget::with_context_menu(node, context_menu)
+            });
+        Ok(node)
+    }
+
+    fn build_attribute(
+        &self,
+        attribute: &roxmltree::Attribute,
+    ) -> Result<Attribute<MSG>, MarkupError> {
+        let name = attribute.name();
+        let value = attribute.value();
+        let line = self.line(attribute.range().start);
+
+        if let Some(event_key) = event_key(name) {
+            let func =
+                self.markup.slots.get(value).cloned().ok_or_else(|| {
+                    MarkupError::UnboundEvent {
+                        slot: value.to_string(),
+                        line,
+                    }
+                })?;
+            return Ok(event::on(event_key, move |ev: Event| func(ev)));
+        }
+
+        let key =
+            attrib_key(name).ok_or_else(|| MarkupError::UnknownAttribute {
+                name: name.to_string(),
+                line,
+            })?;
+        let invalid = || MarkupError::InvalidValue {
+            name: name.to_string(),
+            value: value.to_string(),
+            line,
+        };
+        let value = match key {
+            AttribKey::Data | AttribKey::SvgImage => {
+                Value::from(fs::read(self.base_dir.join(value))?)
+            }
+            AttribKey::Source => {
+                Value::from(ImageSource::path(self.base_dir.join(value)))
+            }
+            _ => parse_value(&key, value).ok_or_else(invalid)?,
+        };
+        Ok(mt_dom::attr(key, value))
+    }
+}
+
+/// the builder functions which take more than attributes and children,
+/// so these can not be declared in markup
+const UNSUPPORTED_TAGS: &[&str] = &["line_chart", "bar_chart", "scatter_plot"];
+
+/// the widget for each of the builder function names
+fn widget_from_tag(tag: &str) -> Option<Widget> {
+    let widget = match tag {
+        "column" => Widget::Vbox,
+        "row" => Widget::Hbox,
+        "vpane" => Widget::Vpane,
+        "hpane" => Widget::Hpane,
+        "overlay" => Widget::Overlay,
+        "groupbox" => Widget::GroupBox,
+        "grid" => Widget::Grid,
+        "header_bar" => Widget::HeaderBar,
+        "menu_bar" => Widget::MenuBar,
+        "menu" => Widget::Menu,
+        "menu_item" => Widget::MenuItem,
+        "context_menu" => Widget::ContextMenu,
+        "menu_separator" => Widget::MenuSeparator,
+        "button" => Widget::Button,
+        "paragraph" => Widget::Paragraph,
+        "text_input" => Widget::TextInput,
+        "checkbox" => Widget::Checkbox,
+        "radio" => Widget::Radio,
+        "image" => Widget::Image,
+        "svg" => Widget::Svg,
+        "textarea" => Widget::TextArea,
+        "text_label" => Widget::Label,
+        "search_input" => Widget::SearchInput,
+        "link" => Widget::Link,
+        "canvas" => Widget::Canvas,
+        _ => return None,
+    };
+    Some(widget)
+}
+
+/// the event AttribKey for each of the event function names
+fn event_key(name: &str) -> Option<AttribKey> {
+    let key = match name {
+        "on_click" => AttribKey::ClickEvent,
+        "on_mousedown" => AttribKey::MouseDown,
+        "on_mouseup" => AttribKey::MouseUp,
+        "on_mousemove" => AttribKey::MouseMove,
+        "on_doubleclick" => AttribKey::DoubleClickEvent,
+        "on_blur" => AttribKey::BlurEvent,
+        "on_input" => AttribKey::InputEvent,
+        "on_keypress" => AttribKey::KeyEvent,
+        "on_animation_end" => AttribKey::AnimationEndEvent,
+        "on_chart_hover" => AttribKey::ChartHoverEvent,
+        "on_drop" => AttribKey::DropEvent,
+        "on_drag_end" => AttribKey::DragEndEvent,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// the AttribKey for each of the attribute function names
+fn attrib_key(name: &str) -> Option<AttribKey> {
+    let key = match name {
+        "value" => AttribKey::Value,
+        "data" => AttribKey::Data,
+        "source" => AttribKey::Source,
+        "label" => AttribKey::Label,
+        "svg_image" => AttribKey::SvgImage,
+        "editable" => AttribKey::Editable,
+        "scrollable" => AttribKey::Scrollable,
+        "width" => AttribKey::Width,
+        "height" => AttribKey::Height,
+        "position_type" => AttribKey::PositionType,
+        "flex_grow" => AttribKey::FlexGrow,
+        "flex_shrink" => AttribKey::FlexShrink,
+        "flex_basis" => AttribKey::FlexBasis,
+        "justify_content" => AttribKey::JustifyContent,
+        "align_items" => AttribKey::AlignItems,
+        "align_self" => AttribKey::AlignSelf,
+        "flex_wrap" => AttribKey::FlexWrap,
+        "min_width" => AttribKey::MinWidth,
+        "min_height" => AttribKey::MinHeight,
+        "max_width" => AttribKey::MaxWidth,
+        "max_height" => AttribKey::MaxHeight,
+        "resizable" => AttribKey::Resizable,
+        "monospace" => AttribKey::Monospace,
+        "selectable" => AttribKey::Selectable,
+        "preformatted" => AttribKey::Preformatted,
+        "name" => AttribKey::Name,
+        "uri" => AttribKey::Uri,
+        "checked" => AttribKey::Checked,
+        "placeholder" => AttribKey::Placeholder,
+        "key" => AttribKey::Key,
+        "grid_rows" => AttribKey::GridRows,
+        "grid_columns" => AttribKey::GridColumns,
+        "grid_row" => AttribKey::GridRow,
+        "grid_column" => AttribKey::GridColumn,
+        "row_span" => AttribKey::RowSpan,
+        "column_span" => AttribKey::ColumnSpan,
+        "image_scaling" => AttribKey::ImageScaling,
+        "paused" => AttribKey::Paused,
+        "looping" => AttribKey::Looping,
+        "drag_data" => AttribKey::DragData,
+        "drop_effect" => AttribKey::DropEffect,
+        "disabled" => AttribKey::Disabled,
+        "accelerator" => AttribKey::Accelerator,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// parse the value according to the type the attribute expects
+fn parse_value(key: &AttribKey, value: &str) -> Option<Value> {
+    let value = value.trim();
+    let parsed = match key {
+        AttribKey::Editable
+        | AttribKey::Scrollable
+        | AttribKey::Resizable
+        | AttribKey::Monospace
+        | AttribKey::Selectable
+        | AttribKey::Preformatted
+        | AttribKey::Paused
+        | AttribKey::Looping
+        | AttribKey::Checked
+        | AttribKey::Disabled => Value::from(value.parse::<bool>().ok()?),
+        AttribKey::Width
+        | AttribKey::Height
+        | AttribKey::FlexBasis
+        | AttribKey::MinWidth
+        | AttribKey::MinHeight
+        | AttribKey::MaxWidth
+        | AttribKey::MaxHeight => parse_dimension(value)?,
+        AttribKey::FlexGrow | AttribKey::FlexShrink => {
+            Value::from(value.parse::<f64>().ok()?)
+        }
+        AttribKey::GridRow
+        | AttribKey::GridColumn
+        | AttribKey::RowSpan
+        | AttribKey::ColumnSpan => Value::from(value.parse::<usize>().ok()?),
+        AttribKey::GridRows | AttribKey::GridColumns => {
+            let tracks = value
+                .split_whitespace()
+                .map(parse_grid_track)
+                .collect::<Option<Vec<_>>>()?;
+            Value::from(tracks)
+        }
+        AttribKey::ImageScaling => Value::from(match value {
+            "fit" => ImageScaling::Fit,
+            "fill" => ImageScaling::Fill,
+            "stretch" => ImageScaling::Stretch,
+            "none" => ImageScaling::None,
+            _ => return None,
+        }),
+        AttribKey::DropEffect => Value::from(match value {
+            "none" => DropEffect::None,
+            "copy" => DropEffect::Copy,
+            "move" => DropEffect::Move,
+            "link" => DropEffect::Link,
+            _ => return None,
+        }),
+        AttribKey::PositionType => Value::from(match value {
+            "relative" => PositionType::Relative,
+            "absolute" => PositionType::Absolute,
+            _ => return None,
+        }),
+        AttribKey::JustifyContent => Value::from(match value {
+            "flex-start" => JustifyContent::FlexStart,
+            "flex-end" => JustifyContent::FlexEnd,
+            "center" => JustifyContent::Center,
+            "space-between" => JustifyContent::SpaceBetween,
+            "space-around" => JustifyContent::SpaceAround,
+            "space-evenly" => JustifyContent::SpaceEvenly,
+            _ => return None,
+        }),
+        AttribKey::AlignItems => Value::from(match value {
+            "flex-start" => AlignItems::FlexStart,
+            "flex-end" => AlignItems::FlexEnd,
+            "center" => AlignItems::Center,
+            "baseline" => AlignItems::Baseline,
+            "stretch" => AlignItems::Stretch,
+            _ => return None,
+        }),
+        AttribKey::AlignSelf => Value::from(match value {
+            "auto" => AlignSelf::Auto,
+            "flex-start" => AlignSelf::FlexStart,
+            "flex-end" => AlignSelf::FlexEnd,
+            "center" => AlignSelf::Center,
+            "baseline" => AlignSelf::Baseline,
+            "stretch" => AlignSelf::Stretch,
+            _ => return None,
+        }),
+        AttribKey::FlexWrap => Value::from(match value {
+            "nowrap" => FlexWrap::NoWrap,
+            "wrap" => FlexWrap::Wrap,
+            "wrap-reverse" => FlexWrap::WrapReverse,
+            _ => return None,
+        }),
+        _ => Value::from(value.to_string()),
+    };
+    Some(parsed)
+}
+
+/// `auto`, `50%` or points `100`, which are stored as F64 the same way as `width(100.0)`
+fn parse_dimension(value: &str) -> Option<Value> {
+    if value == "auto" {
+        Some(Value::from(Dimension::Auto))
+    } else if let Some(percent) = value.strip_suffix('%') {
+        let percent = percent.parse::<f32>().ok()?;
+        Some(Value::from(widget::attribute::percent(percent)))
+    } else {
+        Some(Value::from(value.parse::<f64>().ok()?))
+    }
+}
+
+/// `100` points, `25%` percent or `1fr` fraction
+fn parse_grid_track(track: &str) -> Option<GridTrack> {
+    if let Some(fraction) = track.strip_suffix("fr") {
+        Some(GridTrack::Fraction(fraction.parse().ok()?))
+    } else if let Some(percent) = track.strip_suffix('%') {
+        Some(GridTrack::Percent(percent.parse::<f32>().ok()? / 100.0))
+    } else {
+        Some(GridTrack::Points(track.parse().ok()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::{
+        attribute::{find_callback, find_value},
+        event::MouseEvent,
+    };
+
+    #[derive(Debug, PartialEq)]
+    enum Msg {
+        Increment,
+    }
+
+    fn load_err(markup: &str) -> MarkupError {
+        match Markup::<Msg>::new().load_str(markup) {
+            Err(Error::Markup(e)) => e,
+            Err(e) => panic!("expecting a markup error, got {}", e),
+            Ok(_) => panic!("expecting an error in {}", markup),
+        }
+    }
+
+    #[test]
+    fn values_are_parsed_for_their_attribute() {
+        let parsed = |key, value| parse_value(&key, value);
+        assert_eq!(parsed(AttribKey::Checked, "true"), Some(Value::from(true)));
+        assert_eq!(parsed(AttribKey::Checked, "yes"), None);
+        assert_eq!(parsed(AttribKey::FlexGrow, " 2 "), Some(Value::from(2.0)));
+        assert_eq!(parsed(AttribKey::RowSpan, "3"), Some(Value::from(3usize)));
+        assert_eq!(parsed(AttribKey::RowSpan, "-1"), None);
+        assert_eq!(
+            parsed(AttribKey::GridColumns, "100 25% 1fr"),
+            Some(Value::from(vec![
+                GridTrack::Points(100.0),
+                GridTrack::Percent(0.25),
+                GridTrack::Fraction(1.0),
+            ]))
+        );
+        assert_eq!(parsed(AttribKey::GridColumns, "100 wide"), None);
+        assert_eq!(
+            parsed(AttribKey::ImageScaling, "fill"),
+            Some(Value::from(ImageScaling::Fill))
+        );
+        assert_eq!(
+            parsed(AttribKey::DropEffect, "move"),
+            Some(Value::from(DropEffect::Move))
+        );
+        assert_eq!(
+            parsed(AttribKey::JustifyContent, "space-between"),
+            Some(Value::from(JustifyContent::SpaceBetween))
+        );
+        assert_eq!(parsed(AttribKey::AlignSelf, "middle"), None);
+        assert_eq!(
+            parsed(AttribKey::Label, " + "),
+            Some(Value::from("+".to_string()))
+        );
+    }
+
+    #[test]
+    fn dimensions_are_points_percent_or_auto() {
+        assert_eq!(parse_dimension("auto"), Some(Value::from(Dimension::Auto)));
+        assert_eq!(
+            parse_dimension("50%"),
+            Some(Value::from(widget::attribute::percent(50.0)))
+        );
+        assert_eq!(parse_dimension("100"), Some(Value::from(100.0)));
+        assert_eq!(parse_dimension("wide"), None);
+        assert_eq!(parse_dimension("%"), None);
+    }
+
+    #[test]
+    fn grid_tracks_are_points_percent_or_fractions() {
+        assert_eq!(parse_grid_track("100"), Some(GridTrack::Points(100.0)));
+        assert_eq!(parse_grid_track("25%"), Some(GridTrack::Percent(0.25)));
+        assert_eq!(parse_grid_track("2fr"), Some(GridTrack::Fraction(2.0)));
+        assert_eq!(parse_grid_track("fr"), None);
+        assert_eq!(parse_grid_track("auto"), None);
+    }
+
+    #[test]
+    fn errors_are_reported_with_their_line() {
+        assert!(matches!(load_err("<column>"), MarkupError::Xml(_)));
+        assert_eq!(
+            load_err("<column>\n    <slider/>\n</column>"),
+            MarkupError::UnknownWidget {
+                tag: "slider".to_string(),
+                line: 2,
+            }
+        );
+        assert_eq!(
+            load_err("<column>\n    <line_chart/>\n</column>"),
+            MarkupError::UnsupportedWidget {
+                tag: "line_chart".to_string(),
+                line: 2,
+            }
+        );
+        assert_eq!(
+            load_err(r#"<button color="red"/>"#),
+            MarkupError::UnknownAttribute {
+                name: "color".to_string(),
+                line: 1,
+            }
+        );
+        assert_eq!(
+            load_err(r#"<text_label width="wide"/>"#),
+            MarkupError::InvalidValue {
+                name: "width".to_string(),
+                value: "wide".to_string(),
+                line: 1,
+            }
+        );
+        assert_eq!(
+            load_err(r#"<button on_click="increment"/>"#),
+            MarkupError::UnboundEvent {
+                slot: "increment".to_string(),
+                line: 1,
+            }
+        );
+        assert_eq!(
+            load_err("<button>\n    <text_label/>\n</button>"),
+            MarkupError::NotContainer {
+                tag: "button".to_string(),
+                line: 1,
+            }
+        );
+        assert_eq!(
+            load_err("<column>\n    hello\n</column>"),
+            MarkupError::UnexpectedText {
+                text: "hello".to_string(),
+                line: 1,
+            }
+        );
+        assert!(matches!(
+            Markup::<Msg>::new().load_file("missing/view.xml"),
+            Err(Error::Markup(MarkupError::Io(_)))
+        ));
+    }
+
+    #[test]
+    fn events_are_bound_to_their_slot() {
+        let node = Markup::new()
+            .on("increment", |_| Msg::Increment)
+            .load_str(r#"<button label="+" on_click="increment"/>"#)
+            .expect("must load");
+        let attrs = node.get_attributes().expect("must be an element");
+        let callbacks = find_callback(AttribKey::ClickEvent, attrs)
+            .expect("must have a click callback");
+        assert_eq!(callbacks[0].emit(MouseEvent::click(1, 1)), Msg::Increment);
+    }
+
+    #[test]
+    fn paragraph_passes_its_attributes_through() {
+        let node = Markup::<Msg>::new()
+            .load_str(r#"<paragraph value="Hello" height="40" name="intro"/>"#)
+            .expect("must load");
+        let attrs = node.get_attributes().expect("must be an element");
+        assert_eq!(
+            find_value(AttribKey::Value, attrs),
+            Some(&Value::from("Hello".to_string()))
+        );
+        assert_eq!(
+            find_value(AttribKey::Name, attrs),
+            Some(&Value::from("intro".to_string()))
+        );
+        let style = get_style(&node).expect("must have a style");
+        assert_eq!(style.size.height, Dimension::Points(40.0));
+    }
+}
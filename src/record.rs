@@ -0,0 +1,357 @@
+//! Record and replay the events of a user session
+//!
+//! `Recording` wraps the app so the callbacks of its view log every event,
+//! the path of the target widget and the resulting MSG into a file with one
+//! json record per line.
+//! The `Replayer` feeds the recorded events into an app without a backend,
+//! or into any backend through its `Dispatch`, to reproduce a bug report.
+//!
+//! ```rust,ignore
+//! GtkBackend::init(Recording::new(Model::new(), "session.jsonl")?);
+//!
+//! let mut app = Model::new();
+//! Replayer::from_file("session.jsonl")?.replay(&mut app)?;
+//! ```
+use crate::{
+    backend::Dispatch,
+    serialize::{find_event_callbacks, EventId},
+    Callback, Component, Event, Node, Widget,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    fmt,
+    fmt::Debug,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    rc::Rc,
+    time::Instant,
+};
+
+/// An event which is recorded from a user session
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    /// the milliseconds elapsed since the recording started
+    pub elapsed_ms: u64,
+    /// the node and the event the callback is attached to
+    pub event_id: EventId,
+    /// the widgets from the root to the target widget
+    pub path: Vec<Widget>,
+    /// the event passed to the callback
+    pub event: Event,
+    /// the debug format of the MSG returned by the callback
+    pub msg: String,
+}
+
+struct Log {
+    file: File,
+    started: Instant,
+}
+
+impl Log {
+    fn write(&mut self, recorded: &RecordedEvent) -> io::Result<()> {
+        serde_json::to_writer(&mut self.file, recorded)?;
+        self.file.write_all(b"\n")?;
+        self.file.flush()
+    }
+}
+
+/// Wraps an app to record the events dispatched to it
+pub struct Recording<APP> {
+    app: APP,
+    log: Rc<RefCell<Log>>,
+}
+
+impl<APP> Recording<APP> {
+    /// record the events of the app into the file at `path`
+    pub fn new(app: APP, path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Recording {
+            app,
+            log: Rc::new(RefCell::new(Log {
+                file,
+                started: Instant::now(),
+            })),
+        })
+    }
+
+    /// the wrapped app
+    pub fn app(&self) -> &APP {
+        &self.app
+    }
+}
+
+impl<APP, MSG> Component<MSG> for Recording<APP>
+where
+    APP: Component<MSG>,
+    MSG: Debug + 'static,
+{
+    fn title(&self) -> String {
+        self.app.title()
+    }
+
+    fn update(&mut self, msg: MSG) {
+        self.app.update(msg);
+    }
+
+    fn view(&self) -> Node<MSG> {
+        let mut node_idx = 0;
+        instrument(self.app.view(), &self.log, &mut node_idx, &mut vec![])
+    }
+}
+
+/// replace the callbacks in the node tree with callbacks which also record the event
+fn instrument<MSG>(
+    node: Node<MSG>,
+    log: &Rc<RefCell<Log>>,
+    node_idx: &mut usize,
+    path: &mut Vec<Widget>,
+) -> Node<MSG>
+where
+    MSG: Debug + 'static,
+{
+    match node {
+        Node::Element(element) => {
+            let current_idx = *node_idx;
+            path.push(element.tag.clone());
+            let mut attrs = vec![];
+            for att in element.attrs.iter() {
+                for value in att.get_plain() {
+                    attrs.push(mt_dom::attr(att.name().clone(), value.clone()));
+                }
+                for cb in att.get_callback() {
+                    let event_id = EventId {
+                        node_idx: current_idx,
+                        event: att.name().clone(),
+                    };
+                    let recorded_cb = record_callback(
+                        cb.clone(),
+                        event_id,
+                        path.clone(),
+                        log,
+                    );
+                    attrs.push(mt_dom::on(att.name().clone(), recorded_cb));
+                }
+            }
+            let children = element
+                .children
+                .into_iter()
+                .map(|child| {
+                    *node_idx += 1;
+                    instrument(child, log, node_idx, path)
+                })
+                .collect();
+            path.pop();
+            mt_dom::element(element.tag, attrs, children)
+        }
+        // the text nodes have no callbacks, but still take a node index
+        Node::Text(_) => node,
+    }
+}
+
+fn record_callback<MSG>(
+    cb: Callback<MSG>,
+    event_id: EventId,
+    path: Vec<Widget>,
+    log: &Rc<RefCell<Log>>,
+) -> Callback<MSG>
+where
+    MSG: Debug + 'static,
+{
+    let log = Rc::clone(log);
+    Callback::from(move |event: Event| {
+        let msg = cb.emit(event.clone());
+        let mut session = log.borrow_mut();
+        let recorded = RecordedEvent {
+            elapsed_ms: session.started.elapsed().as_millis() as u64,
+            event_id: event_id.clone(),
+            path: path.clone(),
+            event,
+            msg: format!("{:?}", msg),
+        };
+        if let Err(e) = session.write(&recorded) {
+            log::error!("unable to record the event: {}", e);
+        }
+        msg
+    })
+}
+
+/// The reasons a replay can not continue
+#[derive(Debug)]
+pub enum ReplayError {
+    /// the widget at the recorded position is not the same widget
+    WidgetMismatch {
+        /// the index of the recorded event
+        index: usize,
+        /// the recorded path of the widget
+        expected: Vec<Widget>,
+        /// the path of the widget found in the current view
+        found: Option<Vec<Widget>>,
+    },
+    /// there is no callback for the recorded event in the current view
+    NoCallback {
+        /// the index of the recorded event
+        index: usize,
+        /// the recorded event id
+        event_id: EventId,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::WidgetMismatch {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "event {}: expecting widget path {:?}, found {:?}",
+                index, expected, found
+            ),
+            ReplayError::NoCallback { index, event_id } => write!(
+                f,
+                "event {}: no callback for {:?} on node {}",
+                index, event_id.event, event_id.node_idx
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Replays the recorded events of a session
+pub struct Replayer {
+    events: Vec<RecordedEvent>,
+}
+
+impl Replayer {
+    /// replay the given events
+    pub fn new(events: Vec<RecordedEvent>) -> Self {
+        Replayer { events }
+    }
+
+    /// load the events recorded into the file at `path`
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut events = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            events.push(serde_json::from_str(&line)?);
+        }
+        Ok(Replayer { events })
+    }
+
+    /// the recorded events
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// feed the events to the app directly, without a backend
+    pub fn replay<APP, MSG>(&self, app: &mut APP) -> Result<(), ReplayError>
+    where
+        APP: Component<MSG>,
+        MSG: 'static,
+    {
+        for index in 0..self.events.len() {
+            let msgs = self.emit(index, &app.view())?;
+            for msg in msgs {
+                app.update(msg);
+            }
+        }
+        Ok(())
+    }
+
+    /// feed the events into a backend, `view` returns the view currently
+    /// displayed by the backend
+    pub fn replay_into<MSG, F, D>(
+        &self,
+        mut view: F,
+        dispatch: &D,
+    ) -> Result<(), ReplayError>
+    where
+        MSG: 'static,
+        F: FnMut() -> Node<MSG>,
+        D: Dispatch<MSG>,
+    {
+        for index in 0..self.events.len() {
+            let msgs = self.emit(index, &view())?;
+            for msg in msgs {
+                dispatch.dispatch(msg);
+            }
+        }
+        Ok(())
+    }
+
+    /// emit the recorded event into the callbacks of the view
+    fn emit<MSG>(
+        &self,
+        index: usize,
+        view: &Node<MSG>,
+    ) -> Result<Vec<MSG>, ReplayError>
+    where
+        MSG: 'static,
+    {
+        let recorded = &self.events[index];
+        let found = widget_path(view, recorded.event_id.node_idx);
+        if found.as_ref() != Some(&recorded.path) {
+            return Err(ReplayError::WidgetMismatch {
+                index,
+                expected: recorded.path.clone(),
+                found,
+            });
+        }
+        let callbacks = find_event_callbacks(view, &recorded.event_id);
+        if callbacks.is_empty() {
+            return Err(ReplayError::NoCallback {
+                index,
+                event_id: recorded.event_id.clone(),
+            });
+        }
+        Ok(callbacks
+            .into_iter()
+            .map(|cb| cb.emit(recorded.event.clone()))
+            .collect())
+    }
+}
+
+/// the widgets from the root to the node at `target_idx` in a depth first traversal
+fn widget_path<MSG>(
+    node: &Node<MSG>,
+    target_idx: usize,
+) -> Option<Vec<Widget>> {
+    fn find<MSG>(
+        node: &Node<MSG>,
+        target_idx: usize,
+        node_idx: &mut usize,
+        path: &mut Vec<Widget>,
+    ) -> bool {
+        let tag = match node.tag() {
+            Some(tag) => tag,
+            None => return false,
+        };
+        path.push(tag.clone());
+        if *node_idx == target_idx {
+            return true;
+        }
+        for child in node.get_children().unwrap_or(&[]) {
+            *node_idx += 1;
+            if find(child, target_idx, node_idx, path) {
+                return true;
+            }
+        }
+        path.pop();
+        false
+    }
+    let mut path = vec![];
+    let mut node_idx = 0;
+    if find(node, target_idx, &mut node_idx, &mut path) {
+        Some(path)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,177 @@
+//! Serializable representation of the Node tree
+//!
+//! Callbacks can not be serialized, so they are replaced by an `EventId`
+//! which identifies the node and the event the callbacks are attached to.
+//! The EventId is used to bind the callbacks back when deserializing
+//! or to find the callbacks in the live Node tree when replaying events.
+use crate::{AttribKey, Callback, Node, Value, Widget};
+use serde::{Deserialize, Serialize};
+
+pub(crate) mod remote;
+
+/// Identifies the callbacks of an event in a Node tree
+#[derive(
+    Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub struct EventId {
+    /// the index of the node in a depth first traversal of the tree
+    pub node_idx: usize,
+    /// the event the callbacks are attached to
+    pub event: AttribKey,
+}
+
+/// An attribute with its callbacks replaced by EventId
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SerializedAttribute {
+    /// a plain value of an attribute
+    Value(AttribKey, Value),
+    /// the callbacks of an event
+    Event(EventId),
+}
+
+/// A Node tree which can be serialized
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SerializedNode {
+    /// a widget with its attributes and child nodes
+    Element {
+        /// the widget of this node
+        widget: Widget,
+        /// the attributes of this node
+        attributes: Vec<SerializedAttribute>,
+        /// the child nodes
+        children: Vec<SerializedNode>,
+    },
+    /// a text node
+    Text(String),
+}
+
+impl SerializedNode {
+    /// create a serializable copy of the node tree
+    pub fn from_node<MSG>(node: &Node<MSG>) -> Self
+    where
+        MSG: 'static,
+    {
+        let mut node_idx = 0;
+        Self::from_node_with_idx(node, &mut node_idx)
+    }
+
+    fn from_node_with_idx<MSG>(node: &Node<MSG>, node_idx: &mut usize) -> Self
+    where
+        MSG: 'static,
+    {
+        match node {
+            Node::Element(element) => {
+                let current_idx = *node_idx;
+                let mut attributes = vec![];
+                for att in element.attrs.iter() {
+                    for value in att.get_plain() {
+                        attributes.push(SerializedAttribute::Value(
+                            att.name().clone(),
+                            value.clone(),
+                        ));
+                    }
+                    if !att.get_callback().is_empty() {
+                        attributes.push(SerializedAttribute::Event(EventId {
+                            node_idx: current_idx,
+                            event: att.name().clone(),
+                        }));
+                    }
+                }
+                let children = element
+                    .children
+                    .iter()
+                    .map(|child| {
+                        *node_idx += 1;
+                        Self::from_node_with_idx(child, node_idx)
+                    })
+                    .collect();
+                SerializedNode::Element {
+                    widget: element.tag.clone(),
+                    attributes,
+                    children,
+                }
+            }
+            Node::Text(txt) => SerializedNode::Text(txt.to_string()),
+        }
+    }
+
+    /// convert back into a Node tree, the callbacks are supplied by `bind`,
+    /// the events which `bind` returns None for are left out
+    pub fn into_node<MSG, F>(self, mut bind: F) -> Node<MSG>
+    where
+        MSG: 'static,
+        F: FnMut(&EventId) -> Option<Callback<MSG>>,
+    {
+        self.into_node_with_bind(&mut bind)
+    }
+
+    fn into_node_with_bind<MSG, F>(self, bind: &mut F) -> Node<MSG>
+    where
+        MSG: 'static,
+        F: FnMut(&EventId) -> Option<Callback<MSG>>,
+    {
+        let (widget, attributes, children) = match self {
+            SerializedNode::Element {
+                widget,
+                attributes,
+                children,
+            } => (widget, attributes, children),
+            SerializedNode::Text(txt) => return mt_dom::text(txt),
+        };
+        let attrs = attributes
+            .into_iter()
+            .filter_map(|att| match att {
+                SerializedAttribute::Value(key, value) => {
+                    Some(mt_dom::attr(key, value))
+                }
+                SerializedAttribute::Event(event_id) => bind(&event_id)
+                    .map(|cb| mt_dom::on(event_id.event.clone(), cb)),
+            })
+            .collect();
+        let children = children
+            .into_iter()
+            .map(|child| child.into_node_with_bind(bind))
+            .collect();
+        mt_dom::element(widget, attrs, children)
+    }
+}
+
+/// find the callbacks of the event identified by `event_id` in the live node tree
+pub fn find_event_callbacks<'a, MSG>(
+    node: &'a Node<MSG>,
+    event_id: &EventId,
+) -> Vec<&'a Callback<MSG>>
+where
+    MSG: 'static,
+{
+    let mut node_idx = 0;
+    find_node(node, event_id.node_idx, &mut node_idx)
+        .map(|found| {
+            found
+                .get_attributes()
+                .unwrap_or(&[])
+                .iter()
+                .filter(|att| *att.name() == event_id.event)
+                .flat_map(|att| att.get_callback())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// find the node at `target_idx` in a depth first traversal
+fn find_node<'a, MSG>(
+    node: &'a Node<MSG>,
+    target_idx: usize,
+    node_idx: &mut usize,
+) -> Option<&'a Node<MSG>> {
+    if *node_idx == target_idx {
+        return Some(node);
+    }
+    for child in node.get_children().unwrap_or(&[]) {
+        *node_idx += 1;
+        if let Some(found) = find_node(child, target_idx, node_idx) {
+            return Some(found);
+        }
+    }
+    None
+}
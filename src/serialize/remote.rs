@@ -0,0 +1,243 @@
+//! serde definitions of the stretch types, which do not implement serde
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use stretch::{
+    geometry::{Point, Rect, Size},
+    number::Number,
+    result::Layout,
+    style::{
+        AlignContent, AlignItems, AlignSelf, Dimension, Direction, Display,
+        FlexDirection, FlexWrap, JustifyContent, Overflow, PositionType, Style,
+    },
+};
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AlignItems")]
+pub(crate) enum AlignItemsDef {
+    FlexStart,
+    FlexEnd,
+    Center,
+    Baseline,
+    Stretch,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AlignSelf")]
+pub(crate) enum AlignSelfDef {
+    Auto,
+    FlexStart,
+    FlexEnd,
+    Center,
+    Baseline,
+    Stretch,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AlignContent")]
+enum AlignContentDef {
+    FlexStart,
+    FlexEnd,
+    Center,
+    Stretch,
+    SpaceBetween,
+    SpaceAround,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Direction")]
+enum DirectionDef {
+    Inherit,
+    LTR,
+    RTL,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Display")]
+enum DisplayDef {
+    Flex,
+    None,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "FlexDirection")]
+enum FlexDirectionDef {
+    Row,
+    Column,
+    RowReverse,
+    ColumnReverse,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "JustifyContent")]
+pub(crate) enum JustifyContentDef {
+    FlexStart,
+    FlexEnd,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Overflow")]
+enum OverflowDef {
+    Visible,
+    Hidden,
+    Scroll,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "PositionType")]
+pub(crate) enum PositionTypeDef {
+    Relative,
+    Absolute,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "FlexWrap")]
+pub(crate) enum FlexWrapDef {
+    NoWrap,
+    Wrap,
+    WrapReverse,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Dimension")]
+pub(crate) enum DimensionDef {
+    Undefined,
+    Auto,
+    Points(f32),
+    Percent(f32),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Number")]
+enum NumberDef {
+    Defined(f32),
+    Undefined,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Rect<Dimension>")]
+struct RectDef {
+    #[serde(with = "DimensionDef")]
+    start: Dimension,
+    #[serde(with = "DimensionDef")]
+    end: Dimension,
+    #[serde(with = "DimensionDef")]
+    top: Dimension,
+    #[serde(with = "DimensionDef")]
+    bottom: Dimension,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Size<Dimension>")]
+struct SizeDef {
+    #[serde(with = "DimensionDef")]
+    width: Dimension,
+    #[serde(with = "DimensionDef")]
+    height: Dimension,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Style")]
+pub(crate) struct StyleDef {
+    #[serde(with = "DisplayDef")]
+    display: Display,
+    #[serde(with = "PositionTypeDef")]
+    position_type: PositionType,
+    #[serde(with = "DirectionDef")]
+    direction: Direction,
+    #[serde(with = "FlexDirectionDef")]
+    flex_direction: FlexDirection,
+    #[serde(with = "FlexWrapDef")]
+    flex_wrap: FlexWrap,
+    #[serde(with = "OverflowDef")]
+    overflow: Overflow,
+    #[serde(with = "AlignItemsDef")]
+    align_items: AlignItems,
+    #[serde(with = "AlignSelfDef")]
+    align_self: AlignSelf,
+    #[serde(with = "AlignContentDef")]
+    align_content: AlignContent,
+    #[serde(with = "JustifyContentDef")]
+    justify_content: JustifyContent,
+    #[serde(with = "RectDef")]
+    position: Rect<Dimension>,
+    #[serde(with = "RectDef")]
+    margin: Rect<Dimension>,
+    #[serde(with = "RectDef")]
+    padding: Rect<Dimension>,
+    #[serde(with = "RectDef")]
+    border: Rect<Dimension>,
+    flex_grow: f32,
+    flex_shrink: f32,
+    #[serde(with = "DimensionDef")]
+    flex_basis: Dimension,
+    #[serde(with = "SizeDef")]
+    size: Size<Dimension>,
+    #[serde(with = "SizeDef")]
+    min_size: Size<Dimension>,
+    #[serde(with = "SizeDef")]
+    max_size: Size<Dimension>,
+    #[serde(with = "NumberDef")]
+    aspect_ratio: Number,
+}
+
+/// the location and size of a calculated Layout
+#[derive(Serialize, Deserialize)]
+struct LayoutDef {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+/// serde functions for Layout, used with `#[serde(with = "layout")]`
+pub(crate) mod layout {
+    use super::*;
+
+    pub(crate) fn serialize<S>(
+        layout: &Layout,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        LayoutDef {
+            x: layout.location.x,
+            y: layout.location.y,
+            width: layout.size.width,
+            height: layout.size.height,
+        }
+        .serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<Layout, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let def = LayoutDef::deserialize(deserializer)?;
+        let mut layout = empty_layout();
+        layout.location = Point { x: def.x, y: def.y };
+        layout.size = Size {
+            width: def.width,
+            height: def.height,
+        };
+        Ok(layout)
+    }
+
+    /// Layout has a private field and no public constructor,
+    /// so an empty one is obtained from computing the layout of an empty node
+    fn empty_layout() -> Layout {
+        let mut stretch = stretch::Stretch::new();
+        let node = stretch
+            .new_node(Style::default(), &[])
+            .expect("must create a node");
+        stretch
+            .compute_layout(node, Size::undefined())
+            .expect("must compute layout");
+        *stretch.layout(node).expect("must have a layout")
+    }
+}
@@ -0,0 +1,250 @@
+//! Snapshot testing of the rendered views
+//!
+//! The view is rendered into a stable text which lists the widget tree,
+//! the attributes of each widget, its computed layout rectangle and the text nodes.
+//! With the `with-html` feature, the view can also be snapshot as the html markup
+//! produced by the html backend, so regressions in the conversion of the widgets show up too.
+//! The text is compared with the snapshot stored in `tests/snapshots/<name>.snap`,
+//! so layout regressions show up as a failing `cargo test`.
+//!
+//! A missing snapshot fails the test. The snapshots are created, or overwritten
+//! with the rendered views, when the `SAURON_NATIVE_UPDATE_SNAPSHOTS` environment variable is set.
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn todo_list() {
+//!     sauron_native::snapshot::assert_view_snapshot("todo_list", &Model::new(), 800.0, 600.0);
+//! }
+//! ```
+use crate::{
+    widget::{attribute::util::get_layout, layout::compute_node_layout},
+    AttribKey, Component, Node, Value,
+};
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+use stretch::{geometry::Size, number::Number};
+
+/// the environment variable which overwrites the snapshots with the rendered views
+pub const UPDATE_SNAPSHOTS_ENV: &str = "SAURON_NATIVE_UPDATE_SNAPSHOTS";
+
+/// render the view of the app with its layout computed for the given size
+pub fn render_view<APP, MSG>(app: &APP, width: f32, height: f32) -> String
+where
+    APP: Component<MSG>,
+    MSG: 'static,
+{
+    render_node(&layout_view(app, width, height))
+}
+
+/// render the view of the app into the html markup of the html backend,
+/// with its layout computed for the given size
+#[cfg(feature = "with-html")]
+pub fn render_html_view<APP, MSG>(app: &APP, width: f32, height: f32) -> String
+where
+    APP: Component<MSG>,
+    MSG: Clone + std::fmt::Debug + 'static,
+{
+    crate::backend::html::render_html(&layout_view(app, width, height))
+}
+
+/// the view of the app with its layout computed for the given size
+fn layout_view<APP, MSG>(app: &APP, width: f32, height: f32) -> Node<MSG>
+where
+    APP: Component<MSG>,
+    MSG: 'static,
+{
+    let mut view = app.view();
+    compute_node_layout(
+        &mut view,
+        Size {
+            width: Number::Defined(width),
+            height: Number::Defined(height),
+        },
+    );
+    view
+}
+
+/// render the node tree into a stable text
+pub fn render_node<MSG>(node: &Node<MSG>) -> String
+where
+    MSG: 'static,
+{
+    let mut buffer = String::new();
+    render_node_with_indent(node, 0, &mut buffer);
+    buffer
+}
+
+fn render_node_with_indent<MSG>(
+    node: &Node<MSG>,
+    indent: usize,
+    buffer: &mut String,
+) where
+    MSG: 'static,
+{
+    let padding = "    ".repeat(indent);
+    let element = match node {
+        Node::Element(element) => element,
+        Node::Text(txt) => {
+            writeln!(buffer, "{}{:?}", padding, txt.to_string())
+                .expect("must write");
+            return;
+        }
+    };
+    write!(buffer, "{}{:?}", padding, element.tag).expect("must write");
+    if let Some(layout) = get_layout(element) {
+        write!(
+            buffer,
+            " [x: {:.1}, y: {:.1}, width: {:.1}, height: {:.1}]",
+            layout.location.x,
+            layout.location.y,
+            layout.size.width,
+            layout.size.height
+        )
+        .expect("must write");
+    }
+    buffer.push('\n');
+
+    let mut attributes = vec![];
+    for att in element.get_attributes() {
+        match att.name() {
+            // the layout is shown next to the widget, and the style
+            // is already reflected in the layout
+            AttribKey::Layout | AttribKey::Style => continue,
+            _ => (),
+        }
+        for value in att.get_plain() {
+            attributes.push((att.name(), format_value(value)));
+        }
+        if !att.get_callback().is_empty() {
+            attributes.push((att.name(), "<callback>".to_string()));
+        }
+    }
+    // the attributes are sorted so the order they are declared in does not matter
+    attributes.sort();
+    for (key, value) in attributes {
+        writeln!(buffer, "{}  - {:?}: {}", padding, key, value)
+            .expect("must write");
+    }
+
+    for child in element.get_children() {
+        render_node_with_indent(child, indent + 1, buffer);
+    }
+}
+
+/// format the value without the verbose content such as image bytes
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(v) => format!("{:?}", v),
+        Value::Str(v) => format!("{:?}", v),
+        Value::Bool(v) => v.to_string(),
+        Value::F64(v) => v.to_string(),
+        Value::Bytes(v) => format!("<{} bytes>", v.len()),
+        _ => format!("{:?}", value),
+    }
+}
+
+/// the location of the snapshot files of the crate being tested
+pub fn snapshot_dir() -> PathBuf {
+    let manifest_dir =
+        env::var_os("CARGO_MANIFEST_DIR").unwrap_or_else(|| ".".into());
+    Path::new(&manifest_dir).join("tests").join("snapshots")
+}
+
+/// render the view of the app and compare it with the snapshot named `name`
+pub fn assert_view_snapshot<APP, MSG>(
+    name: &str,
+    app: &APP,
+    width: f32,
+    height: f32,
+) where
+    APP: Component<MSG>,
+    MSG: 'static,
+{
+    let rendered = render_view(app, width, height);
+    let path = snapshot_dir().join(format!("{}.snap", name));
+    assert_snapshot(path, &rendered);
+}
+
+/// render the view of the app into html and compare it with the snapshot named `name`
+#[cfg(feature = "with-html")]
+pub fn assert_html_snapshot<APP, MSG>(
+    name: &str,
+    app: &APP,
+    width: f32,
+    height: f32,
+) where
+    APP: Component<MSG>,
+    MSG: Clone + std::fmt::Debug + 'static,
+{
+    let rendered = render_html_view(app, width, height);
+    let path = snapshot_dir().join(format!("{}.html.snap", name));
+    assert_snapshot(path, &rendered);
+}
+
+/// compare the rendered text with the snapshot stored in `path`,
+/// panics with the differing lines when they don't match, or when the snapshot is missing.
+/// The snapshot is overwritten instead when `SAURON_NATIVE_UPDATE_SNAPSHOTS` is set.
+pub fn assert_snapshot(path: impl AsRef<Path>, rendered: &str) {
+    let path = path.as_ref();
+    if env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .expect("unable to create the snapshot directory");
+        }
+        fs::write(path, rendered).expect("unable to write the snapshot");
+        return;
+    }
+    compare_snapshot(path, rendered);
+}
+
+/// compare the rendered text with the snapshot stored in `path` like `assert_snapshot`,
+/// but never write the snapshot, whether `SAURON_NATIVE_UPDATE_SNAPSHOTS` is set or not
+pub fn compare_snapshot(path: impl AsRef<Path>, rendered: &str) {
+    let path = path.as_ref();
+    match fs::read_to_string(path) {
+        Ok(stored) => {
+            if stored != rendered {
+                panic!(
+                    "snapshot {} does not match, set {}=1 to update it\n{}",
+                    path.display(),
+                    UPDATE_SNAPSHOTS_ENV,
+                    diff_lines(&stored, rendered)
+                );
+            }
+        }
+        Err(_) => panic!(
+            "snapshot {} is missing, set {}=1 to create it\n{}",
+            path.display(),
+            UPDATE_SNAPSHOTS_ENV,
+            rendered
+        ),
+    }
+}
+
+/// list the lines of both texts, marking the stored lines with `-`
+/// and the rendered lines with `+` where they differ
+fn diff_lines(stored: &str, rendered: &str) -> String {
+    let stored: Vec<&str> = stored.lines().collect();
+    let rendered: Vec<&str> = rendered.lines().collect();
+    let mut buffer = String::new();
+    for i in 0..stored.len().max(rendered.len()) {
+        match (stored.get(i), rendered.get(i)) {
+            (Some(old), Some(new)) if old == new => {
+                writeln!(buffer, "  {}", old).expect("must write")
+            }
+            (old, new) => {
+                if let Some(old) = old {
+                    writeln!(buffer, "- {}", old).expect("must write");
+                }
+                if let Some(new) = new {
+                    writeln!(buffer, "+ {}", new).expect("must write");
+                }
+            }
+        }
+    }
+    buffer
+}
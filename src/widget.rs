@@ -5,7 +5,13 @@ use crate::widget::attribute::style;
 use crate::widget::attribute::util::find_value;
 use crate::{AttribKey, Attribute, Node, Value};
 pub use builder::*;
+pub use image_source::{clear_image_cache, ImageSource};
+pub use layout::{GridCell, GridTrack};
+pub(crate) use lazy::evict_unused_lazy;
+pub use lazy::{clear_lazy_cache, lazy};
 use mt_dom::{attr, element};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use stretch::geometry::Size;
 use stretch::style::Dimension;
@@ -15,8 +21,13 @@ use stretch::style::Style;
 
 pub mod attribute;
 mod builder;
+pub mod canvas;
+pub mod chart;
 pub mod event;
-pub(crate) mod layout;
+pub mod image_source;
+pub mod layout;
+mod lazy;
+pub mod stateful;
 
 /// TODO: Each widget variant will need to have more details
 ///  such as attributes, that will be converted to their
@@ -26,6 +37,7 @@ pub(crate) mod layout;
 /// This will have a counterparts for each of the supported
 /// different platforms
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Widget {
     /// vertical flexbox
     Vbox,
@@ -58,6 +70,8 @@ pub enum Widget {
     Overlay,
     /// groupbox
     GroupBox,
+    /// a grid container where children are placed in rows and columns
+    Grid,
     /// headerbar, such as in gtk where it can contains
     /// menu buttons
     HeaderBar,
@@ -67,10 +81,18 @@ pub enum Widget {
     Menu,
     /// menu item
     MenuItem,
+    /// a line between the groups of menu items
+    MenuSeparator,
+    /// a popup menu of its parent widget,
+    /// shown on right click, the menu key or a long press
+    ContextMenu,
     /// Search entry
     SearchInput,
     /// Link button
     Link,
+    /// a canvas which draws a list of drawing commands,
+    /// or the chart in its chart attribute
+    Canvas,
 }
 
 impl Widget {
@@ -83,10 +105,12 @@ impl Widget {
             | Widget::Hpane
             | Widget::Overlay
             | Widget::GroupBox
+            | Widget::Grid
             | Widget::HeaderBar
             | Widget::MenuBar
             | Widget::Menu
-            | Widget::MenuItem => true,
+            | Widget::MenuItem
+            | Widget::ContextMenu => true,
 
             Widget::SearchInput
             | Widget::Button
@@ -98,6 +122,8 @@ impl Widget {
             | Widget::Image
             | Widget::Svg
             | Widget::Link
+            | Widget::Canvas
+            | Widget::MenuSeparator
             | Widget::TextArea => false,
         }
     }
@@ -3,9 +3,11 @@
 pub use super::event::Event;
 use crate::Attribute;
 use mt_dom::attr;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
-use stretch::style::Style;
-pub use util::{find_callback, find_value, get_style};
+use stretch::style::{Dimension, Style};
+pub use util::{find_callback, find_value, get_style, merge_style};
 pub use value::Value;
 
 pub mod util;
@@ -35,12 +37,13 @@ macro_rules! declare_attr {
 /// These are attribute keys used in sauron-native, which will be translated to their
 /// corresponding backends
 #[derive(Clone, PartialEq, PartialOrd, Debug, Eq, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AttribKey {
     /// String, used in text_input
     Value,
     /// String, used in button, label, checkbox, radio
     Label,
-    /// bool, used in checkbox, radio
+    /// bool, used in checkbox, radio and the checkable menu items
     Checked,
     /// Alignment Enum, used in hbox and vbox
     Alignment,
@@ -50,6 +53,8 @@ pub enum AttribKey {
     Editable,
     /// data, used in image blobs and svg
     Data,
+    /// ImageSource, where the data of the image is loaded from, used in image
+    Source,
     /// svg image data attribute used in button
     SvgImage,
     /// The style attribute
@@ -94,6 +99,102 @@ pub enum AttribKey {
     Uri,
     /// Placeholder is used in text input, search input, and text_area
     Placeholder,
+    /// the sizes of the rows in a grid
+    GridRows,
+    /// the sizes of the columns in a grid
+    GridColumns,
+    /// the row index of a widget inside a grid
+    GridRow,
+    /// the column index of a widget inside a grid
+    GridColumn,
+    /// the number of rows a widget spans inside a grid
+    RowSpan,
+    /// the number of columns a widget spans inside a grid
+    ColumnSpan,
+    /// how much the widget grows relative to its siblings
+    FlexGrow,
+    /// how much the widget shrinks relative to its siblings
+    FlexShrink,
+    /// the initial size of the widget before growing or shrinking
+    FlexBasis,
+    /// how the children are aligned along the main axis of a container
+    JustifyContent,
+    /// how the children are aligned along the cross axis of a container
+    AlignItems,
+    /// overrides the AlignItems of the parent container for this widget
+    AlignSelf,
+    /// whether the children of a container wraps into multiple lines
+    FlexWrap,
+    /// the minimum width of the widget
+    MinWidth,
+    /// the minimum height of the widget
+    MinHeight,
+    /// the maximum width of the widget
+    MaxWidth,
+    /// the maximum height of the widget
+    MaxHeight,
+    /// how an image is scaled into the size of the widget
+    ImageScaling,
+    /// bool, whether the animation of an animated image is paused
+    Paused,
+    /// bool, whether the animation of an animated image starts over when it ends
+    Looping,
+    /// the animation of an animated image has finished playing
+    AnimationEndEvent,
+    /// the drawing commands of a canvas
+    Drawing,
+    /// the series and axes of a chart
+    Chart,
+    /// the pointer has moved over a chart, nearer to one of its data points
+    ChartHoverEvent,
+    /// the payload of a widget which can be dragged
+    DragData,
+    /// the operation of a drag and drop, allowed by a drag source or accepted by a drop target
+    DropEffect,
+    /// something is dropped into the widget
+    DropEvent,
+    /// the drag of the widget has finished, whether it was dropped or not
+    DragEndEvent,
+    /// bool, whether the widget is greyed out and can not be activated, used in menu items
+    Disabled,
+    /// the keyboard shortcut displayed in a menu item, such as `Ctrl+Shift+S`
+    Accelerator,
+}
+
+/// How an image is scaled when it is smaller or larger than its widget,
+/// the image is displayed in its original size unless a scaling is set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ImageScaling {
+    /// scale the image to fit inside the widget, keeping its aspect ratio
+    Fit,
+    /// scale the image to cover the whole widget, keeping its aspect ratio,
+    /// the parts outside of the widget are cut off
+    Fill,
+    /// scale the image to the size of the widget, ignoring its aspect ratio
+    Stretch,
+    /// display the image in its original size, the parts outside of the widget are cut off
+    None,
+}
+
+impl Default for ImageScaling {
+    fn default() -> Self {
+        ImageScaling::None
+    }
+}
+
+/// The operation of a drag and drop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DropEffect {
+    /// nothing is dropped, the drag is cancelled
+    None,
+    /// the payload is copied into the drop target
+    Copy,
+    /// the payload is moved into the drop target, the drag source removes it
+    Move,
+    /// the drop target refers to the payload
+    Link,
 }
 
 declare_attr! {
@@ -101,6 +202,8 @@ declare_attr! {
     value => Value;
     /// data attribute, used in image, svg
     data => Data;
+    /// the source of the image data, used in image instead of data
+    source => Source;
     /// label attribute, used in button, checkbox and radio
     label => Label;
     /// height attribute, used in most widgets
@@ -110,10 +213,34 @@ declare_attr! {
     editable => Editable;
     /// scrollable attribute
     scrollable => Scrollable;
-    /// specified width
+    /// specified width, in points or a percent Dimension
     width => Width;
-    /// specified height
+    /// specified height, in points or a percent Dimension
     height => Height;
+    /// absolute or relative position
+    position_type => PositionType;
+    /// flex grow factor
+    flex_grow => FlexGrow;
+    /// flex shrink factor
+    flex_shrink => FlexShrink;
+    /// flex basis, in points or a percent Dimension
+    flex_basis => FlexBasis;
+    /// justify content of a container
+    justify_content => JustifyContent;
+    /// align items of a container
+    align_items => AlignItems;
+    /// align self
+    align_self => AlignSelf;
+    /// flex wrap of a container
+    flex_wrap => FlexWrap;
+    /// minimum width
+    min_width => MinWidth;
+    /// minimum height
+    min_height => MinHeight;
+    /// maximum width
+    max_width => MaxWidth;
+    /// maximum height
+    max_height => MaxHeight;
     /// resizable item
     resizable => Resizable;
     /// monospace
@@ -132,6 +259,36 @@ declare_attr! {
     placeholder => Placeholder;
     /// key
     key => Key;
+    /// the row sizes of a grid
+    grid_rows => GridRows;
+    /// the column sizes of a grid
+    grid_columns => GridColumns;
+    /// the row of the widget in a grid
+    grid_row => GridRow;
+    /// the column of the widget in a grid
+    grid_column => GridColumn;
+    /// the number of rows the widget spans in a grid
+    row_span => RowSpan;
+    /// the number of columns the widget spans in a grid
+    column_span => ColumnSpan;
+    /// how the image is scaled into the size of the widget
+    image_scaling => ImageScaling;
+    /// pause the animation of an animated image
+    paused => Paused;
+    /// play the animation of an animated image repeatedly, defaults to true
+    looping => Looping;
+    /// the drawing commands of a canvas
+    drawing => Drawing;
+    /// make the widget draggable, carrying this payload to the drop target
+    drag_data => DragData;
+    /// the operation allowed when dragging the widget, or accepted when dropping into it,
+    /// both copy and move are allowed if not specified
+    drop_effect => DropEffect;
+    /// grey out the menu item, so it can not be activated
+    disabled => Disabled;
+    /// the keyboard shortcut displayed in a menu item, such as `Ctrl+S`.
+    /// It is only displayed, the app handles the key event itself
+    accelerator => Accelerator;
 }
 
 impl fmt::Display for AttribKey {
@@ -141,6 +298,15 @@ impl fmt::Display for AttribKey {
 }
 
 /// create a style attribute from the style in stretch crate
-pub(crate) fn style<MSG>(style: Style) -> Attribute<MSG> {
+///
+/// The builders derive the style from the layout attributes such as `width`,
+/// `flex_grow` and `align_items`, a style attribute specified before
+/// the builder adds its own takes precedence.
+pub fn style<MSG>(style: Style) -> Attribute<MSG> {
     attr(AttribKey::Style, Value::Style(style))
 }
+
+/// a dimension relative to the size of the parent, 100.0 is the full size
+pub fn percent(v: f32) -> Dimension {
+    Dimension::Percent(v / 100.0)
+}
@@ -1,8 +1,10 @@
 //! utility functions for manipulating attributes
 //!
 use crate::{
-    widget::attribute::AttribKey, Attribute, Callback, Element, Node, Value,
+    widget::{attribute::AttribKey, layout::GridCell},
+    Attribute, Callback, Element, Node, Value,
 };
+use stretch::geometry::Size;
 use stretch::result::Layout;
 use stretch::style::Style;
 
@@ -58,3 +60,74 @@ pub fn get_layout<MSG>(element: &Element<MSG>) -> Option<&Layout> {
         .flatten()
         .flatten()
 }
+
+/// return the cell of a grid child widget, defaults to the first row and column
+/// spanning 1 row and 1 column
+pub fn get_grid_cell<MSG: 'static>(attrs: &[Attribute<MSG>]) -> GridCell {
+    let index = |key| {
+        find_value(key, attrs)
+            .map(|v| v.as_f64())
+            .flatten()
+            .map(|v| v.max(0.0) as usize)
+    };
+    GridCell {
+        row: index(AttribKey::GridRow).unwrap_or(0),
+        column: index(AttribKey::GridColumn).unwrap_or(0),
+        row_span: index(AttribKey::RowSpan).unwrap_or(1).max(1),
+        column_span: index(AttribKey::ColumnSpan).unwrap_or(1).max(1),
+    }
+}
+
+/// override the fields of the style with the layout attributes specified in attrs,
+/// fields which are not specified retain their value from `style`
+pub fn merge_style<MSG: 'static>(
+    attrs: &[Attribute<MSG>],
+    style: Style,
+) -> Style {
+    let value = |key| find_value(key, attrs);
+    let dimension = |key| value(key).map(|v| v.as_dimension()).flatten();
+    let factor =
+        |key| value(key).map(|v| v.as_f64()).flatten().map(|v| v as f32);
+    Style {
+        position_type: value(AttribKey::PositionType)
+            .map(|v| v.as_position_type())
+            .flatten()
+            .unwrap_or(style.position_type),
+        flex_wrap: value(AttribKey::FlexWrap)
+            .map(|v| v.as_flex_wrap())
+            .flatten()
+            .unwrap_or(style.flex_wrap),
+        align_items: value(AttribKey::AlignItems)
+            .map(|v| v.as_align_items())
+            .flatten()
+            .unwrap_or(style.align_items),
+        align_self: value(AttribKey::AlignSelf)
+            .map(|v| v.as_align_self())
+            .flatten()
+            .unwrap_or(style.align_self),
+        justify_content: value(AttribKey::JustifyContent)
+            .map(|v| v.as_justify_content())
+            .flatten()
+            .unwrap_or(style.justify_content),
+        flex_grow: factor(AttribKey::FlexGrow).unwrap_or(style.flex_grow),
+        flex_shrink: factor(AttribKey::FlexShrink).unwrap_or(style.flex_shrink),
+        flex_basis: dimension(AttribKey::FlexBasis).unwrap_or(style.flex_basis),
+        size: Size {
+            width: dimension(AttribKey::Width).unwrap_or(style.size.width),
+            height: dimension(AttribKey::Height).unwrap_or(style.size.height),
+        },
+        min_size: Size {
+            width: dimension(AttribKey::MinWidth)
+                .unwrap_or(style.min_size.width),
+            height: dimension(AttribKey::MinHeight)
+                .unwrap_or(style.min_size.height),
+        },
+        max_size: Size {
+            width: dimension(AttribKey::MaxWidth)
+                .unwrap_or(style.max_size.width),
+            height: dimension(AttribKey::MaxHeight)
+                .unwrap_or(style.max_size.height),
+        },
+        ..style
+    }
+}
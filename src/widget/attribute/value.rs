@@ -1,27 +1,86 @@
+#[cfg(feature = "serde")]
+use crate::serialize::remote::{
+    layout, AlignItemsDef, AlignSelfDef, DimensionDef, FlexWrapDef,
+    JustifyContentDef, PositionTypeDef, StyleDef,
+};
+use crate::widget::{
+    attribute::{DropEffect, ImageScaling},
+    canvas::DrawCommand,
+    chart::Chart,
+    image_source::ImageSource,
+    layout::GridTrack,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use stretch::result::Layout;
-use stretch::style::PositionType;
-use stretch::style::Style;
+use stretch::style::{
+    AlignItems, AlignSelf, Dimension, FlexWrap, JustifyContent, PositionType,
+    Style,
+};
 
 /// The possible values of widget attributes
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Value {
     /// generic strings, used in labels, btn labels
     String(String),
     /// a static version of the string, for efficient storage
-    Str(&'static str),
+    /// it is serialized as a String, and deserialized back as String
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "String"), skip_deserializing)
+    )]
+    Str(#[cfg_attr(feature = "serde", serde(skip_deserializing))] &'static str),
     /// boolean values such as checked,
     Bool(bool),
     /// Bytes value, used in controls such as image
     Bytes(Vec<u8>),
     /// The stretch style used in calculating the layout of a widget
-    Style(Style),
+    Style(#[cfg_attr(feature = "serde", serde(with = "StyleDef"))] Style),
     /// the calculated layout of a widget
-    Layout(Layout),
+    Layout(#[cfg_attr(feature = "serde", serde(with = "layout"))] Layout),
     /// Position type of a widget
-    PositionType(PositionType),
+    PositionType(
+        #[cfg_attr(feature = "serde", serde(with = "PositionTypeDef"))]
+        PositionType,
+    ),
+    /// A dimension in points or percentage, used in width, height and flex basis
+    Dimension(
+        #[cfg_attr(feature = "serde", serde(with = "DimensionDef"))] Dimension,
+    ),
+    /// alignment of the children along the main axis
+    JustifyContent(
+        #[cfg_attr(feature = "serde", serde(with = "JustifyContentDef"))]
+        JustifyContent,
+    ),
+    /// alignment of the children along the cross axis
+    AlignItems(
+        #[cfg_attr(feature = "serde", serde(with = "AlignItemsDef"))]
+        AlignItems,
+    ),
+    /// alignment of a widget along the cross axis of its parent
+    AlignSelf(
+        #[cfg_attr(feature = "serde", serde(with = "AlignSelfDef"))] AlignSelf,
+    ),
+    /// wrapping of the children in a container
+    FlexWrap(
+        #[cfg_attr(feature = "serde", serde(with = "FlexWrapDef"))] FlexWrap,
+    ),
     /// float values
     F64(f64),
+    /// the track sizes of the rows or columns of a grid
+    GridTracks(Vec<GridTrack>),
+    /// how an image is scaled into the size of the widget
+    ImageScaling(ImageScaling),
+    /// where the data of an image is loaded from
+    ImageSource(ImageSource),
+    /// the drawing commands of a canvas
+    Drawing(Vec<DrawCommand>),
+    /// the series and axes of a chart
+    Chart(Box<Chart>),
+    /// the operation of a drag and drop
+    DropEffect(DropEffect),
 }
 
 impl Value {
@@ -66,6 +125,46 @@ impl Value {
         }
     }
 
+    /// return the image scaling if it is an ImageScaling variant
+    pub fn as_image_scaling(&self) -> Option<ImageScaling> {
+        match self {
+            Value::ImageScaling(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// return the image source if it is an ImageSource variant
+    pub fn as_image_source(&self) -> Option<&ImageSource> {
+        match self {
+            Value::ImageSource(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// return the drawing commands if it is a Drawing variant
+    pub fn as_drawing(&self) -> Option<&[DrawCommand]> {
+        match self {
+            Value::Drawing(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// return the drop effect if it is a DropEffect variant
+    pub fn as_drop_effect(&self) -> Option<DropEffect> {
+        match self {
+            Value::DropEffect(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// return the chart if it is a Chart variant
+    pub fn as_chart(&self) -> Option<&Chart> {
+        match self {
+            Value::Chart(v) => Some(v),
+            _ => None,
+        }
+    }
+
     /// return the layout if it is a layout variant
     pub fn as_layout(&self) -> Option<&Layout> {
         match self {
@@ -74,6 +173,56 @@ impl Value {
         }
     }
 
+    /// return the dimension if it is a Dimension variant,
+    /// a float value is treated as points
+    pub fn as_dimension(&self) -> Option<Dimension> {
+        match self {
+            Value::Dimension(dimension) => Some(*dimension),
+            Value::F64(v) => Some(Dimension::Points(*v as f32)),
+            _ => None,
+        }
+    }
+
+    /// return the justify content if it is a JustifyContent variant
+    pub fn as_justify_content(&self) -> Option<JustifyContent> {
+        match self {
+            Value::JustifyContent(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// return the align items if it is an AlignItems variant
+    pub fn as_align_items(&self) -> Option<AlignItems> {
+        match self {
+            Value::AlignItems(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// return the align self if it is an AlignSelf variant
+    pub fn as_align_self(&self) -> Option<AlignSelf> {
+        match self {
+            Value::AlignSelf(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// return the flex wrap if it is a FlexWrap variant
+    pub fn as_flex_wrap(&self) -> Option<FlexWrap> {
+        match self {
+            Value::FlexWrap(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// return the grid tracks if it is a GridTracks variant
+    pub fn as_grid_tracks(&self) -> Option<&[GridTrack]> {
+        match self {
+            Value::GridTracks(tracks) => Some(&tracks),
+            _ => None,
+        }
+    }
+
     /// return the position type if it is a PositionType variant
     pub fn as_position_type(&self) -> Option<PositionType> {
         match self {
@@ -125,6 +274,12 @@ impl From<u64> for Value {
     }
 }
 
+impl From<usize> for Value {
+    fn from(v: usize) -> Self {
+        Value::F64(v as f64)
+    }
+}
+
 impl From<i8> for Value {
     fn from(v: i8) -> Self {
         Value::F64(v as f64)
@@ -185,6 +340,72 @@ impl From<PositionType> for Value {
     }
 }
 
+impl From<Dimension> for Value {
+    fn from(v: Dimension) -> Self {
+        Value::Dimension(v)
+    }
+}
+
+impl From<JustifyContent> for Value {
+    fn from(v: JustifyContent) -> Self {
+        Value::JustifyContent(v)
+    }
+}
+
+impl From<AlignItems> for Value {
+    fn from(v: AlignItems) -> Self {
+        Value::AlignItems(v)
+    }
+}
+
+impl From<AlignSelf> for Value {
+    fn from(v: AlignSelf) -> Self {
+        Value::AlignSelf(v)
+    }
+}
+
+impl From<FlexWrap> for Value {
+    fn from(v: FlexWrap) -> Self {
+        Value::FlexWrap(v)
+    }
+}
+
+impl From<ImageScaling> for Value {
+    fn from(v: ImageScaling) -> Self {
+        Value::ImageScaling(v)
+    }
+}
+
+impl From<ImageSource> for Value {
+    fn from(v: ImageSource) -> Self {
+        Value::ImageSource(v)
+    }
+}
+
+impl From<Vec<DrawCommand>> for Value {
+    fn from(v: Vec<DrawCommand>) -> Self {
+        Value::Drawing(v)
+    }
+}
+
+impl From<DropEffect> for Value {
+    fn from(v: DropEffect) -> Self {
+        Value::DropEffect(v)
+    }
+}
+
+impl From<Chart> for Value {
+    fn from(v: Chart) -> Self {
+        Value::Chart(Box::new(v))
+    }
+}
+
+impl From<Vec<GridTrack>> for Value {
+    fn from(v: Vec<GridTrack>) -> Self {
+        Value::GridTracks(v)
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -192,7 +413,10 @@ impl fmt::Display for Value {
             Value::Str(s) => write!(f, "{}", s),
             Value::Bool(b) => write!(f, "{}", b),
             Value::F64(v) => write!(f, "{}", v),
-            _ => todo!(),
+            Value::Bytes(v) => write!(f, "<{} bytes>", v.len()),
+            // the layout related values have no textual form,
+            // so they are displayed with their debug format
+            _ => write!(f, "{:?}", self),
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::widget::attribute;
 use crate::widget::attribute::style;
-use crate::widget::attribute::util::find_value;
+use crate::widget::attribute::util::{get_style, merge_style};
+use crate::widget::chart::{Axis, Chart, ChartKind, Series};
 use crate::Widget;
 use crate::{AttribKey, Attribute, Node, Value};
 use mt_dom::{attr, element};
@@ -22,6 +23,29 @@ where
     element(widget, attrs, children)
 }
 
+/// the default style of most widgets, which fills the whole space of its parent
+fn fill_style() -> Style {
+    Style {
+        size: Size {
+            width: Dimension::Percent(1.0),
+            height: Dimension::Percent(1.0),
+        },
+        ..Default::default()
+    }
+}
+
+/// the default style of the bar widgets, which fills the width of its parent
+/// and takes only the height it needs
+fn bar_style() -> Style {
+    Style {
+        size: Size {
+            width: Dimension::Percent(1.0),
+            height: Dimension::Auto,
+        },
+        ..Default::default()
+    }
+}
+
 /// a vertically oriented flexbox
 pub fn column<MSG>(
     mut attrs: Vec<Attribute<MSG>>,
@@ -30,38 +54,14 @@ pub fn column<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        flex_direction: FlexDirection::Column,
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(
+        &attrs,
+        Style {
+            flex_direction: FlexDirection::Column,
+            ..fill_style()
+        },
+    );
+    attrs.push(style(node_style));
     widget(Widget::Vbox, attrs, children)
 }
 
@@ -73,38 +73,14 @@ pub fn row<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        flex_direction: FlexDirection::Row,
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(
+        &attrs,
+        Style {
+            flex_direction: FlexDirection::Row,
+            ..fill_style()
+        },
+    );
+    attrs.push(style(node_style));
     widget(Widget::Hbox, attrs, children)
 }
 
@@ -116,38 +92,14 @@ pub fn vpane<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        flex_direction: FlexDirection::Column,
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(
+        &attrs,
+        Style {
+            flex_direction: FlexDirection::Column,
+            ..fill_style()
+        },
+    );
+    attrs.push(style(node_style));
     widget(Widget::Vpane, attrs, children)
 }
 
@@ -159,38 +111,14 @@ pub fn hpane<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        flex_direction: FlexDirection::Row,
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(
+        &attrs,
+        Style {
+            flex_direction: FlexDirection::Row,
+            ..fill_style()
+        },
+    );
+    attrs.push(style(node_style));
     widget(Widget::Hpane, attrs, children)
 }
 
@@ -202,35 +130,31 @@ pub fn overlay<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
+    // the children are already built with their styles merged,
+    // so the position type is set into the style they are laid out with
     children.iter_mut().for_each(|child| {
+        let child_style = Style {
+            position_type: PositionType::Absolute,
+            ..get_style(child).cloned().unwrap_or_default()
+        };
+        if let Node::Element(child_element) = child {
+            child_element
+                .attrs
+                .retain(|att| *att.name() != AttribKey::Style);
+            child_element.attrs.push(style(child_style));
+        }
         child.add_attributes_ref_mut(vec![attr(
             AttribKey::PositionType,
             Value::from(PositionType::Absolute),
         )]);
     });
 
-    attrs.push(style(Style {
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-        },
-        ..Default::default()
-    }));
+    // the overlay itself is not affected by the position type
+    let node_style = Style {
+        position_type: Default::default(),
+        ..merge_style(&attrs, fill_style())
+    };
+    attrs.push(style(node_style));
     widget(Widget::Overlay, attrs, children)
 }
 
@@ -242,76 +166,33 @@ pub fn groupbox<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
     widget(Widget::GroupBox, attrs, children)
 }
 
+/// a grid container, the children are placed using the `grid_row`, `grid_column`,
+/// `row_span` and `column_span` attributes, while the size of each row and column
+/// is specified with `grid_rows` and `grid_columns`
+pub fn grid<MSG>(
+    mut attrs: Vec<Attribute<MSG>>,
+    children: Vec<Node<MSG>>,
+) -> Node<MSG>
+where
+    MSG: 'static,
+{
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
+    widget(Widget::Grid, attrs, children)
+}
+
 /// create a button
 pub fn button<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
     widget(Widget::Button, attrs, vec![])
 }
 
@@ -322,16 +203,7 @@ where
 {
     widget(
         Widget::Paragraph,
-        vec![
-            attribute::value(txt.to_string()),
-            style(Style {
-                size: Size {
-                    width: Dimension::Percent(1.0),
-                    height: Dimension::Percent(1.0),
-                },
-                ..Default::default()
-            }),
-        ],
+        vec![attribute::value(txt.to_string()), style(fill_style())],
         vec![],
     )
 }
@@ -341,38 +213,28 @@ pub fn text_input<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: Dimension::Percent(1.0),
-            height: Dimension::Percent(1.0),
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
     widget(Widget::TextInput, attrs, vec![])
 }
 
 /// create a checkbox control
-pub fn checkbox<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG>
+pub fn checkbox<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
+    let node_style = merge_style(&attrs, Style::default());
+    attrs.push(style(node_style));
     widget(Widget::Checkbox, attrs, vec![])
 }
 
 /// create a radio control
-pub fn radio<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG>
+pub fn radio<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
+    let node_style = merge_style(&attrs, Style::default());
+    attrs.push(style(node_style));
     widget(Widget::Radio, attrs, vec![])
 }
 
@@ -381,22 +243,8 @@ pub fn image<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: Dimension::Percent(1.0),
-            height: Dimension::Percent(1.0),
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
     widget(Widget::Image, attrs, vec![])
 }
 
@@ -405,22 +253,8 @@ pub fn svg<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: Dimension::Percent(1.0),
-            height: Dimension::Percent(1.0),
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
     widget(Widget::Svg, attrs, vec![])
 }
 
@@ -429,22 +263,8 @@ pub fn textarea<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: Dimension::Percent(1.0),
-            height: Dimension::Percent(1.0),
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
     widget(Widget::TextArea, attrs, vec![])
 }
 
@@ -453,25 +273,12 @@ pub fn text_label<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: Dimension::Percent(1.0),
-            height: Dimension::Percent(1.0),
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
     widget(Widget::Label, attrs, vec![])
 }
 
+/// create a header bar, which can contain menu buttons
 pub fn header_bar<MSG>(
     mut attrs: Vec<Attribute<MSG>>,
     children: Vec<Node<MSG>>,
@@ -479,44 +286,21 @@ pub fn header_bar<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Auto
+    let node_style = merge_style(
+        &attrs,
+        Style {
+            min_size: Size {
+                height: Dimension::Points(30.0),
+                ..Default::default()
             },
+            ..bar_style()
         },
-        min_size: Size {
-            height: Dimension::Points(30.0),
-            ..Default::default()
-        },
-        ..Default::default()
-    }));
+    );
+    attrs.push(style(node_style));
     widget(Widget::HeaderBar, attrs, children)
 }
 
+/// create a menu bar
 pub fn menu_bar<MSG>(
     mut attrs: Vec<Attribute<MSG>>,
     children: Vec<Node<MSG>>,
@@ -524,40 +308,12 @@ pub fn menu_bar<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Auto
-            },
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, bar_style());
+    attrs.push(style(node_style));
     widget(Widget::MenuBar, attrs, children)
 }
 
+/// create a menu
 pub fn menu<MSG>(
     mut attrs: Vec<Attribute<MSG>>,
     children: Vec<Node<MSG>>,
@@ -565,40 +321,18 @@ pub fn menu<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Auto
-            },
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, bar_style());
+    attrs.push(style(node_style));
     widget(Widget::Menu, attrs, children)
 }
 
+/// create a menu item, which shows its `label` or its text label children,
+/// and has its submenu as the last child.
+///
+/// The `svg_image` is shown as its icon, and the `accelerator` next to its label.
+/// A menu item with a `checked` attribute is a check menu item,
+/// and a radio menu item when it also has a `name`, which is shared by the menu items
+/// of the same group in a menu. The `disabled` menu items can not be activated.
 pub fn menu_item<MSG>(
     mut attrs: Vec<Attribute<MSG>>,
     children: Vec<Node<MSG>>,
@@ -606,38 +340,58 @@ pub fn menu_item<MSG>(
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Auto
+    let node_style = merge_style(&attrs, bar_style());
+    attrs.push(style(node_style));
+    widget(Widget::MenuItem, attrs, children)
+}
+
+/// create a separator line between the menu items
+pub fn menu_separator<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
+where
+    MSG: 'static,
+{
+    let node_style = merge_style(&attrs, bar_style());
+    attrs.push(style(node_style));
+    widget(Widget::MenuSeparator, attrs, vec![])
+}
+
+/// create a context menu, which pops up from its parent widget
+/// on a right click, the menu key or a long press.
+/// Its children are `menu_item`s, and it takes no space in the layout of its parent.
+/// The context menu is supported in gtk and html, titik has no popups
+/// so the context menu is never shown in the terminal
+pub fn context_menu<MSG>(
+    mut attrs: Vec<Attribute<MSG>>,
+    children: Vec<Node<MSG>>,
+) -> Node<MSG>
+where
+    MSG: 'static,
+{
+    let node_style = merge_style(
+        &attrs,
+        Style {
+            position_type: PositionType::Absolute,
+            size: Size {
+                width: Dimension::Points(200.0),
+                height: Dimension::Auto,
             },
+            ..Default::default()
         },
-        ..Default::default()
-    }));
-    widget(Widget::MenuItem, attrs, children)
+    );
+    attrs.push(style(node_style));
+    widget(Widget::ContextMenu, attrs, children)
+}
+
+/// attach the context menu to the widget,
+/// which is how the widgets which are not containers get a context menu
+pub fn with_context_menu<MSG>(
+    node: Node<MSG>,
+    context_menu: Node<MSG>,
+) -> Node<MSG>
+where
+    MSG: 'static,
+{
+    node.add_children(vec![context_menu])
 }
 
 /// create a text input
@@ -645,22 +399,8 @@ pub fn search_input<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: Dimension::Percent(1.0),
-            height: Dimension::Percent(1.0),
-        },
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
     widget(Widget::SearchInput, attrs, vec![])
 }
 
@@ -669,42 +409,77 @@ pub fn link<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
 where
     MSG: 'static,
 {
-    let spec_width = find_value(AttribKey::Width, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-    let spec_height = find_value(AttribKey::Height, &attrs)
-        .map(|w| w.as_f64())
-        .flatten();
-
-    let spec_position = find_value(AttribKey::PositionType, &attrs)
-        .map(|w| w.as_position_type())
-        .flatten();
-
-    attrs.push(style(Style {
-        position_type: if let Some(spec_position) = spec_position {
-            spec_position
-        } else {
-            Default::default()
-        },
-        size: Size {
-            width: if let Some(width) = spec_width {
-                Dimension::Points(width as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-            height: if let Some(height) = spec_height {
-                Dimension::Points(height as f32)
-            } else {
-                Dimension::Percent(1.0)
-            },
-        },
-        /*
-        min_size: Size {
-            height: Dimension::Points(30.0),
-            ..Default::default()
-        },
-        */
-        ..Default::default()
-    }));
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
     widget(Widget::Link, attrs, vec![])
 }
+
+/// create a canvas which draws the commands in its drawing attribute
+pub fn canvas<MSG>(mut attrs: Vec<Attribute<MSG>>) -> Node<MSG>
+where
+    MSG: 'static,
+{
+    let node_style = merge_style(&attrs, fill_style());
+    attrs.push(style(node_style));
+    widget(Widget::Canvas, attrs, vec![])
+}
+
+/// create a line chart, the points of each series are connected with lines
+pub fn line_chart<MSG>(
+    attrs: Vec<Attribute<MSG>>,
+    series: Vec<Series>,
+    x_axis: Axis,
+    y_axis: Axis,
+) -> Node<MSG>
+where
+    MSG: 'static,
+{
+    chart(ChartKind::Line, attrs, series, x_axis, y_axis)
+}
+
+/// create a bar chart, the points with the same index in each series are grouped together
+pub fn bar_chart<MSG>(
+    attrs: Vec<Attribute<MSG>>,
+    series: Vec<Series>,
+    x_axis: Axis,
+    y_axis: Axis,
+) -> Node<MSG>
+where
+    MSG: 'static,
+{
+    chart(ChartKind::Bar, attrs, series, x_axis, y_axis)
+}
+
+/// create a scatter plot, each point of the series is drawn as a dot
+pub fn scatter_plot<MSG>(
+    attrs: Vec<Attribute<MSG>>,
+    series: Vec<Series>,
+    x_axis: Axis,
+    y_axis: Axis,
+) -> Node<MSG>
+where
+    MSG: 'static,
+{
+    chart(ChartKind::Scatter, attrs, series, x_axis, y_axis)
+}
+
+/// a canvas with the chart in its chart attribute
+fn chart<MSG>(
+    kind: ChartKind,
+    mut attrs: Vec<Attribute<MSG>>,
+    series: Vec<Series>,
+    x_axis: Axis,
+    y_axis: Axis,
+) -> Node<MSG>
+where
+    MSG: 'static,
+{
+    let chart = Chart {
+        kind,
+        series,
+        x_axis,
+        y_axis,
+    };
+    attrs.push(attr(AttribKey::Chart, Value::from(chart)));
+    canvas(attrs)
+}
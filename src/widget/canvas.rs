@@ -0,0 +1,624 @@
+//! Drawing commands of a canvas, similar to the 2D context of a html canvas
+//!
+//! The commands are kept in the `drawing` attribute of the canvas, so the backends
+//! draw them directly instead of parsing an svg document on every update.
+//! The mouse events of a canvas have their coordinates relative to the canvas.
+//!
+//! ```rust,ignore
+//! canvas(vec![
+//!     drawing(vec![
+//!         DrawCommand::FillStyle(Color::rgb(200, 0, 0)),
+//!         DrawCommand::Rect { x: 10.0, y: 10.0, width: 50.0, height: 50.0 },
+//!         DrawCommand::Fill,
+//!     ]),
+//!     on_mousedown(|me| Msg::Pressed(me.x(), me.y())),
+//! ])
+//! ```
+use crate::{
+    image_util,
+    widget::{attribute::util::find_value, ImageSource},
+    AttribKey, Attribute,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{f64::consts::FRAC_PI_2, f64::consts::PI, fmt::Write};
+
+/// A color with red, green, blue and alpha components
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Color {
+    /// red
+    pub red: u8,
+    /// green
+    pub green: u8,
+    /// blue
+    pub blue: u8,
+    /// alpha, 255 is opaque
+    pub alpha: u8,
+}
+
+impl Color {
+    /// opaque black
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    /// opaque white
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    /// fully transparent
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+    /// an opaque color
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Color::rgba(red, green, blue, 255)
+    }
+
+    /// a color with transparency
+    pub const fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Color {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// the components of the color in the range 0.0 to 1.0
+    pub fn to_f64(&self) -> (f64, f64, f64, f64) {
+        (
+            self.red as f64 / 255.0,
+            self.green as f64 / 255.0,
+            self.blue as f64 / 255.0,
+            self.alpha as f64 / 255.0,
+        )
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color::BLACK
+    }
+}
+
+/// A command drawn into the canvas, in the order they are listed
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DrawCommand {
+    /// start a new path, discarding the current path
+    BeginPath,
+    /// start a new sub path at the point
+    MoveTo(f64, f64),
+    /// add a straight line from the current point to the point
+    LineTo(f64, f64),
+    /// add a cubic bezier curve from the current point to the point (x, y)
+    BezierCurveTo {
+        /// the first control point
+        cp1: (f64, f64),
+        /// the second control point
+        cp2: (f64, f64),
+        /// the end point
+        x: f64,
+        /// the end point
+        y: f64,
+    },
+    /// add an arc, going clockwise from the start to the end angle in radians
+    Arc {
+        /// the center of the arc
+        x: f64,
+        /// the center of the arc
+        y: f64,
+        /// the radius of the arc
+        radius: f64,
+        /// the start angle, 0.0 is at the right of the center
+        start_angle: f64,
+        /// the end angle
+        end_angle: f64,
+    },
+    /// add a rectangle as a closed sub path
+    Rect {
+        /// the left of the rectangle
+        x: f64,
+        /// the top of the rectangle
+        y: f64,
+        /// the width of the rectangle
+        width: f64,
+        /// the height of the rectangle
+        height: f64,
+    },
+    /// add a straight line to the start of the current sub path
+    ClosePath,
+    /// fill the current path with the fill style
+    Fill,
+    /// stroke the current path with the stroke style and line width
+    Stroke,
+    /// the color used in fill and text
+    FillStyle(Color),
+    /// the color used in stroke
+    StrokeStyle(Color),
+    /// the width of the stroked lines
+    LineWidth(f64),
+    /// draw the text with the fill style, at the baseline of the point,
+    /// the current path is not changed
+    Text {
+        /// the start of the text
+        x: f64,
+        /// the baseline of the text
+        y: f64,
+        /// the text
+        text: String,
+        /// the font size
+        size: f64,
+    },
+    /// draw the image into the rectangle, the current path is not changed
+    Image {
+        /// the left of the image
+        x: f64,
+        /// the top of the image
+        y: f64,
+        /// the width the image is scaled to
+        width: f64,
+        /// the height the image is scaled to
+        height: f64,
+        /// the image
+        source: ImageSource,
+    },
+    /// save the styles and the transform
+    Save,
+    /// restore the styles and the transform that are saved last
+    Restore,
+    /// move the origin of the next commands
+    Translate(f64, f64),
+    /// scale the next commands
+    Scale(f64, f64),
+    /// rotate the next commands around the origin, in radians clockwise
+    Rotate(f64),
+}
+
+/// the drawing commands of a canvas displayed in this size,
+/// from its chart if it has one, otherwise from its drawing attribute
+pub(crate) fn canvas_commands<MSG>(
+    attrs: &[Attribute<MSG>],
+    width: f32,
+    height: f32,
+) -> Vec<DrawCommand>
+where
+    MSG: 'static,
+{
+    if let Some(chart) =
+        find_value(AttribKey::Chart, attrs).and_then(|v| v.as_chart())
+    {
+        chart.draw(width, height)
+    } else {
+        find_value(AttribKey::Drawing, attrs)
+            .and_then(|v| v.as_drawing())
+            .map(|commands| commands.to_vec())
+            .unwrap_or_default()
+    }
+}
+
+/// the end angle of an arc, so it goes clockwise from the start angle.
+/// The sweep is at most a full circle, the same as in a html canvas
+pub(crate) fn clockwise_end_angle(start_angle: f64, end_angle: f64) -> f64 {
+    let sweep = end_angle - start_angle;
+    if !sweep.is_finite() {
+        start_angle
+    } else if sweep >= 2.0 * PI {
+        start_angle + 2.0 * PI
+    } else {
+        start_angle + sweep.rem_euclid(2.0 * PI)
+    }
+}
+
+/// the styles and transform of the svg elements, which are saved and restored
+#[derive(Clone)]
+struct SvgState {
+    /// the affine transform [a, b, c, d, e, f]
+    transform: [f64; 6],
+    fill: Color,
+    stroke: Color,
+    line_width: f64,
+}
+
+impl Default for SvgState {
+    fn default() -> Self {
+        SvgState {
+            transform: [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            fill: Color::BLACK,
+            stroke: Color::BLACK,
+            line_width: 1.0,
+        }
+    }
+}
+
+impl SvgState {
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let [a, b, c, d, e, f] = self.transform;
+        (a * x + c * y + e, b * x + d * y + f)
+    }
+
+    /// how much the lengths are scaled by the transform
+    fn scale_factor(&self) -> f64 {
+        let [a, b, c, d, _, _] = self.transform;
+        (a * d - b * c).abs().sqrt()
+    }
+}
+
+/// render the drawing commands into an svg document, the points of the paths
+/// are transformed as they are added, the same as in a html canvas
+pub fn to_svg(
+    commands: &[DrawCommand],
+    width: f32,
+    height: f32,
+) -> String {
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height
+    );
+    let mut state = SvgState::default();
+    let mut saved = vec![];
+    let mut path = String::new();
+    let mut has_point = false;
+
+    for command in commands {
+        match command {
+            DrawCommand::BeginPath => {
+                path.clear();
+                has_point = false;
+            }
+            DrawCommand::MoveTo(x, y) => {
+                let (x, y) = state.apply(*x, *y);
+                write!(path, "M{} {} ", x, y).expect("must write");
+                has_point = true;
+            }
+            DrawCommand::LineTo(x, y) => {
+                let (x, y) = state.apply(*x, *y);
+                let op = if has_point { "L" } else { "M" };
+                write!(path, "{}{} {} ", op, x, y).expect("must write");
+                has_point = true;
+            }
+            DrawCommand::BezierCurveTo { cp1, cp2, x, y } => {
+                if !has_point {
+                    let (x, y) = state.apply(cp1.0, cp1.1);
+                    write!(path, "M{} {} ", x, y).expect("must write");
+                }
+                let cp1 = state.apply(cp1.0, cp1.1);
+                let cp2 = state.apply(cp2.0, cp2.1);
+                let (x, y) = state.apply(*x, *y);
+                write!(
+                    path,
+                    "C{} {} {} {} {} {} ",
+                    cp1.0, cp1.1, cp2.0, cp2.1, x, y
+                )
+                .expect("must write");
+                has_point = true;
+            }
+            DrawCommand::Arc {
+                x,
+                y,
+                radius,
+                start_angle,
+                end_angle,
+            } => {
+                let end_angle = clockwise_end_angle(*start_angle, *end_angle);
+                write_arc(
+                    &mut path,
+                    &state,
+                    (*x, *y, *radius),
+                    (*start_angle, end_angle),
+                    has_point,
+                );
+                has_point = true;
+            }
+            DrawCommand::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                let corners = [
+                    state.apply(*x, *y),
+                    state.apply(x + width, *y),
+                    state.apply(x + width, y + height),
+                    state.apply(*x, y + height),
+                ];
+                write!(
+                    path,
+                    "M{} {} L{} {} L{} {} L{} {} Z ",
+                    corners[0].0,
+                    corners[0].1,
+                    corners[1].0,
+                    corners[1].1,
+                    corners[2].0,
+                    corners[2].1,
+                    corners[3].0,
+                    corners[3].1
+                )
+                .expect("must write");
+                has_point = true;
+            }
+            DrawCommand::ClosePath => {
+                if has_point {
+                    path.push_str("Z ");
+                }
+            }
+            DrawCommand::Fill => {
+                if !path.is_empty() {
+                    write!(
+                        svg,
+                        r#"<path d="{}" fill="{}" fill-opacity="{}" stroke="none"/>"#,
+                        path.trim_end(),
+                        svg_color(&state.fill),
+                        state.fill.to_f64().3
+                    )
+                    .expect("must write");
+                }
+            }
+            DrawCommand::Stroke => {
+                if !path.is_empty() {
+                    write!(
+                        svg,
+                        r#"<path d="{}" fill="none" stroke="{}" stroke-opacity="{}" stroke-width="{}"/>"#,
+                        path.trim_end(),
+                        svg_color(&state.stroke),
+                        state.stroke.to_f64().3,
+                        state.line_width * state.scale_factor()
+                    )
+                    .expect("must write");
+                }
+            }
+            DrawCommand::FillStyle(color) => state.fill = *color,
+            DrawCommand::StrokeStyle(color) => state.stroke = *color,
+            DrawCommand::LineWidth(line_width) => {
+                state.line_width = *line_width
+            }
+            DrawCommand::Text { x, y, text, size } => {
+                write!(
+                    svg,
+                    r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" fill="{}" fill-opacity="{}" transform="{}">{}</text>"#,
+                    x,
+                    y,
+                    size,
+                    svg_color(&state.fill),
+                    state.fill.to_f64().3,
+                    svg_transform(&state),
+                    escape(text)
+                )
+                .expect("must write");
+            }
+            DrawCommand::Image {
+                x,
+                y,
+                width,
+                height,
+                source,
+            } => {
+                let image = source
+                    .load()
+                    .and_then(|bytes| image_util::web_image(&bytes));
+                match image {
+                    Ok((mime_type, bytes)) => write!(
+                        svg,
+                        r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none" transform="{}" xlink:href="data:{};base64,{}"/>"#,
+                        x,
+                        y,
+                        width,
+                        height,
+                        svg_transform(&state),
+                        mime_type,
+                        base64::encode(&bytes)
+                    )
+                    .expect("must write"),
+                    Err(e) => {
+                        log::warn!("unable to draw the image {:?}: {}", source, e)
+                    }
+                }
+            }
+            DrawCommand::Save => saved.push(state.clone()),
+            DrawCommand::Restore => {
+                if let Some(restored) = saved.pop() {
+                    state = restored;
+                }
+            }
+            DrawCommand::Translate(tx, ty) => {
+                let [a, b, c, d, e, f] = state.transform;
+                state.transform =
+                    [a, b, c, d, e + a * tx + c * ty, f + b * tx + d * ty];
+            }
+            DrawCommand::Scale(sx, sy) => {
+                let [a, b, c, d, e, f] = state.transform;
+                state.transform = [a * sx, b * sx, c * sy, d * sy, e, f];
+            }
+            DrawCommand::Rotate(angle) => {
+                let [a, b, c, d, e, f] = state.transform;
+                let (sin, cos) = angle.sin_cos();
+                state.transform = [
+                    a * cos + c * sin,
+                    b * cos + d * sin,
+                    c * cos - a * sin,
+                    d * cos - b * sin,
+                    e,
+                    f,
+                ];
+            }
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// add the arc as cubic bezier curves of at most a quarter circle each,
+/// so it can be transformed by its points
+fn write_arc(
+    path: &mut String,
+    state: &SvgState,
+    (cx, cy, radius): (f64, f64, f64),
+    (start_angle, end_angle): (f64, f64),
+    has_point: bool,
+) {
+    let point =
+        |angle: f64| (cx + radius * angle.cos(), cy + radius * angle.sin());
+    let (x, y) = state.apply(point(start_angle).0, point(start_angle).1);
+    let op = if has_point { "L" } else { "M" };
+    write!(path, "{}{} {} ", op, x, y).expect("must write");
+
+    let sweep = end_angle - start_angle;
+    let segments = (sweep / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / segments as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+    for i in 0..segments {
+        let a1 = start_angle + step * i as f64;
+        let a2 = a1 + step;
+        let (x1, y1) = point(a1);
+        let (x2, y2) = point(a2);
+        let cp1 = state.apply(x1 - k * a1.sin(), y1 + k * a1.cos());
+        let cp2 = state.apply(x2 + k * a2.sin(), y2 - k * a2.cos());
+        let end = state.apply(x2, y2);
+        write!(
+            path,
+            "C{} {} {} {} {} {} ",
+            cp1.0, cp1.1, cp2.0, cp2.1, end.0, end.1
+        )
+        .expect("must write");
+    }
+}
+
+fn svg_color(color: &Color) -> String {
+    format!("rgb({},{},{})", color.red, color.green, color.blue)
+}
+
+fn svg_transform(state: &SvgState) -> String {
+    let [a, b, c, d, e, f] = state.transform;
+    format!("matrix({} {} {} {} {} {})", a, b, c, d, e, f)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the numbers in the path of the first svg path element
+    fn path_numbers(svg: &str) -> Vec<f64> {
+        let start = svg.find(r#" d=""#).expect("must have a path") + 4;
+        let end = start + svg[start..].find('"').expect("must be closed");
+        svg[start..end]
+            .split_whitespace()
+            .map(|number| {
+                number
+                    .trim_start_matches(char::is_alphabetic)
+                    .parse()
+                    .expect("must be a number")
+            })
+            .collect()
+    }
+
+    fn curves(svg: &str) -> usize {
+        svg.matches('C').count()
+    }
+
+    fn assert_near(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    fn stroked_arc(start_angle: f64, end_angle: f64) -> String {
+        let commands = vec![
+            DrawCommand::Arc {
+                x: 50.0,
+                y: 50.0,
+                radius: 10.0,
+                start_angle,
+                end_angle,
+            },
+            DrawCommand::Stroke,
+        ];
+        to_svg(&commands, 100.0, 100.0)
+    }
+
+    #[test]
+    fn end_angle_goes_clockwise_from_the_start() {
+        assert_eq!(clockwise_end_angle(1.0, 1.0), 1.0);
+        assert_eq!(clockwise_end_angle(0.0, PI), PI);
+        assert_near(
+            &[clockwise_end_angle(0.0, -FRAC_PI_2)],
+            &[3.0 * FRAC_PI_2],
+        );
+        assert_near(&[clockwise_end_angle(PI, 0.0)], &[2.0 * PI]);
+        assert_eq!(clockwise_end_angle(0.0, -2.0 * PI), 0.0);
+    }
+
+    #[test]
+    fn end_angle_is_at_most_a_full_circle() {
+        assert_eq!(clockwise_end_angle(0.0, 2.0 * PI), 2.0 * PI);
+        assert_eq!(clockwise_end_angle(0.0, 5.0 * PI), 2.0 * PI);
+        assert_eq!(clockwise_end_angle(PI, 10.0 * PI), 3.0 * PI);
+        assert_eq!(clockwise_end_angle(1.0, f64::INFINITY), 1.0);
+        assert_eq!(clockwise_end_angle(1.0, f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn zero_sweep_arc_is_a_point() {
+        let svg = stroked_arc(0.0, 0.0);
+        assert_eq!(curves(&svg), 1);
+        assert_near(
+            &path_numbers(&svg),
+            &[60.0, 50.0, 60.0, 50.0, 60.0, 50.0, 60.0, 50.0],
+        );
+    }
+
+    #[test]
+    fn negative_sweep_arc_goes_clockwise_around() {
+        let svg = stroked_arc(0.0, -FRAC_PI_2);
+        assert_eq!(curves(&svg), 3);
+        let numbers = path_numbers(&svg);
+        assert_near(&numbers[..2], &[60.0, 50.0]);
+        assert_near(&numbers[numbers.len() - 2..], &[50.0, 40.0]);
+    }
+
+    #[test]
+    fn full_circle_arc_is_drawn_once() {
+        let svg = stroked_arc(0.0, 2.0 * PI);
+        assert_eq!(curves(&svg), 4);
+        let numbers = path_numbers(&svg);
+        assert_near(&numbers[numbers.len() - 2..], &[60.0, 50.0]);
+        assert_eq!(stroked_arc(0.0, 6.0 * PI), svg);
+    }
+
+    #[test]
+    fn nested_transforms_are_restored_in_order() {
+        let commands = vec![
+            DrawCommand::Save,
+            DrawCommand::Translate(10.0, 20.0),
+            DrawCommand::Save,
+            DrawCommand::Rotate(FRAC_PI_2),
+            DrawCommand::MoveTo(5.0, 0.0),
+            DrawCommand::Restore,
+            DrawCommand::LineTo(5.0, 0.0),
+            DrawCommand::Restore,
+            DrawCommand::LineTo(5.0, 0.0),
+            DrawCommand::Stroke,
+        ];
+        let svg = to_svg(&commands, 100.0, 100.0);
+        assert_near(&path_numbers(&svg), &[10.0, 25.0, 15.0, 20.0, 5.0, 0.0]);
+    }
+
+    #[test]
+    fn restore_without_save_keeps_the_transform() {
+        let commands = vec![
+            DrawCommand::Translate(10.0, 20.0),
+            DrawCommand::Scale(2.0, 2.0),
+            DrawCommand::Restore,
+            DrawCommand::MoveTo(1.0, 1.0),
+            DrawCommand::LineTo(2.0, 1.0),
+            DrawCommand::Stroke,
+        ];
+        let svg = to_svg(&commands, 100.0, 100.0);
+        assert_near(&path_numbers(&svg), &[12.0, 22.0, 14.0, 22.0]);
+        assert!(svg.contains(r#"stroke-width="2""#), "{}", svg);
+    }
+}
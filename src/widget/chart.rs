@@ -0,0 +1,423 @@
+//! Line, bar and scatter charts drawn with the drawing commands of a canvas
+//!
+//! The chart is kept as an attribute of the canvas, and the backends turn it into
+//! drawing commands for the size the canvas is displayed in.
+//!
+//! ```rust,ignore
+//! line_chart(
+//!     vec![on_chart_hover(Msg::Hovered)],
+//!     vec![Series::new("cpu", self.cpu_samples.clone())],
+//!     Axis::default().label("time"),
+//!     Axis::default().label("%").range(0.0, 100.0),
+//! )
+//! ```
+use crate::widget::canvas::{Color, DrawCommand};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// the colors of the series which have no color of their own
+const PALETTE: [Color; 6] = [
+    Color::rgb(31, 119, 180),
+    Color::rgb(255, 127, 14),
+    Color::rgb(44, 160, 44),
+    Color::rgb(214, 39, 40),
+    Color::rgb(148, 103, 189),
+    Color::rgb(140, 86, 75),
+];
+const AXIS_COLOR: Color = Color::rgb(128, 128, 128);
+const FONT_SIZE: f64 = 10.0;
+/// the space around the plot area for the ticks and labels
+const MARGIN_LEFT: f64 = 40.0;
+const MARGIN_RIGHT: f64 = 10.0;
+const MARGIN_TOP: f64 = 20.0;
+const MARGIN_BOTTOM: f64 = 30.0;
+const POINT_RADIUS: f64 = 3.0;
+
+/// How the series of a chart are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ChartKind {
+    /// the points of each series are connected with lines
+    Line,
+    /// each point is a bar from zero, the bars of the same index are grouped together
+    Bar,
+    /// each point is a dot
+    Scatter,
+}
+
+/// A named list of (x, y) data points
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Series {
+    /// the name displayed in the legend
+    pub name: String,
+    /// the data points
+    pub points: Vec<(f64, f64)>,
+    /// the color of the series, a color of the palette is used if not specified
+    pub color: Option<Color>,
+}
+
+impl Series {
+    /// create a series of the data points
+    pub fn new(name: impl Into<String>, points: Vec<(f64, f64)>) -> Self {
+        Series {
+            name: name.into(),
+            points,
+            color: None,
+        }
+    }
+
+    /// set the color of the series
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// The range, ticks and label of an axis
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Axis {
+    /// the label displayed along the axis
+    pub label: Option<String>,
+    /// the start of the axis, the smallest value of the data if not specified
+    pub min: Option<f64>,
+    /// the end of the axis, the largest value of the data if not specified
+    pub max: Option<f64>,
+    /// the number of ticks along the axis
+    pub ticks: usize,
+}
+
+impl Default for Axis {
+    fn default() -> Self {
+        Axis {
+            label: None,
+            min: None,
+            max: None,
+            ticks: 5,
+        }
+    }
+}
+
+impl Axis {
+    /// set the label of the axis
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// set the start and end of the axis
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// set the number of ticks along the axis
+    pub fn ticks(mut self, ticks: usize) -> Self {
+        self.ticks = ticks;
+        self
+    }
+}
+
+/// The data point nearest to the pointer, emitted in the chart hover event
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChartPoint {
+    /// the index of the series
+    pub series: usize,
+    /// the index of the point in the series
+    pub index: usize,
+    /// the x value of the point
+    pub x: f64,
+    /// the y value of the point
+    pub y: f64,
+}
+
+/// The series and axes of a chart
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Chart {
+    /// how the series are drawn
+    pub kind: ChartKind,
+    /// the data of the chart
+    pub series: Vec<Series>,
+    /// the horizontal axis
+    pub x_axis: Axis,
+    /// the vertical axis
+    pub y_axis: Axis,
+}
+
+/// maps the data values into the plot area of the canvas
+struct Plot {
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+}
+
+impl Plot {
+    fn x(&self, x: f64) -> f64 {
+        let (min, max) = self.x_range;
+        self.left + (x - min) / (max - min) * self.width
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        let (min, max) = self.y_range;
+        self.top + self.height - (y - min) / (max - min) * self.height
+    }
+
+    fn bottom(&self) -> f64 {
+        self.top + self.height
+    }
+}
+
+impl Chart {
+    /// the drawing commands of the chart in a canvas of this size
+    pub fn draw(&self, width: f32, height: f32) -> Vec<DrawCommand> {
+        let plot = self.plot(width, height);
+        let mut commands = vec![];
+        self.draw_axes(&plot, &mut commands);
+        for (index, series) in self.series.iter().enumerate() {
+            let color = self.series_color(index);
+            match self.kind {
+                ChartKind::Line => {
+                    commands.push(DrawCommand::BeginPath);
+                    for (i, (x, y)) in series.points.iter().enumerate() {
+                        let point = (plot.x(*x), plot.y(*y));
+                        commands.push(if i == 0 {
+                            DrawCommand::MoveTo(point.0, point.1)
+                        } else {
+                            DrawCommand::LineTo(point.0, point.1)
+                        });
+                    }
+                    commands.push(DrawCommand::StrokeStyle(color));
+                    commands.push(DrawCommand::LineWidth(2.0));
+                    commands.push(DrawCommand::Stroke);
+                }
+                ChartKind::Scatter => {
+                    commands.push(DrawCommand::FillStyle(color));
+                    for (x, y) in series.points.iter() {
+                        commands.push(DrawCommand::BeginPath);
+                        commands.push(DrawCommand::Arc {
+                            x: plot.x(*x),
+                            y: plot.y(*y),
+                            radius: POINT_RADIUS,
+                            start_angle: 0.0,
+                            end_angle: 2.0 * PI,
+                        });
+                        commands.push(DrawCommand::Fill);
+                    }
+                }
+                ChartKind::Bar => {
+                    commands.push(DrawCommand::FillStyle(color));
+                    for (i, (x, y)) in series.points.iter().enumerate() {
+                        let (left, right) = self.bar_span(&plot, index, i);
+                        let (top, bottom) = (plot.y(*y), plot.y(0.0));
+                        commands.push(DrawCommand::BeginPath);
+                        commands.push(DrawCommand::Rect {
+                            x: left,
+                            y: top.min(bottom),
+                            width: right - left,
+                            height: (bottom - top).abs(),
+                        });
+                        commands.push(DrawCommand::Fill);
+                        // the bars are labeled with the x value of the first series
+                        if index == 0 {
+                            commands.push(DrawCommand::FillStyle(AXIS_COLOR));
+                            commands.push(text(
+                                left,
+                                plot.bottom() + FONT_SIZE + 2.0,
+                                format_value(*x),
+                            ));
+                            commands.push(DrawCommand::FillStyle(color));
+                        }
+                    }
+                }
+            }
+        }
+        self.draw_legend(width as f64, &mut commands);
+        commands
+    }
+
+    /// the data point nearest to the position in a canvas of this size,
+    /// for a bar chart it is the bar under the position
+    pub fn nearest(
+        &self,
+        (x, y): (f64, f64),
+        (width, height): (f32, f32),
+    ) -> Option<ChartPoint> {
+        let plot = self.plot(width, height);
+        let mut nearest: Option<(f64, ChartPoint)> = None;
+        for (index, series) in self.series.iter().enumerate() {
+            for (i, (px, py)) in series.points.iter().enumerate() {
+                let distance = match self.kind {
+                    ChartKind::Bar => {
+                        let (left, right) = self.bar_span(&plot, index, i);
+                        if x >= left && x <= right {
+                            0.0
+                        } else {
+                            (x - (left + right) / 2.0).abs()
+                        }
+                    }
+                    ChartKind::Line | ChartKind::Scatter => {
+                        (x - plot.x(*px)).hypot(y - plot.y(*py))
+                    }
+                };
+                if nearest.map_or(true, |(d, _)| distance < d) {
+                    let point = ChartPoint {
+                        series: index,
+                        index: i,
+                        x: *px,
+                        y: *py,
+                    };
+                    nearest = Some((distance, point));
+                }
+            }
+        }
+        nearest.map(|(_, point)| point)
+    }
+
+    fn plot(&self, width: f32, height: f32) -> Plot {
+        let points = || self.series.iter().flat_map(|s| s.points.iter());
+        let x_values = points().map(|(x, _)| *x);
+        let y_values = points().map(|(_, y)| *y);
+        let mut y_range = range(&self.y_axis, y_values);
+        // the bars start from zero
+        if self.kind == ChartKind::Bar {
+            y_range = (
+                self.y_axis.min.unwrap_or_else(|| y_range.0.min(0.0)),
+                self.y_axis.max.unwrap_or_else(|| y_range.1.max(0.0)),
+            );
+        }
+        Plot {
+            left: MARGIN_LEFT,
+            top: MARGIN_TOP,
+            width: (width as f64 - MARGIN_LEFT - MARGIN_RIGHT).max(1.0),
+            height: (height as f64 - MARGIN_TOP - MARGIN_BOTTOM).max(1.0),
+            x_range: range(&self.x_axis, x_values),
+            y_range: non_empty(y_range),
+        }
+    }
+
+    /// the left and right of the bar of a point, the bars of the same
+    /// index are placed side by side in a group
+    fn bar_span(&self, plot: &Plot, series: usize, index: usize) -> (f64, f64) {
+        let groups = self
+            .series
+            .iter()
+            .map(|s| s.points.len())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let group_width = plot.width / groups as f64;
+        let bar_width = group_width * 0.8 / self.series.len().max(1) as f64;
+        let left = plot.left
+            + group_width * index as f64
+            + group_width * 0.1
+            + bar_width * series as f64;
+        (left, left + bar_width)
+    }
+
+    fn series_color(&self, index: usize) -> Color {
+        self.series[index]
+            .color
+            .unwrap_or(PALETTE[index % PALETTE.len()])
+    }
+
+    fn draw_axes(&self, plot: &Plot, commands: &mut Vec<DrawCommand>) {
+        commands.push(DrawCommand::BeginPath);
+        commands.push(DrawCommand::MoveTo(plot.left, plot.top));
+        commands.push(DrawCommand::LineTo(plot.left, plot.bottom()));
+        commands
+            .push(DrawCommand::LineTo(plot.left + plot.width, plot.bottom()));
+        commands.push(DrawCommand::StrokeStyle(AXIS_COLOR));
+        commands.push(DrawCommand::LineWidth(1.0));
+        commands.push(DrawCommand::Stroke);
+        commands.push(DrawCommand::FillStyle(AXIS_COLOR));
+
+        for value in ticks(plot.y_range, self.y_axis.ticks) {
+            let y = plot.y(value);
+            commands.push(text(2.0, y + FONT_SIZE / 2.0, format_value(value)));
+        }
+        // the bars are labeled with their x values instead
+        if self.kind != ChartKind::Bar {
+            for value in ticks(plot.x_range, self.x_axis.ticks) {
+                let x = plot.x(value);
+                commands.push(text(
+                    x - FONT_SIZE,
+                    plot.bottom() + FONT_SIZE + 2.0,
+                    format_value(value),
+                ));
+            }
+        }
+        if let Some(label) = &self.x_axis.label {
+            commands.push(text(
+                plot.left + plot.width / 2.0,
+                plot.bottom() + FONT_SIZE * 2.0 + 6.0,
+                label.clone(),
+            ));
+        }
+        if let Some(label) = &self.y_axis.label {
+            commands.push(text(2.0, FONT_SIZE + 2.0, label.clone()));
+        }
+    }
+
+    fn draw_legend(&self, width: f64, commands: &mut Vec<DrawCommand>) {
+        let mut right = width - MARGIN_RIGHT;
+        for (index, series) in self.series.iter().enumerate().rev() {
+            // an approximate width of the text, since it is not measured
+            right -= series.name.chars().count() as f64 * FONT_SIZE * 0.6;
+            commands.push(DrawCommand::FillStyle(self.series_color(index)));
+            commands.push(text(right, FONT_SIZE + 2.0, series.name.clone()));
+            right -= FONT_SIZE;
+        }
+    }
+}
+
+fn text(x: f64, y: f64, text: String) -> DrawCommand {
+    DrawCommand::Text {
+        x,
+        y,
+        text,
+        size: FONT_SIZE,
+    }
+}
+
+/// the range of the axis, from the data values if it is not specified
+fn range(axis: &Axis, values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |r, v| {
+        (r.0.min(v), r.1.max(v))
+    });
+    let (min, max) = if min > max { (0.0, 1.0) } else { (min, max) };
+    non_empty((axis.min.unwrap_or(min), axis.max.unwrap_or(max)))
+}
+
+/// widen the range when it is only a single value
+fn non_empty((min, max): (f64, f64)) -> (f64, f64) {
+    if max > min {
+        (min, max)
+    } else {
+        (min - 1.0, max + 1.0)
+    }
+}
+
+/// the values of the ticks, evenly spaced from the start to the end of the range
+fn ticks((min, max): (f64, f64), count: usize) -> Vec<f64> {
+    match count {
+        0 => vec![],
+        1 => vec![min],
+        _ => (0..count)
+            .map(|i| min + (max - min) * i as f64 / (count - 1) as f64)
+            .collect(),
+    }
+}
+
+/// format the value with at most 2 decimal places
+fn format_value(value: f64) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    format!("{}", rounded)
+}
@@ -1,13 +1,18 @@
 //! provides struct and methods for constructing sauron event
 //!
+use crate::widget::{attribute::DropEffect, chart::ChartPoint};
 use crate::AttribKey;
 use crate::Attribute;
 use crate::Value;
 use mt_dom::Callback;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::PathBuf};
 
 /// A container for generic event and the common values
 /// needed for the user.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Event {
     /// It is a mouse event
     MouseEvent(MouseEvent),
@@ -17,6 +22,14 @@ pub enum Event {
     InputEvent(InputEvent),
     /// resize event
     Resize(f32, f32),
+    /// the animation of an image has finished playing
+    AnimationEnd,
+    /// the pointer is over a chart, with the data point nearest to it
+    ChartHover(ChartPoint),
+    /// something is dropped into a drop target
+    Drop(DropEvent),
+    /// the drag of a drag source has finished, with the operation done by the drop target
+    DragEnd(DropEffect),
 }
 
 impl From<MouseEvent> for Event {
@@ -37,11 +50,96 @@ impl From<KeyEvent> for Event {
     }
 }
 
+/// What is dropped into a drop target
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DragPayload {
+    /// the drag_data of a widget of the app
+    Value(Value),
+    /// text dragged from another application
+    Text(String),
+    /// files dragged from the desktop or a file manager
+    Files(Vec<DroppedFile>),
+}
+
+/// A file dropped from outside of the app
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DroppedFile {
+    /// the name of the file
+    pub name: String,
+    /// the path of the file, which is not exposed by the browsers
+    pub path: Option<PathBuf>,
+    /// the content of the file, which the browsers give in place of the path
+    pub data: Option<Vec<u8>>,
+}
+
+impl DroppedFile {
+    /// the content of the file, or else read from its path
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        if let Some(data) = &self.data {
+            return Ok(data.clone());
+        }
+        match &self.path {
+            Some(path) => fs::read(path),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} has no path", self.name),
+            )),
+        }
+    }
+}
+
+/// The payload dropped into a drop target, at a position relative to the drop target
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DropEvent {
+    /// what is dropped
+    pub payload: DragPayload,
+    /// the x position of the drop
+    pub x: i32,
+    /// the y position of the drop
+    pub y: i32,
+    /// the operation of the drop
+    pub effect: DropEffect,
+}
+
+/// The names of the mouse event types, the empty name is of the default mouse event
+#[cfg(feature = "serde")]
+const MOUSE_EVENT_TYPES: &[&str] =
+    &["", "click", "dblclick", "mousedown", "mouseup", "mousemove"];
+
+/// deserialize the mouse event type into its static name,
+/// since the name can not borrow from the deserializer
+#[cfg(feature = "serde")]
+fn mouse_event_type<'de, D>(deserializer: D) -> Result<EventType, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    MOUSE_EVENT_TYPES
+        .iter()
+        .find(|event_type| **event_type == name)
+        .copied()
+        .ok_or_else(|| {
+            serde::de::Error::unknown_variant(&name, MOUSE_EVENT_TYPES)
+        })
+}
+
+/// The name of the mouse event type, which is static so the mouse event is Copy.
+/// It is an alias so serde does not borrow it from the deserializer
+pub type EventType = &'static str;
+
 /// A mouse event contains the (x,y) coordinates, buttons and modifier keys
 #[derive(Default, Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MouseEvent {
     /// the mouse event type
-    pub r#type: &'static str,
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "mouse_event_type")
+    )]
+    pub r#type: EventType,
     /// the location of the mouse event
     pub coordinate: Coordinate,
     /// which modifier keys are pressed
@@ -103,6 +201,7 @@ impl MouseEvent {
 
 /// Keypresses creates a key event
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct KeyEvent {
     /// the key pressed
     pub key_code: KeyCode,
@@ -112,6 +211,7 @@ pub struct KeyEvent {
 
 /// The keycode of the character pressed
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum KeyCode {
     /// backspace
     Backspace,
@@ -153,6 +253,7 @@ pub enum KeyCode {
 
 /// Input event is triggered by controls such as text_area and text_input
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct InputEvent {
     /// the input value
     pub value: Value,
@@ -175,6 +276,7 @@ impl From<Value> for InputEvent {
 
 /// Which mouse button is used
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MouseButton {
     /// left mouse button
     Left,
@@ -196,6 +298,7 @@ impl Default for MouseButton {
 
 /// The coordinate of the event
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Coordinate {
     /// x component of the client
     /// https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent/clientX
@@ -245,6 +348,7 @@ impl Coordinate {
 /// Modifier contains the information of which modifier keys are pressed when the event is
 /// triggered
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Modifier {
     /// whether the alt key is pressed
     pub alt_key: bool,
@@ -378,3 +482,55 @@ where
         _ => unreachable!(),
     })
 }
+
+/// create an attribute which attach a callback to the end of the animation
+/// of an animated image, which is not emitted while it is looping
+pub fn on_animation_end<F, MSG>(func: F) -> Attribute<MSG>
+where
+    F: Fn() -> MSG + 'static,
+{
+    on(AttribKey::AnimationEndEvent, move |ev: Event| match ev {
+        Event::AnimationEnd => func(),
+        _ => unreachable!(),
+    })
+}
+
+/// create an attribute which attach a callback to the pointer moving over a chart,
+/// with the data point nearest to the pointer.
+/// The hover is emitted in gtk and html, the mouse events of titik have no coordinates
+/// and the nwg widgets don't emit their events yet, so the charts have no hover there
+pub fn on_chart_hover<F, MSG>(func: F) -> Attribute<MSG>
+where
+    F: Fn(ChartPoint) -> MSG + 'static,
+{
+    on(AttribKey::ChartHoverEvent, move |ev: Event| match ev {
+        Event::ChartHover(point) => func(point),
+        _ => unreachable!(),
+    })
+}
+
+/// create an attribute which attach a callback to the drop of a payload into the widget,
+/// the widget becomes a drop target.
+/// The drops are supported in gtk and html, the nwg widgets don't emit their events yet
+/// and titik has no drag and drop, so nothing is dropped in these backends
+pub fn on_drop<F, MSG>(func: F) -> Attribute<MSG>
+where
+    F: Fn(DropEvent) -> MSG + 'static,
+{
+    on(AttribKey::DropEvent, move |ev: Event| match ev {
+        Event::Drop(drop_event) => func(drop_event),
+        _ => unreachable!(),
+    })
+}
+
+/// create an attribute which attach a callback to the end of dragging the widget,
+/// with the operation done by the drop target, or `DropEffect::None` when it is cancelled
+pub fn on_drag_end<F, MSG>(func: F) -> Attribute<MSG>
+where
+    F: Fn(DropEffect) -> MSG + 'static,
+{
+    on(AttribKey::DragEndEvent, move |ev: Event| match ev {
+        Event::DragEnd(effect) => func(effect),
+        _ => unreachable!(),
+    })
+}
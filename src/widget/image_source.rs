@@ -0,0 +1,142 @@
+//! Where the data of an image is loaded from
+//!
+//! An `ImageSource` is cheap to clone and compare, so the image data is not copied
+//! into every view, nor compared byte by byte in the diff.
+//! The backends also keep the decoded images keyed by their source,
+//! so an image is only decoded the first time it is displayed.
+//!
+//! ```rust,ignore
+//! ImageSource::register("spinner", &include_bytes!("../assets/spinner.gif")[..]);
+//!
+//! image(vec![source(ImageSource::resource("spinner"))])
+//! image(vec![source(ImageSource::path("assets/photo.jpg"))])
+//! image(vec![source(self.thumbnail.clone())])
+//! ```
+use crate::Error;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell, collections::HashMap, fmt, fs, path::PathBuf, sync::Arc,
+};
+
+thread_local! {
+    static RESOURCES: RefCell<HashMap<String, Arc<[u8]>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// The source of the data of an image
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ImageSource {
+    /// a file, which is read when the image is displayed
+    Path(PathBuf),
+    /// the image data registered with `ImageSource::register`
+    Resource(String),
+    /// image data shared by its owner, it is compared by its pointer
+    Shared(#[cfg_attr(feature = "serde", serde(with = "shared"))] Arc<[u8]>),
+}
+
+impl ImageSource {
+    /// an image from a file
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        ImageSource::Path(path.into())
+    }
+
+    /// an image registered with this id
+    pub fn resource(id: impl Into<String>) -> Self {
+        ImageSource::Resource(id.into())
+    }
+
+    /// register the image data with the id, such as an image embedded with `include_bytes!`,
+    /// the resources are registered in the same thread as the app is run
+    pub fn register(id: impl Into<String>, bytes: impl Into<Arc<[u8]>>) {
+        RESOURCES.with(|resources| {
+            resources.borrow_mut().insert(id.into(), bytes.into())
+        });
+    }
+
+    /// read the image data from the source
+    pub fn load(&self) -> Result<Arc<[u8]>, Error> {
+        match self {
+            ImageSource::Path(path) => {
+                fs::read(path).map(Arc::from).map_err(|e| {
+                    Error::MissingImage(format!("{}: {}", path.display(), e))
+                })
+            }
+            ImageSource::Resource(id) => RESOURCES
+                .with(|resources| resources.borrow().get(id).cloned())
+                .ok_or_else(|| {
+                    Error::MissingImage(format!("no resource named {:?}", id))
+                }),
+            ImageSource::Shared(bytes) => Ok(Arc::clone(bytes)),
+        }
+    }
+}
+
+/// remove all the decoded images from the cache of the backends,
+/// such as when the image files are changed
+pub fn clear_image_cache() {
+    crate::image_util::clear_image_cache();
+}
+
+impl From<Arc<[u8]>> for ImageSource {
+    fn from(bytes: Arc<[u8]>) -> Self {
+        ImageSource::Shared(bytes)
+    }
+}
+
+impl From<Vec<u8>> for ImageSource {
+    fn from(bytes: Vec<u8>) -> Self {
+        ImageSource::Shared(bytes.into())
+    }
+}
+
+impl PartialEq for ImageSource {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ImageSource::Path(a), ImageSource::Path(b)) => a == b,
+            (ImageSource::Resource(a), ImageSource::Resource(b)) => a == b,
+            (ImageSource::Shared(a), ImageSource::Shared(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ImageSource {}
+
+impl fmt::Debug for ImageSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImageSource::Path(path) => write!(f, "Path({:?})", path),
+            ImageSource::Resource(id) => write!(f, "Resource({:?})", id),
+            ImageSource::Shared(bytes) => {
+                write!(f, "Shared(<{} bytes>)", bytes.len())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod shared {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::sync::Arc;
+
+    pub fn serialize<S>(
+        bytes: &Arc<[u8]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(bytes)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Arc<[u8]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<u8>::deserialize(deserializer).map(Arc::from)
+    }
+}
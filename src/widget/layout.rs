@@ -1,30 +1,384 @@
-use crate::widget::attribute::util::get_style;
+//! compute the layout of the widget tree using stretch
+//!
+use crate::widget::attribute::util::{get_grid_cell, get_style};
 use crate::widget::attribute::AttribKey;
-use crate::widget::attribute::Value;
+use crate::widget::attribute::{find_value, Value};
+use crate::{Attribute, Node, Widget};
 use mt_dom::attr;
-use stretch::geometry::Size;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use stretch::geometry::{Rect, Size};
 use stretch::number::Number;
+use stretch::style::{Dimension, PositionType, Style};
 use stretch::Stretch;
 
+/// the maximum number of times the layout is recomputed to settle the placement
+/// of the children in nested grids
+const MAX_GRID_PASSES: usize = 8;
+
+/// The size of a row or a column in a grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GridTrack {
+    /// a fixed size in points
+    Points(f32),
+    /// a percentage of the grid size, 1.0 is 100%
+    Percent(f32),
+    /// a fraction of the space left by the fixed and percentage tracks
+    Fraction(f32),
+}
+
+/// The placement of a widget inside a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GridCell {
+    /// the row index, starts at 0
+    pub row: usize,
+    /// the column index, starts at 0
+    pub column: usize,
+    /// the number of rows this cell spans
+    pub row_span: usize,
+    /// the number of columns this cell spans
+    pub column_span: usize,
+}
+
+impl Default for GridCell {
+    fn default() -> Self {
+        GridCell {
+            row: 0,
+            column: 0,
+            row_span: 1,
+            column_span: 1,
+        }
+    }
+}
+
 /// calculate the layout of the nodes utilizing the styles set on each of the widget
 /// and its children widget styles
-#[allow(unused)]
-pub(crate) fn compute_node_layout<MSG>(
-    widget_node: &mut crate::Node<MSG>,
+///
+/// This builds a throw away layout tree, use `LayoutTree` when the view is recomputed
+/// often, such as on every dispatch.
+pub fn compute_node_layout<MSG: 'static>(
+    widget_node: &mut Node<MSG>,
     parent_size: Size<Number>,
 ) {
-    let mut stretch = Stretch::new();
-    let stretch_node = build_stretch_node_recursive(&mut stretch, &widget_node)
-        .expect("must have built a style node");
-    stretch
-        .compute_layout(stretch_node, parent_size)
-        .expect("must compute the layout");
-    set_node_layout_from_stretch_node(widget_node, stretch_node, &stretch)
+    let mut layout_tree = LayoutTree::new();
+    layout_tree.rebuild(widget_node);
+    layout_tree.compute(widget_node, parent_size);
+}
+
+/// A persistent stretch tree which mirrors the widget node tree.
+///
+/// Instead of rebuilding every stretch node on each view, the layout tree is
+/// synced with the styles and the children of the new view, so stretch only
+/// recomputes the subtrees that are marked dirty.
+pub struct LayoutTree {
+    stretch: Stretch,
+    root: Option<stretch::node::Node>,
+}
+
+impl Default for LayoutTree {
+    fn default() -> Self {
+        LayoutTree::new()
+    }
+}
+
+impl LayoutTree {
+    /// create an empty layout tree
+    pub fn new() -> Self {
+        LayoutTree {
+            stretch: Stretch::new(),
+            root: None,
+        }
+    }
+
+    /// update the layout tree to match `new_node`, then set the calculated layout
+    /// to each of the widgets in `new_node`
+    ///
+    /// The new view is not diffed here, the backends diff it once the layout is set,
+    /// only the styles and the number of children are compared with the stretch nodes.
+    pub fn update<MSG: 'static>(
+        &mut self,
+        new_node: &mut Node<MSG>,
+        parent_size: Size<Number>,
+    ) {
+        let is_synced = match self.root {
+            Some(root) => self.sync(root, new_node, false),
+            None => false,
+        };
+        if !is_synced {
+            self.rebuild(new_node);
+        }
+        self.compute(new_node, parent_size);
+    }
+
+    /// discard the existing stretch nodes and build them again from this widget tree
+    pub fn rebuild<MSG>(&mut self, widget_node: &Node<MSG>) {
+        self.stretch.clear();
+        self.root = Some(
+            build_stretch_node_recursive(&mut self.stretch, widget_node)
+                .expect("must have built a style node"),
+        );
+    }
+
+    /// compute the layout of the dirty nodes and set the calculated layout
+    /// into each of the widget in this widget tree
+    pub fn compute<MSG: 'static>(
+        &mut self,
+        widget_node: &mut Node<MSG>,
+        parent_size: Size<Number>,
+    ) {
+        let root = match self.root {
+            Some(root) if is_in_sync(widget_node, root, &self.stretch) => root,
+            _ => {
+                log::warn!("layout tree is out of sync, rebuilding..");
+                self.rebuild(widget_node);
+                self.root.expect("must have a root node")
+            }
+        };
+        // the grid children can only be placed once the size of the grid is known,
+        // the layout is then computed again with the children placed in their cells
+        for _ in 0..MAX_GRID_PASSES {
+            self.stretch
+                .compute_layout(root, parent_size)
+                .expect("must compute the layout");
+            if !place_grid_children(&mut self.stretch, widget_node, root) {
+                break;
+            }
+        }
+        set_node_layout_from_stretch_node(widget_node, root, &self.stretch)
+    }
+
+    /// set the changed styles into the stretch nodes and build the children again
+    /// where they are added or removed, this marks the affected nodes as dirty.
+    ///
+    /// The style of the grid children is set when they are placed in their cells,
+    /// so it is not compared here.
+    ///
+    /// Returns false when the stretch nodes can not be updated, in which case the layout tree
+    /// needs to be rebuilt.
+    fn sync<MSG>(
+        &mut self,
+        stretch_node: stretch::node::Node,
+        widget_node: &Node<MSG>,
+        in_grid: bool,
+    ) -> bool {
+        if !in_grid {
+            let node_style =
+                get_style(widget_node).cloned().unwrap_or_default();
+            let is_same = self
+                .stretch
+                .style(stretch_node)
+                .map(|style| *style == node_style)
+                .unwrap_or(false);
+            if !is_same
+                && self.stretch.set_style(stretch_node, node_style).is_err()
+            {
+                return false;
+            }
+        }
+        let stretch_children = match self.stretch.children(stretch_node) {
+            Ok(stretch_children) => stretch_children,
+            Err(_) => return false,
+        };
+        let widget_children = widget_node.get_children().unwrap_or(&[]);
+        if stretch_children.len() != widget_children.len() {
+            return self.rebuild_children(
+                stretch_node,
+                stretch_children,
+                widget_children,
+            );
+        }
+        let is_grid = widget_node.tag() == Some(&Widget::Grid);
+        stretch_children
+            .into_iter()
+            .zip(widget_children.iter())
+            .all(|(stretch_child, widget_child)| {
+                self.sync(stretch_child, widget_child, is_grid)
+            })
+    }
+
+    /// replace the children of the stretch node with the stretch nodes built from `widget_children`
+    fn rebuild_children<MSG>(
+        &mut self,
+        stretch_node: stretch::node::Node,
+        stretch_children: Vec<stretch::node::Node>,
+        widget_children: &[Node<MSG>],
+    ) -> bool {
+        let new_children: Option<Vec<stretch::node::Node>> = widget_children
+            .iter()
+            .map(|child| build_stretch_node_recursive(&mut self.stretch, child))
+            .collect();
+        let new_children = match new_children {
+            Some(new_children) => new_children,
+            None => return false,
+        };
+        if self
+            .stretch
+            .set_children(stretch_node, new_children)
+            .is_err()
+        {
+            return false;
+        }
+        for removed in stretch_children {
+            remove_stretch_node_recursive(&mut self.stretch, removed);
+        }
+        true
+    }
+}
+
+/// calculate the offset and size of each of the tracks which fills the `available` space
+pub fn compute_grid_tracks(
+    tracks: &[GridTrack],
+    available: f32,
+) -> Vec<(f32, f32)> {
+    let fixed: f32 = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Points(points) => *points,
+            GridTrack::Percent(percent) => percent * available,
+            GridTrack::Fraction(_) => 0.0,
+        })
+        .sum();
+    let fractions: f32 = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Fraction(fraction) => *fraction,
+            _ => 0.0,
+        })
+        .sum();
+    let remaining = (available - fixed).max(0.0);
+
+    let mut offset = 0.0;
+    tracks
+        .iter()
+        .map(|track| {
+            let size = match track {
+                GridTrack::Points(points) => *points,
+                GridTrack::Percent(percent) => percent * available,
+                GridTrack::Fraction(fraction) if fractions > 0.0 => {
+                    remaining * fraction / fractions
+                }
+                GridTrack::Fraction(_) => 0.0,
+            };
+            let track_offset = offset;
+            offset += size;
+            (track_offset, size)
+        })
+        .collect()
+}
+
+/// set the position and size of the children of the grid widgets into their stretch nodes.
+///
+/// Returns true if any of the stretch node style is changed and the layout needs to be
+/// computed again.
+fn place_grid_children<MSG: 'static>(
+    stretch: &mut Stretch,
+    widget_node: &Node<MSG>,
+    stretch_node: stretch::node::Node,
+) -> bool {
+    let stretch_children = stretch.children(stretch_node).unwrap_or_default();
+    let widget_children = widget_node.get_children().unwrap_or(&[]);
+    let mut is_changed = false;
+
+    if let Some(Widget::Grid) = widget_node.tag() {
+        let grid_size =
+            stretch.layout(stretch_node).expect("must have layout").size;
+        let attrs = widget_node.get_attributes().unwrap_or(&[]);
+        let cells: Vec<GridCell> = widget_children
+            .iter()
+            .map(|child| get_grid_cell(child.get_attributes().unwrap_or(&[])))
+            .collect();
+
+        let row_count =
+            cells.iter().map(|c| c.row + c.row_span).max().unwrap_or(0);
+        let column_count = cells
+            .iter()
+            .map(|c| c.column + c.column_span)
+            .max()
+            .unwrap_or(0);
+        let rows = compute_grid_tracks(
+            &grid_template(AttribKey::GridRows, attrs, row_count),
+            grid_size.height,
+        );
+        let columns = compute_grid_tracks(
+            &grid_template(AttribKey::GridColumns, attrs, column_count),
+            grid_size.width,
+        );
+
+        for ((stretch_child, widget_child), cell) in stretch_children
+            .iter()
+            .zip(widget_children.iter())
+            .zip(cells.iter())
+        {
+            let (top, height) = span_tracks(&rows, cell.row, cell.row_span);
+            let (start, width) =
+                span_tracks(&columns, cell.column, cell.column_span);
+            let child_style = Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    start: Dimension::Points(start),
+                    top: Dimension::Points(top),
+                    ..Default::default()
+                },
+                size: Size {
+                    width: Dimension::Points(width),
+                    height: Dimension::Points(height),
+                },
+                ..get_style(widget_child).cloned().unwrap_or_default()
+            };
+            let is_same = stretch
+                .style(*stretch_child)
+                .map(|style| *style == child_style)
+                .unwrap_or(false);
+            if !is_same {
+                stretch
+                    .set_style(*stretch_child, child_style)
+                    .expect("must set the style");
+                is_changed = true;
+            }
+        }
+    }
+
+    for (stretch_child, widget_child) in
+        stretch_children.into_iter().zip(widget_children.iter())
+    {
+        if place_grid_children(stretch, widget_child, stretch_child) {
+            is_changed = true;
+        }
+    }
+    is_changed
+}
+
+/// the grid tracks specified in the attribute, the missing tracks each takes
+/// an equal fraction of the remaining space
+fn grid_template<MSG: 'static>(
+    key: AttribKey,
+    attrs: &[Attribute<MSG>],
+    count: usize,
+) -> Vec<GridTrack> {
+    let mut tracks = find_value(key, attrs)
+        .map(|v| v.as_grid_tracks())
+        .flatten()
+        .map(|tracks| tracks.to_vec())
+        .unwrap_or_default();
+    if tracks.len() < count {
+        tracks.resize(count, GridTrack::Fraction(1.0));
+    }
+    tracks
+}
+
+/// the offset and the total size of the tracks starting at `index` spanning `span` tracks
+fn span_tracks(tracks: &[(f32, f32)], index: usize, span: usize) -> (f32, f32) {
+    let spanned = tracks.iter().skip(index).take(span);
+    let offset = tracks.get(index).map(|(offset, _)| *offset).unwrap_or(0.0);
+    let size = spanned.map(|(_, size)| size).sum();
+    (offset, size)
 }
 
 fn build_stretch_node_recursive<MSG>(
     stretch: &mut Stretch,
-    widget_node: &crate::Node<MSG>,
+    widget_node: &Node<MSG>,
 ) -> Option<stretch::node::Node> {
     let children_styles = if let Some(children) = widget_node.get_children() {
         children
@@ -38,8 +392,38 @@ fn build_stretch_node_recursive<MSG>(
     stretch.new_node(node_style, &children_styles).ok()
 }
 
+/// remove the stretch node and all of its descendants
+fn remove_stretch_node_recursive(
+    stretch: &mut Stretch,
+    stretch_node: stretch::node::Node,
+) {
+    if let Ok(children) = stretch.children(stretch_node) {
+        for child in children {
+            remove_stretch_node_recursive(stretch, child);
+        }
+    }
+    stretch.remove(stretch_node);
+}
+
+/// whether the stretch tree has the same shape as the widget tree
+fn is_in_sync<MSG>(
+    widget_node: &Node<MSG>,
+    stretch_node: stretch::node::Node,
+    stretch: &Stretch,
+) -> bool {
+    let stretch_children = stretch.children(stretch_node).unwrap_or_default();
+    let widget_children = widget_node.get_children().unwrap_or(&[]);
+    stretch_children.len() == widget_children.len()
+        && stretch_children
+            .into_iter()
+            .zip(widget_children.iter())
+            .all(|(stretch_child, widget_child)| {
+                is_in_sync(widget_child, stretch_child, stretch)
+            })
+}
+
 fn set_node_layout_from_stretch_node<MSG>(
-    widget_node: &mut crate::Node<MSG>,
+    widget_node: &mut Node<MSG>,
     stretch_node: stretch::node::Node,
     stretch: &Stretch,
 ) {
@@ -65,3 +449,257 @@ fn set_node_layout_from_stretch_node<MSG>(
         Value::from(layout),
     )]);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::attribute::{
+        column_span, grid_column, grid_columns, grid_row, grid_rows, row_span,
+        style, util::get_layout,
+    };
+    use crate::widget::widget;
+    use crate::Attribute;
+    use stretch::style::FlexDirection;
+
+    const WINDOW: Size<Number> = Size {
+        width: Number::Defined(800.0),
+        height: Number::Defined(600.0),
+    };
+
+    fn sized(width: f32, height: f32) -> Style {
+        Style {
+            size: Size {
+                width: Dimension::Points(width),
+                height: Dimension::Points(height),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// a column of buttons with these heights
+    fn view(heights: &[f32]) -> Node<()> {
+        widget(
+            Widget::Vbox,
+            vec![style(Style {
+                flex_direction: FlexDirection::Column,
+                ..sized(100.0, 300.0)
+            })],
+            heights
+                .iter()
+                .map(|height| {
+                    widget(
+                        Widget::Button,
+                        vec![style(sized(100.0, *height))],
+                        vec![],
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// the location and size of each widget, in depth first order
+    fn rects(node: &Node<()>) -> Vec<(f32, f32, f32, f32)> {
+        let mut rects = vec![];
+        if let Some(layout) = node.as_element_ref().and_then(get_layout) {
+            rects.push((
+                layout.location.x,
+                layout.location.y,
+                layout.size.width,
+                layout.size.height,
+            ));
+        }
+        for child in node.get_children().unwrap_or(&[]) {
+            rects.extend(rects(child));
+        }
+        rects
+    }
+
+    fn computed(heights: &[f32]) -> Vec<(f32, f32, f32, f32)> {
+        let mut node = view(heights);
+        compute_node_layout(&mut node, WINDOW);
+        rects(&node)
+    }
+
+    fn layout_tree(node: &mut Node<()>) -> LayoutTree {
+        let mut layout_tree = LayoutTree::new();
+        layout_tree.rebuild(node);
+        layout_tree.compute(node, WINDOW);
+        layout_tree
+    }
+
+    fn is_tree_in_sync(layout_tree: &LayoutTree, node: &Node<()>) -> bool {
+        let root = layout_tree.root.expect("must have a root node");
+        is_in_sync(node, root, &layout_tree.stretch)
+    }
+
+    #[test]
+    fn update_syncs_the_changed_styles_and_children() {
+        let mut old_view = view(&[10.0, 20.0]);
+        let mut layout_tree = layout_tree(&mut old_view);
+
+        let views: Vec<Vec<f32>> = vec![
+            vec![10.0, 50.0],
+            vec![10.0, 50.0, 60.0],
+            vec![70.0],
+            vec![],
+            vec![15.0, 25.0],
+        ];
+        for heights in &views {
+            let mut new_view = view(heights);
+            layout_tree.update(&mut new_view, WINDOW);
+            assert!(is_tree_in_sync(&layout_tree, &new_view));
+            assert_eq!(rects(&new_view), computed(heights));
+        }
+    }
+
+    #[test]
+    fn in_sync_when_the_children_match() {
+        let mut node = view(&[10.0, 20.0]);
+        let layout_tree = layout_tree(&mut node);
+
+        assert!(is_tree_in_sync(&layout_tree, &view(&[10.0, 20.0])));
+        // only the shape of the tree is compared, not the styles
+        assert!(is_tree_in_sync(&layout_tree, &view(&[30.0, 40.0])));
+        assert!(!is_tree_in_sync(&layout_tree, &view(&[10.0])));
+        assert!(!is_tree_in_sync(&layout_tree, &view(&[10.0, 20.0, 30.0])));
+    }
+
+    /// a button placed in the cell of a grid
+    fn cell(
+        row: usize,
+        column: usize,
+        rows: usize,
+        columns: usize,
+    ) -> Node<()> {
+        let attrs: Vec<Attribute<()>> = vec![
+            grid_row(row),
+            grid_column(column),
+            row_span(rows),
+            column_span(columns),
+        ];
+        widget(Widget::Button, attrs, vec![])
+    }
+
+    #[test]
+    fn grid_tracks_fill_the_available_space() {
+        let tracks = compute_grid_tracks(
+            &[
+                GridTrack::Points(100.0),
+                GridTrack::Fraction(1.0),
+                GridTrack::Fraction(3.0),
+                GridTrack::Percent(0.1),
+            ],
+            600.0,
+        );
+        // the fractions share the 440 left by the 100 points and the 10% of 600
+        assert_eq!(
+            tracks,
+            vec![(0.0, 100.0), (100.0, 110.0), (210.0, 330.0), (540.0, 60.0)]
+        );
+        // the fixed tracks which overflow leave nothing to the fractions
+        assert_eq!(
+            compute_grid_tracks(
+                &[GridTrack::Points(500.0), GridTrack::Fraction(1.0)],
+                400.0
+            ),
+            vec![(0.0, 500.0), (500.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn spanned_tracks_are_added_together() {
+        let tracks = [(0.0, 100.0), (100.0, 110.0), (210.0, 330.0)];
+        assert_eq!(span_tracks(&tracks, 0, 1), (0.0, 100.0));
+        assert_eq!(span_tracks(&tracks, 1, 2), (100.0, 440.0));
+        // the span is cut at the last track
+        assert_eq!(span_tracks(&tracks, 2, 3), (210.0, 330.0));
+        assert_eq!(span_tracks(&tracks, 5, 1), (0.0, 0.0));
+    }
+
+    #[test]
+    fn missing_grid_tracks_are_fractions() {
+        let attrs: Vec<Attribute<()>> =
+            vec![grid_rows(vec![GridTrack::Points(50.0)])];
+        assert_eq!(
+            grid_template(AttribKey::GridRows, &attrs, 3),
+            vec![
+                GridTrack::Points(50.0),
+                GridTrack::Fraction(1.0),
+                GridTrack::Fraction(1.0),
+            ]
+        );
+        // the specified tracks are kept even when there are fewer children
+        assert_eq!(
+            grid_template(AttribKey::GridRows, &attrs, 0),
+            vec![GridTrack::Points(50.0)]
+        );
+        assert_eq!(
+            grid_template(AttribKey::GridColumns, &attrs, 2),
+            vec![GridTrack::Fraction(1.0), GridTrack::Fraction(1.0)]
+        );
+    }
+
+    #[test]
+    fn grid_children_are_placed_in_their_cells() {
+        let mut node = widget(
+            Widget::Grid,
+            vec![
+                style(sized(400.0, 300.0)),
+                grid_columns(vec![
+                    GridTrack::Points(100.0),
+                    GridTrack::Fraction(1.0),
+                ]),
+                grid_rows(vec![
+                    GridTrack::Fraction(1.0),
+                    GridTrack::Fraction(2.0),
+                ]),
+            ],
+            vec![cell(0, 0, 1, 1), cell(0, 1, 1, 1), cell(1, 0, 1, 2)],
+        );
+        compute_node_layout(&mut node, WINDOW);
+        assert_eq!(
+            rects(&node),
+            vec![
+                (0.0, 0.0, 400.0, 300.0),
+                (0.0, 0.0, 100.0, 100.0),
+                (100.0, 0.0, 300.0, 100.0),
+                // spanning both of the columns
+                (0.0, 100.0, 400.0, 200.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_grids_are_placed_in_more_than_one_pass() {
+        // the inner grid only has a size once the outer grid has placed it,
+        // so its children are placed in the passes which follow
+        let inner = widget(
+            Widget::Grid,
+            vec![
+                grid_row(0usize),
+                grid_column(1usize),
+                grid_columns(vec![
+                    GridTrack::Fraction(1.0),
+                    GridTrack::Fraction(3.0),
+                ]),
+            ],
+            vec![cell(0, 0, 1, 1), cell(0, 1, 1, 1)],
+        );
+        let mut node = widget(
+            Widget::Grid,
+            vec![style(sized(400.0, 300.0))],
+            vec![cell(0, 0, 1, 1), inner],
+        );
+        compute_node_layout(&mut node, WINDOW);
+        assert_eq!(
+            rects(&node),
+            vec![
+                (0.0, 0.0, 400.0, 300.0),
+                (0.0, 0.0, 200.0, 300.0),
+                (200.0, 0.0, 200.0, 300.0),
+                (0.0, 0.0, 50.0, 300.0),
+                (50.0, 0.0, 150.0, 300.0),
+            ]
+        );
+    }
+}
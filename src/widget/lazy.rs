@@ -0,0 +1,157 @@
+//! Memoized view subtrees
+//!
+//! `lazy` keeps the subtree built by the view function together with its
+//! dependencies, and reuses it on the next view when the dependencies compare equal,
+//! so the mostly static parts of a large view are not rebuilt on every dispatch.
+//!
+//! Only the building of the subtree is skipped, the diffing is not: the reused subtree
+//! is cloned into the view and is diffed against the previous view like the rest of it.
+//! The layouts are written into the nodes after the view is built, so a reused subtree
+//! can still have patches when the widgets around it move, and mt_dom has no way to
+//! mark a subtree as skipped in the diff.
+//! The subtrees which were not used in the last view are removed from the cache.
+use crate::Node;
+use std::{any::Any, cell::RefCell, collections::HashMap, mem};
+
+thread_local! {
+    static LAZY_CACHE: RefCell<HashMap<String, Entry>> =
+        RefCell::new(HashMap::new());
+}
+
+struct Entry {
+    memo: Box<dyn Any>,
+    /// whether the subtree is used in the view being built
+    is_used: bool,
+}
+
+struct Memo<DEPS, MSG> {
+    deps: DEPS,
+    node: Node<MSG>,
+}
+
+/// build the subtree with `view`, or reuse the subtree built with the same `key`
+/// when its `deps` are equal to the previous ones
+///
+/// ```rust,ignore
+/// lazy("todo_list", (self.entries.clone(), self.visibility.clone()), || {
+///     self.view_entries()
+/// })
+/// ```
+pub fn lazy<DEPS, MSG, F>(key: &str, deps: DEPS, view: F) -> Node<MSG>
+where
+    DEPS: PartialEq + 'static,
+    MSG: Clone + 'static,
+    F: FnOnce() -> Node<MSG>,
+{
+    let cached = LAZY_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.get_mut(key)?;
+        let node = entry
+            .memo
+            .downcast_ref::<Memo<DEPS, MSG>>()
+            .filter(|memo| memo.deps == deps)
+            .map(|memo| memo.node.clone())?;
+        entry.is_used = true;
+        Some(node)
+    });
+    if let Some(node) = cached {
+        return node;
+    }
+    // the cache is not borrowed while building the view,
+    // since the view can have lazy subtrees of its own
+    let node = view();
+    LAZY_CACHE.with(|cache| {
+        cache.borrow_mut().insert(
+            key.to_string(),
+            Entry {
+                memo: Box::new(Memo {
+                    deps,
+                    node: node.clone(),
+                }),
+                is_used: true,
+            },
+        );
+    });
+    node
+}
+
+/// remove all the memoized subtrees, such as when the app is replaced
+pub fn clear_lazy_cache() {
+    LAZY_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+/// remove the memoized subtrees which are not used since the last call,
+/// the backends call this after every view
+pub(crate) fn evict_unused_lazy() {
+    LAZY_CACHE.with(|cache| {
+        cache
+            .borrow_mut()
+            .retain(|_key, entry| mem::replace(&mut entry.is_used, false))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        widget::{
+            attribute::{find_value, label},
+            text_label,
+        },
+        AttribKey, Value,
+    };
+    use std::cell::Cell;
+
+    /// the label built for `deps`, counting the builds in `builds`
+    fn counted(deps: u32, builds: &Cell<u32>) -> Node<()> {
+        lazy("counted", deps, || {
+            builds.set(builds.get() + 1);
+            text_label(vec![label(format!("built with {}", deps))])
+        })
+    }
+
+    fn label_of(node: &Node<()>) -> Option<&Value> {
+        find_value(AttribKey::Label, node.get_attributes()?)
+    }
+
+    #[test]
+    fn same_deps_reuse_the_subtree() {
+        clear_lazy_cache();
+        let builds = Cell::new(0);
+        let first = counted(1, &builds);
+        evict_unused_lazy();
+        let second = counted(1, &builds);
+        assert_eq!(builds.get(), 1);
+        assert_eq!(label_of(&first), label_of(&second));
+    }
+
+    #[test]
+    fn changed_deps_rebuild_the_subtree() {
+        clear_lazy_cache();
+        let builds = Cell::new(0);
+        counted(1, &builds);
+        evict_unused_lazy();
+        let changed = counted(2, &builds);
+        assert_eq!(builds.get(), 2);
+        assert_eq!(
+            label_of(&changed),
+            Some(&Value::from("built with 2".to_string()))
+        );
+        // the rebuilt subtree replaces the previous one in the cache
+        evict_unused_lazy();
+        counted(2, &builds);
+        assert_eq!(builds.get(), 2);
+    }
+
+    #[test]
+    fn unused_subtrees_are_evicted() {
+        clear_lazy_cache();
+        let builds = Cell::new(0);
+        counted(1, &builds);
+        evict_unused_lazy();
+        // a view which did not use the subtree
+        evict_unused_lazy();
+        counted(1, &builds);
+        assert_eq!(builds.get(), 2);
+    }
+}
@@ -0,0 +1,117 @@
+//! Reusable widgets which keep their own UI state
+//!
+//! A `StatefulWidget` is built from the other widgets, and handles its own
+//! interactions such as hover, expand or drag in a local state.
+//! Only the high level events, such as a color being picked, are emitted to the app.
+//!
+//! ```rust,ignore
+//! struct Expander {
+//!     title: String,
+//! }
+//!
+//! enum ExpanderMsg {
+//!     Toggle,
+//! }
+//!
+//! impl StatefulWidget for Expander {
+//!     type State = bool;
+//!     type Msg = ExpanderMsg;
+//!     type Event = bool;
+//!
+//!     fn update(&self, expanded: &mut bool, msg: ExpanderMsg) -> Option<bool> {
+//!         match msg {
+//!             ExpanderMsg::Toggle => *expanded = !*expanded,
+//!         }
+//!         Some(*expanded)
+//!     }
+//!
+//!     fn view(&self, expanded: &bool) -> Node<ExpanderMsg> {
+//!         button(vec![label(&self.title), on_click(|_| ExpanderMsg::Toggle)])
+//!     }
+//! }
+//!
+//! // in the app
+//! self.expander.view(Msg::Expanded, Msg::Redraw)
+//! ```
+use crate::{MapMsg, Node};
+use std::{
+    cell::{Ref, RefCell},
+    rc::Rc,
+};
+
+/// A custom widget with a local state
+pub trait StatefulWidget {
+    /// the local UI state of the widget
+    type State: Default + 'static;
+    /// the msg of the interactions inside the widget
+    type Msg: 'static;
+    /// the high level event emitted to the parent
+    type Event: 'static;
+
+    /// update the local state, and return the event to be emitted to the parent, if any
+    fn update(
+        &self,
+        state: &mut Self::State,
+        msg: Self::Msg,
+    ) -> Option<Self::Event>;
+
+    /// the view of the widget for the current state
+    fn view(&self, state: &Self::State) -> Node<Self::Msg>;
+}
+
+/// Holds a stateful widget and its local state, the app keeps it across views
+pub struct Stateful<W>
+where
+    W: StatefulWidget,
+{
+    widget: Rc<W>,
+    state: Rc<RefCell<W::State>>,
+}
+
+impl<W> Stateful<W>
+where
+    W: StatefulWidget + 'static,
+{
+    /// create the widget with the default state
+    pub fn new(widget: W) -> Self {
+        Stateful {
+            widget: Rc::new(widget),
+            state: Rc::new(RefCell::new(W::State::default())),
+        }
+    }
+
+    /// the widget
+    pub fn widget(&self) -> &W {
+        &self.widget
+    }
+
+    /// replace the widget, such as when its properties are changed by the parent,
+    /// the local state is kept
+    pub fn set_widget(&mut self, widget: W) {
+        self.widget = Rc::new(widget);
+    }
+
+    /// the current local state
+    pub fn state(&self) -> Ref<W::State> {
+        self.state.borrow()
+    }
+
+    /// the view of the widget, `on_event` converts the emitted events into the app msg,
+    /// and `redraw` is dispatched when only the local state has changed,
+    /// so the widget is rendered again, the app can ignore it in its update
+    pub fn view<F, MSG>(&self, on_event: F, redraw: MSG) -> Node<MSG>
+    where
+        F: Fn(W::Event) -> MSG + 'static,
+        MSG: Clone + 'static,
+    {
+        let widget = Rc::clone(&self.widget);
+        let state = Rc::clone(&self.state);
+        let view = self.widget.view(&self.state.borrow());
+        MapMsg::map_msg(view, move |msg| {
+            match widget.update(&mut state.borrow_mut(), msg) {
+                Some(event) => on_event(event),
+                None => redraw.clone(),
+            }
+        })
+    }
+}
@@ -0,0 +1,76 @@
+use sauron_native::{
+    stretch::{
+        geometry::Size,
+        number::Number,
+        style::{Dimension, FlexDirection, PositionType},
+    },
+    widget::{
+        attribute::{util::get_layout, *},
+        layout::compute_node_layout,
+        *,
+    },
+    Node,
+};
+
+const WINDOW: Size<Number> = Size {
+    width: Number::Defined(800.0),
+    height: Number::Defined(600.0),
+};
+
+/// the location and size of the child at `index`
+fn child_rect(node: &Node<()>, index: usize) -> (f32, f32, f32, f32) {
+    let child = &node.get_children().expect("must have children")[index];
+    let layout = child
+        .as_element_ref()
+        .and_then(get_layout)
+        .expect("must have a layout");
+    (
+        layout.location.x,
+        layout.location.y,
+        layout.size.width,
+        layout.size.height,
+    )
+}
+
+#[test]
+fn attributes_override_the_builder_defaults() {
+    let node: Node<()> = column(vec![width(200.0), flex_grow(2.0)], vec![]);
+    let style = get_style(&node).expect("must have a style");
+    assert_eq!(style.size.width, Dimension::Points(200.0));
+    assert_eq!(style.flex_grow, 2.0);
+    // the defaults which are not overridden are kept
+    assert_eq!(style.size.height, Dimension::Percent(1.0));
+    assert_eq!(style.flex_direction, FlexDirection::Column);
+}
+
+#[test]
+fn percent_sizes_resolve_from_the_parent() {
+    let mut node: Node<()> = column(
+        vec![width(400.0), height(300.0)],
+        vec![text_label(vec![
+            width(percent(50.0)),
+            height(percent(25.0)),
+        ])],
+    );
+    compute_node_layout(&mut node, WINDOW);
+    assert_eq!(child_rect(&node, 0), (0.0, 0.0, 200.0, 75.0));
+}
+
+#[test]
+fn overlay_children_are_laid_out_on_top_of_each_other() {
+    let mut node: Node<()> = overlay(
+        vec![width(400.0), height(300.0)],
+        vec![
+            text_label(vec![height(100.0)]),
+            text_label(vec![width(50.0), height(40.0)]),
+        ],
+    );
+    for child in node.get_children().expect("must have children") {
+        let style = get_style(child).expect("must have a style");
+        assert_eq!(style.position_type, PositionType::Absolute);
+    }
+    compute_node_layout(&mut node, WINDOW);
+    // the children are not stacked, and keep the sizes from their attributes
+    assert_eq!(child_rect(&node, 0), (0.0, 0.0, 400.0, 100.0));
+    assert_eq!(child_rect(&node, 1), (0.0, 0.0, 50.0, 40.0));
+}
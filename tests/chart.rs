@@ -0,0 +1,113 @@
+use sauron_native::widget::{
+    canvas::to_svg,
+    chart::{Axis, Chart, ChartKind, ChartPoint, Series},
+};
+
+fn chart(kind: ChartKind) -> Chart {
+    Chart {
+        kind,
+        series: vec![
+            Series::new("cpu", vec![(0.0, 10.0), (1.0, 40.0), (2.0, 25.0)]),
+            Series::new("memory", vec![(0.0, 60.0), (1.0, 55.0), (2.0, 70.0)]),
+        ],
+        x_axis: Axis::default().label("time"),
+        y_axis: Axis::default().label("%").range(0.0, 100.0),
+    }
+}
+
+#[test]
+fn charts_are_drawn_into_svg() {
+    for kind in [ChartKind::Line, ChartKind::Bar, ChartKind::Scatter].iter() {
+        let (width, height) = (400.0, 200.0);
+        let commands = chart(*kind).draw(width, height);
+        assert!(!commands.is_empty(), "{:?} chart has no commands", kind);
+        let svg = to_svg(&commands, width, height);
+        assert!(svg.starts_with("<svg"), "{}", svg);
+        assert!(svg.ends_with("</svg>"), "{}", svg);
+        assert!(
+            svg.contains("<path"),
+            "{:?} chart has no path: {}",
+            kind,
+            svg
+        );
+        assert!(!svg.contains("NaN"), "{:?} chart has NaN: {}", kind, svg);
+    }
+}
+
+#[test]
+fn empty_chart_is_drawn_without_nan() {
+    let empty = Chart {
+        kind: ChartKind::Line,
+        series: vec![],
+        x_axis: Axis::default(),
+        y_axis: Axis::default(),
+    };
+    let svg = to_svg(&empty.draw(400.0, 200.0), 400.0, 200.0);
+    assert!(!svg.contains("NaN"), "{}", svg);
+}
+
+#[test]
+fn nearest_point_is_hovered() {
+    // in a 400x200 canvas, the plot area spans from (40, 20) to (390, 170)
+    let size = (400.0, 200.0);
+    for kind in [ChartKind::Line, ChartKind::Scatter].iter() {
+        let chart = chart(*kind);
+        assert_eq!(
+            chart.nearest((215.0, 112.0), size),
+            Some(ChartPoint {
+                series: 0,
+                index: 1,
+                x: 1.0,
+                y: 40.0,
+            }),
+            "{:?}",
+            kind
+        );
+        assert_eq!(
+            chart.nearest((385.0, 70.0), size),
+            Some(ChartPoint {
+                series: 1,
+                index: 2,
+                x: 2.0,
+                y: 70.0,
+            }),
+            "{:?}",
+            kind
+        );
+    }
+}
+
+#[test]
+fn bar_under_the_pointer_is_hovered() {
+    let chart = chart(ChartKind::Bar);
+    // the second group spans from 156.7 to 273.3, with the memory bar on its right half
+    assert_eq!(
+        chart.nearest((240.0, 190.0), (400.0, 200.0)),
+        Some(ChartPoint {
+            series: 1,
+            index: 1,
+            x: 1.0,
+            y: 55.0,
+        })
+    );
+    assert_eq!(
+        chart.nearest((190.0, 30.0), (400.0, 200.0)),
+        Some(ChartPoint {
+            series: 0,
+            index: 1,
+            x: 1.0,
+            y: 40.0,
+        })
+    );
+}
+
+#[test]
+fn empty_chart_has_no_nearest_point() {
+    let empty = Chart {
+        kind: ChartKind::Scatter,
+        series: vec![],
+        x_axis: Axis::default(),
+        y_axis: Axis::default(),
+    };
+    assert_eq!(empty.nearest((100.0, 100.0), (400.0, 200.0)), None);
+}
@@ -0,0 +1,91 @@
+use sauron_native::{
+    mt_dom,
+    widget::{attribute::*, event::*, *},
+    AttribKey, Child, Component, MapMsg, Node,
+};
+
+/// a child component whose view has a text node before its button
+struct Counter {
+    count: i32,
+}
+
+#[derive(Debug, PartialEq)]
+enum CounterMsg {
+    Increment,
+}
+
+impl Component<CounterMsg> for Counter {
+    fn update(&mut self, msg: CounterMsg) {
+        match msg {
+            CounterMsg::Increment => self.count += 1,
+        }
+    }
+
+    fn view(&self) -> Node<CounterMsg> {
+        column(
+            vec![],
+            vec![
+                mt_dom::text(format!("count: {}", self.count)),
+                button(vec![label("+"), on_click(|_| CounterMsg::Increment)]),
+            ],
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Counter(CounterMsg),
+}
+
+struct App {
+    counter: Child<Counter, CounterMsg>,
+}
+
+impl Component<Msg> for App {
+    fn update(&mut self, msg: Msg) {
+        match msg {
+            Msg::Counter(msg) => self.counter.update(msg),
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        row(vec![], vec![self.counter.view(Msg::Counter)])
+    }
+}
+
+/// click the button of the counter in the view of the app
+fn click_counter(view: &Node<Msg>) -> Msg {
+    let counter = &view.get_children().expect("must have children")[0];
+    let children = counter.get_children().expect("must have children");
+    assert!(
+        children[0].get_attributes().is_none(),
+        "must be a text node"
+    );
+    let attrs = children[1].get_attributes().expect("must be an element");
+    let callbacks = find_callback(AttribKey::ClickEvent, attrs)
+        .expect("must have a click callback");
+    callbacks[0].emit(MouseEvent::click(1, 1))
+}
+
+#[test]
+fn child_view_emits_the_parent_msg() {
+    let mut app = App {
+        counter: Child::new(Counter { count: 0 }),
+    };
+    let msg = click_counter(&app.view());
+    assert_eq!(msg, Msg::Counter(CounterMsg::Increment));
+    app.update(msg);
+    assert_eq!(app.counter.component().count, 1);
+}
+
+#[test]
+fn text_nodes_are_kept_when_mapping_the_msg() {
+    let counter = Counter { count: 3 };
+    let view = counter.view().map_msg(Msg::Counter);
+    let children = view.get_children().expect("must have children");
+    assert_eq!(children.len(), 2);
+    match &children[0] {
+        Node::Text(txt) => assert_eq!(txt.to_string(), "count: 3"),
+        Node::Element(_) => panic!("must be a text node"),
+    }
+}
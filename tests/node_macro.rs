@@ -0,0 +1,113 @@
+#![cfg(feature = "with-node-macro")]
+use sauron_native::{
+    node,
+    snapshot::render_node,
+    widget::{
+        attribute::*,
+        chart::{Axis, ChartPoint, Series},
+        event::*,
+        *,
+    },
+    AttribKey, Event, Node,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Increment,
+    Hovered(ChartPoint),
+}
+
+fn samples() -> Vec<Series> {
+    vec![Series::new(
+        "cpu",
+        vec![(0.0, 10.0), (1.0, 40.0), (2.0, 25.0)],
+    )]
+}
+
+fn macro_view(count: i32) -> Node<Msg> {
+    node! {
+        <column width={400.0}>
+            <text_label value={format!("count: {}", count)} />
+            <row key="buttons">
+                <button label="+" on_click={|_| Msg::Increment} />
+                <checkbox label="enabled" checked=true />
+            </row>
+            <line_chart
+                series={samples()}
+                y_axis={Axis::default().range(0.0, 100.0)}
+                on_chart_hover={Msg::Hovered} />
+            {..(0..2).map(|i| text_label(vec![value(i.to_string())]))}
+        </column>
+    }
+}
+
+fn builder_view(count: i32) -> Node<Msg> {
+    let mut children = vec![
+        text_label(vec![value(format!("count: {}", count))]),
+        row(
+            vec![key("buttons")],
+            vec![
+                button(vec![label("+"), on_click(|_| Msg::Increment)]),
+                checkbox(vec![label("enabled"), checked(true)]),
+            ],
+        ),
+        line_chart(
+            vec![on_chart_hover(Msg::Hovered)],
+            samples(),
+            Axis::default(),
+            Axis::default().range(0.0, 100.0),
+        ),
+    ];
+    children.extend((0..2).map(|i| text_label(vec![value(i.to_string())])));
+    column(vec![width(400.0)], children)
+}
+
+/// emit the event into the first callback of `key` of the child at `path`
+fn emit(
+    view: &Node<Msg>,
+    path: &[usize],
+    key: AttribKey,
+    event: impl Into<Event>,
+) -> Msg {
+    let node = path.iter().fold(view, |node, index| {
+        &node.get_children().expect("must have children")[*index]
+    });
+    let attrs = node.get_attributes().expect("must be an element");
+    let callbacks = find_callback(key, attrs).expect("must have the callback");
+    callbacks[0].emit(event.into())
+}
+
+#[test]
+fn node_macro_expands_into_the_builders() {
+    assert_eq!(render_node(&macro_view(3)), render_node(&builder_view(3)));
+}
+
+#[test]
+fn node_macro_events_emit_the_same_msg() {
+    let (expanded, built) = (macro_view(0), builder_view(0));
+    let click = || MouseEvent::click(1, 1);
+    assert_eq!(
+        emit(&expanded, &[1, 0], AttribKey::ClickEvent, click()),
+        Msg::Increment
+    );
+    assert_eq!(
+        emit(&built, &[1, 0], AttribKey::ClickEvent, click()),
+        Msg::Increment
+    );
+
+    let point = ChartPoint {
+        series: 0,
+        index: 1,
+        x: 1.0,
+        y: 40.0,
+    };
+    let hover = || Event::ChartHover(point);
+    assert_eq!(
+        emit(&expanded, &[2], AttribKey::ChartHoverEvent, hover()),
+        emit(&built, &[2], AttribKey::ChartHoverEvent, hover())
+    );
+    assert_eq!(
+        emit(&expanded, &[2], AttribKey::ChartHoverEvent, hover()),
+        Msg::Hovered(point)
+    );
+}
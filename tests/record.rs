@@ -0,0 +1,172 @@
+#![cfg(feature = "with-record")]
+use sauron_native::{
+    mt_dom,
+    record::{Recording, Replayer},
+    serialize::{find_event_callbacks, EventId},
+    widget::{attribute::*, event::*, *},
+    AttribKey, Component, Event, Node, Widget,
+};
+
+#[derive(Debug, PartialEq)]
+struct Counter {
+    count: i32,
+    text: String,
+}
+
+#[derive(Debug)]
+enum Msg {
+    Increment,
+    Decrement,
+    ChangeText(String),
+}
+
+impl Component<Msg> for Counter {
+    fn update(&mut self, msg: Msg) {
+        match msg {
+            Msg::Increment => self.count += 1,
+            Msg::Decrement => self.count -= 1,
+            Msg::ChangeText(text) => self.text = text,
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        column(
+            vec![],
+            vec![
+                button(vec![label("+"), on_click(|_| Msg::Increment)]),
+                button(vec![label("-"), on_click(|_| Msg::Decrement)]),
+                text_input(vec![
+                    value(self.text.clone()),
+                    on_input(|input| Msg::ChangeText(input.value.to_string())),
+                ]),
+            ],
+        )
+    }
+}
+
+fn new_counter() -> Counter {
+    Counter {
+        count: 0,
+        text: String::new(),
+    }
+}
+
+/// emit the event into the callbacks of the node at `node_idx`, the way a backend would
+fn user_event<APP>(app: &mut APP, node_idx: usize, key: AttribKey, event: Event)
+where
+    APP: Component<Msg>,
+{
+    let view = app.view();
+    let event_id = EventId {
+        node_idx,
+        event: key,
+    };
+    let msgs: Vec<Msg> = find_event_callbacks(&view, &event_id)
+        .into_iter()
+        .map(|cb| cb.emit(event.clone()))
+        .collect();
+    assert!(!msgs.is_empty(), "no callback on node {}", node_idx);
+    for msg in msgs {
+        app.update(msg);
+    }
+}
+
+#[test]
+fn replaying_a_recorded_session_reaches_the_same_state() {
+    let path = std::env::temp_dir().join(format!(
+        "sauron-native-session-{}.jsonl",
+        std::process::id()
+    ));
+
+    let mut recording = Recording::new(new_counter(), &path).unwrap();
+    user_event(
+        &mut recording,
+        1,
+        AttribKey::ClickEvent,
+        MouseEvent::click(1, 1).into(),
+    );
+    user_event(
+        &mut recording,
+        1,
+        AttribKey::ClickEvent,
+        MouseEvent::click(1, 1).into(),
+    );
+    user_event(
+        &mut recording,
+        2,
+        AttribKey::ClickEvent,
+        MouseEvent::click(2, 1).into(),
+    );
+    user_event(
+        &mut recording,
+        3,
+        AttribKey::InputEvent,
+        InputEvent::new("hello").into(),
+    );
+    assert_eq!(recording.app().count, 1);
+
+    let replayer = Replayer::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(replayer.events().len(), 4);
+    assert_eq!(replayer.events()[3].msg, "ChangeText(\"hello\")");
+
+    let mut replayed = new_counter();
+    replayer.replay(&mut replayed).unwrap();
+    assert_eq!(&replayed, recording.app());
+}
+
+/// a counter whose view has a text node before its button
+#[derive(Debug, PartialEq)]
+struct TextCounter {
+    count: i32,
+}
+
+impl Component<Msg> for TextCounter {
+    fn update(&mut self, msg: Msg) {
+        if let Msg::Increment = msg {
+            self.count += 1;
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        column(
+            vec![],
+            vec![
+                mt_dom::text(format!("count: {}", self.count)),
+                button(vec![label("+"), on_click(|_| Msg::Increment)]),
+            ],
+        )
+    }
+}
+
+#[test]
+fn text_nodes_are_counted_in_the_recorded_node_index() {
+    let path = std::env::temp_dir().join(format!(
+        "sauron-native-text-session-{}.jsonl",
+        std::process::id()
+    ));
+
+    let mut recording =
+        Recording::new(TextCounter { count: 0 }, &path).unwrap();
+    // the text node is at index 1, so the button is at index 2
+    user_event(
+        &mut recording,
+        2,
+        AttribKey::ClickEvent,
+        MouseEvent::click(1, 1).into(),
+    );
+    assert_eq!(recording.app().count, 1);
+
+    let replayer = Replayer::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(replayer.events().len(), 1);
+    assert_eq!(replayer.events()[0].event_id.node_idx, 2);
+    assert_eq!(
+        replayer.events()[0].path,
+        vec![Widget::Vbox, Widget::Button]
+    );
+
+    let mut replayed = TextCounter { count: 0 };
+    replayer.replay(&mut replayed).unwrap();
+    assert_eq!(&replayed, recording.app());
+}
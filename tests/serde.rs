@@ -0,0 +1,120 @@
+#![cfg(feature = "serde")]
+use sauron_native::{
+    mt_dom,
+    serialize::SerializedNode,
+    stretch::{
+        geometry::{Rect, Size},
+        style::{
+            AlignItems, Dimension, FlexDirection, FlexWrap, JustifyContent,
+            PositionType, Style,
+        },
+    },
+    widget::{
+        attribute::{percent, value, width, DropEffect},
+        button, column,
+        event::{
+            on_click, DragPayload, DropEvent, InputEvent, KeyCode, KeyEvent,
+            Modifier, MouseEvent,
+        },
+        text_input,
+    },
+    Callback, Event, Node, Value,
+};
+
+fn round_trip(event: Event) {
+    let json = serde_json::to_string(&event).expect("must serialize");
+    let back: Event = serde_json::from_str(&json).expect("must deserialize");
+    assert_eq!(back, event, "{}", json);
+}
+
+#[test]
+fn mouse_events_round_trip() {
+    round_trip(MouseEvent::click(3, 4).into());
+    round_trip(MouseEvent::pressed(1, 2).into());
+    round_trip(MouseEvent::release(1, 2).into());
+    round_trip(MouseEvent::mousemove(5, 6).into());
+    round_trip(MouseEvent::default().into());
+}
+
+#[test]
+fn unknown_mouse_event_type_is_an_error() {
+    let json = serde_json::to_string(&Event::from(MouseEvent::click(0, 0)))
+        .unwrap()
+        .replace("\"click\"", "\"wheel\"");
+    assert!(serde_json::from_str::<Event>(&json).is_err());
+}
+
+#[test]
+fn other_events_round_trip() {
+    round_trip(
+        KeyEvent {
+            key_code: KeyCode::Char('a'),
+            modifier: Modifier::ctrl(),
+        }
+        .into(),
+    );
+    round_trip(InputEvent::new("hello").into());
+    round_trip(Event::Resize(640.0, 480.0));
+    round_trip(Event::AnimationEnd);
+    round_trip(Event::Drop(DropEvent {
+        payload: DragPayload::Text("dropped".to_string()),
+        x: 10,
+        y: 20,
+        effect: DropEffect::Copy,
+    }));
+    round_trip(Event::DragEnd(DropEffect::Move));
+}
+
+fn round_trip_value(value: Value) {
+    let json = serde_json::to_string(&value).expect("must serialize");
+    let back: Value = serde_json::from_str(&json).expect("must deserialize");
+    assert_eq!(back, value, "{}", json);
+}
+
+#[test]
+fn stretch_values_round_trip() {
+    round_trip_value(Value::Style(Style {
+        flex_direction: FlexDirection::Column,
+        flex_wrap: FlexWrap::Wrap,
+        align_items: AlignItems::Center,
+        justify_content: JustifyContent::SpaceBetween,
+        position_type: PositionType::Absolute,
+        margin: Rect {
+            start: Dimension::Points(4.0),
+            end: Dimension::Auto,
+            top: Dimension::Percent(0.5),
+            bottom: Dimension::Undefined,
+        },
+        size: Size {
+            width: Dimension::Percent(1.0),
+            height: Dimension::Points(120.0),
+        },
+        flex_grow: 2.0,
+        ..Default::default()
+    }));
+    round_trip_value(Value::Dimension(Dimension::Percent(0.25)));
+    round_trip_value(Value::PositionType(PositionType::Relative));
+    round_trip_value(Value::JustifyContent(JustifyContent::SpaceEvenly));
+}
+
+#[test]
+fn node_tree_round_trips() {
+    let view: Node<()> = column(
+        vec![],
+        vec![
+            mt_dom::text("hello"),
+            button(vec![on_click(|_| ())]),
+            text_input(vec![value("text".to_string()), width(percent(50.0))]),
+        ],
+    );
+    let serialized = SerializedNode::from_node(&view);
+    let json = serde_json::to_string(&serialized).expect("must serialize");
+    let back: SerializedNode =
+        serde_json::from_str(&json).expect("must deserialize");
+    assert_eq!(back, serialized, "{}", json);
+
+    // the callbacks are bound back to the same events
+    let node: Node<()> =
+        back.into_node(|_event_id| Some(Callback::from(|_: Event| ())));
+    assert_eq!(SerializedNode::from_node(&node), serialized);
+}
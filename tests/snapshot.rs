@@ -0,0 +1,118 @@
+use sauron_native::{
+    mt_dom,
+    snapshot::{
+        assert_view_snapshot, compare_snapshot, render_node, render_view,
+    },
+    widget::{attribute::*, event::*, *},
+    Component, Node,
+};
+use std::{fs, path::PathBuf};
+
+struct Counter {
+    count: i32,
+}
+
+enum Msg {
+    Increment,
+    Decrement,
+}
+
+impl Component<Msg> for Counter {
+    fn update(&mut self, msg: Msg) {
+        match msg {
+            Msg::Increment => self.count += 1,
+            Msg::Decrement => self.count -= 1,
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        column(
+            vec![],
+            vec![
+                text_label(vec![value(format!("count: {}", self.count))]),
+                row(
+                    vec![],
+                    vec![
+                        button(vec![label("+"), on_click(|_| Msg::Increment)]),
+                        button(vec![label("-"), on_click(|_| Msg::Decrement)]),
+                    ],
+                ),
+            ],
+        )
+    }
+}
+
+#[test]
+fn counter_matches_the_snapshot() {
+    assert_view_snapshot("counter", &Counter { count: 0 }, 400.0, 300.0);
+}
+
+/// a snapshot path of its own for each test, outside of `tests/snapshots`,
+/// so the failing tests never touch the stored snapshots
+fn temp_snapshot(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "sauron_native_{}_{}.snap",
+        name,
+        std::process::id()
+    ))
+}
+
+#[test]
+#[should_panic(expected = "does not match")]
+fn changed_view_fails_the_snapshot() {
+    let path = temp_snapshot("changed_view");
+    fs::write(&path, render_view(&Counter { count: 0 }, 400.0, 300.0))
+        .expect("must write the snapshot");
+    let mut counter = Counter { count: 0 };
+    counter.update(Msg::Increment);
+    compare_snapshot(&path, &render_view(&counter, 400.0, 300.0));
+}
+
+#[test]
+#[should_panic(expected = "is missing")]
+fn missing_snapshot_fails() {
+    let path = temp_snapshot("missing");
+    let _ = fs::remove_file(&path);
+    compare_snapshot(path, "Vbox\n");
+}
+
+#[test]
+fn text_nodes_are_rendered() {
+    let view: Node<()> = column(
+        vec![],
+        vec![mt_dom::text("count: 0"), text_label(vec![value("total")])],
+    );
+    assert_eq!(
+        render_node(&view),
+        "Vbox\n    \"count: 0\"\n    Label\n      - Value: \"total\"\n"
+    );
+}
+
+#[cfg(feature = "with-html")]
+#[test]
+fn overlay_children_keep_their_size_in_html() {
+    struct Layers;
+
+    impl Component<()> for Layers {
+        fn update(&mut self, _msg: ()) {}
+
+        fn view(&self) -> Node<()> {
+            overlay(
+                vec![width(300.0), height(200.0)],
+                vec![
+                    text_label(vec![value("back"), width(120.0), height(35.0)]),
+                    text_label(vec![
+                        value("front"),
+                        width(120.0),
+                        height(35.0),
+                    ]),
+                ],
+            )
+        }
+    }
+
+    let html = sauron_native::snapshot::render_html_view(&Layers, 300.0, 200.0);
+    // each child keeps its own size, rather than the width of all the children
+    assert!(html.contains("35px"), "{}", html);
+    assert!(!html.contains("240px"), "{}", html);
+}